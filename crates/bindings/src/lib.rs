@@ -1,26 +1,36 @@
 use alloy::sol;
 
 sol!(
-    #![sol(all_derives = true)]
+    #![sol(all_derives = true, abi = true)]
     #[derive(serde::Serialize, serde::Deserialize)]
     IOrderBookV4, "../../out/IOrderBookV4.sol/IOrderBookV4.json"
 );
 
 sol!(
-    #![sol(all_derives = true)]
+    #![sol(all_derives = true, abi = true)]
     #[derive(serde::Serialize, serde::Deserialize)]
     OrderBook, "../../out/OrderBook.sol/OrderBook.json"
 );
 
 sol!(
-    #![sol(all_derives = true)]
+    #![sol(all_derives = true, abi = true)]
     IERC20, "../../out/IERC20.sol/IERC20.json"
 );
 
 sol!(
-    #![sol(all_derives = true)]
+    #![sol(all_derives = true, abi = true)]
     ERC20, "../../out/ERC20.sol/ERC20.json"
 );
 
+sol!(
+    #![sol(all_derives = true, abi = true)]
+    RainterpreterNPE2, "../../out/RainterpreterNPE2.sol/RainterpreterNPE2.json"
+);
+
+sol!(
+    #![sol(all_derives = true, abi = true)]
+    RainterpreterStoreNPE2, "../../out/RainterpreterStoreNPE2.sol/RainterpreterStoreNPE2.json"
+);
+
 #[cfg(target_family = "wasm")]
 pub mod js_api;
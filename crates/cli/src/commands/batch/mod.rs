@@ -0,0 +1,216 @@
+use crate::{
+    execute::Execute,
+    output::output_safe_batch,
+    status::{display_fee_report, display_write_simulations, display_write_transaction_status},
+    transaction::CliTransactionArgs,
+};
+use alloy::primitives::{Address, U256};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rain_orderbook_app_settings::{network::Network, Config};
+use rain_orderbook_bindings::IOrderBookV4::removeOrder2Call;
+use rain_orderbook_common::{
+    add_order::AddOrderArgs, batch::BatchArgs, deposit::DepositArgs,
+    frontmatter::parse_frontmatter, remove_order::RemoveOrderArgs, subgraph::SubgraphArgs,
+    transaction::TransactionArgs, withdraw::WithdrawArgs,
+};
+use serde::Deserialize;
+use std::fs::read_to_string;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct Batch {
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the batch plan, a CSV or YAML file of operations with the fields operation (deposit, withdraw, add-order or remove-order), token, vault_id, amount, order_id, dotrain_file and deployment"
+    )]
+    plan_file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Url of the hosted Subgraph for this Orderbook deployemnt, required to remove orders"
+    )]
+    subgraph_url: Option<String>,
+
+    #[clap(flatten)]
+    pub transaction_args: CliTransactionArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum BatchPlanOperation {
+    Deposit,
+    Withdraw,
+    AddOrder,
+    RemoveOrder,
+}
+
+/// A single operation of a batch plan file, fields that an operation does
+/// not use are left empty. The amount of a withdraw is its target amount
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct BatchPlanEntry {
+    operation: BatchPlanOperation,
+    token: Option<Address>,
+    vault_id: Option<U256>,
+    amount: Option<U256>,
+    order_id: Option<String>,
+    dotrain_file: Option<PathBuf>,
+    deployment: Option<String>,
+}
+
+fn read_plan(path: &Path) -> Result<Vec<BatchPlanEntry>> {
+    let extension = path
+        .extension()
+        .and_then(|v| v.to_str())
+        .map(|v| v.to_lowercase());
+    match extension.as_deref() {
+        Some("csv") => Ok(csv::Reader::from_path(path)?
+            .deserialize()
+            .collect::<Result<Vec<BatchPlanEntry>, _>>()?),
+        Some("yaml") | Some("yml") => Ok(serde_yaml::from_str(&read_to_string(path)?)?),
+        _ => Err(anyhow!("batch plan must be a .csv, .yaml or .yml file")),
+    }
+}
+
+impl Batch {
+    /// Builds the batch of the plan, along with the network of its first
+    /// add-order deployment if it has one
+    async fn to_batch_args(&self, rpc_url: &str) -> Result<(BatchArgs, Option<Network>)> {
+        let mut batch = BatchArgs::default();
+        let mut network = None;
+        for (i, entry) in read_plan(&self.plan_file)?.into_iter().enumerate() {
+            let missing =
+                |fields: &str| anyhow!("operation {} of the plan is missing {}", i + 1, fields);
+            match entry.operation {
+                BatchPlanOperation::Deposit => {
+                    let (Some(token), Some(vault_id), Some(amount)) =
+                        (entry.token, entry.vault_id, entry.amount)
+                    else {
+                        return Err(missing("token, vault_id or amount"));
+                    };
+                    batch.deposit(DepositArgs {
+                        token,
+                        vault_id,
+                        amount,
+                        tasks: vec![],
+                    });
+                }
+                BatchPlanOperation::Withdraw => {
+                    let (Some(token), Some(vault_id), Some(target_amount)) =
+                        (entry.token, entry.vault_id, entry.amount)
+                    else {
+                        return Err(missing("token, vault_id or amount"));
+                    };
+                    batch.withdraw(WithdrawArgs {
+                        token,
+                        vault_id,
+                        target_amount,
+                        tasks: vec![],
+                    });
+                }
+                BatchPlanOperation::AddOrder => {
+                    let (Some(dotrain_file), Some(deployment)) =
+                        (entry.dotrain_file, entry.deployment)
+                    else {
+                        return Err(missing("dotrain_file or deployment"));
+                    };
+                    let text = read_to_string(&dotrain_file).map_err(|e| anyhow!(e))?;
+                    let config: Config = parse_frontmatter(text.clone()).await?.try_into()?;
+                    let config_deployment = config
+                        .deployments
+                        .get(&deployment)
+                        .ok_or(anyhow!("specified deployment is undefined!"))?;
+                    network.get_or_insert_with(|| config_deployment.order.network.as_ref().clone());
+                    let add_order_args =
+                        AddOrderArgs::new_from_deployment(text, config_deployment.deref().clone())
+                            .await?;
+                    batch.add_order(add_order_args.try_into_call(rpc_url.to_string()).await?);
+                }
+                BatchPlanOperation::RemoveOrder => {
+                    let Some(order_id) = entry.order_id else {
+                        return Err(missing("order_id"));
+                    };
+                    let subgraph_args = SubgraphArgs {
+                        url: self
+                            .subgraph_url
+                            .clone()
+                            .ok_or(anyhow!("removing orders requires a subgraph url"))?,
+                    };
+                    let order = subgraph_args
+                        .to_subgraph_client()
+                        .await?
+                        .order_detail(order_id.into())
+                        .await?;
+                    let remove_order_call: removeOrder2Call =
+                        RemoveOrderArgs::from(order).try_into()?;
+                    batch.remove_order(remove_order_call);
+                }
+            }
+        }
+        Ok((batch, network))
+    }
+}
+
+impl Execute for Batch {
+    async fn execute(&self) -> Result<()> {
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
+        tx_args.try_fill_chain_id().await?;
+        let (batch, network) = self.to_batch_args(&tx_args.rpc_url).await?;
+
+        if let Some(path) = &self.transaction_args.safe_batch {
+            return output_safe_batch(path, &tx_args, None, "Batch", |safe_batch| {
+                safe_batch.add_batch(tx_args.orderbook_address, &batch)?;
+                Ok(())
+            });
+        }
+
+        let sender = self
+            .transaction_args
+            .try_into_sender_address(&tx_args)
+            .await?;
+
+        info!("----- Simulate Batch -----");
+        let simulations = batch.simulate(tx_args.clone(), sender).await?;
+        display_write_simulations(&simulations)?;
+        let network = self
+            .transaction_args
+            .resolve_network(tx_args.chain_id, network)
+            .await;
+        display_fee_report(
+            &mut tx_args,
+            &simulations,
+            network.as_ref(),
+            self.transaction_args.max_cost,
+        )
+        .await?;
+        if self.transaction_args.dry_run {
+            return Ok(());
+        }
+        let signer_client = self
+            .transaction_args
+            .try_into_signer_client(&tx_args, sender)
+            .await?;
+
+        info!("----- Approve ERC20 token spends -----");
+        batch
+            .execute_approvals(tx_args.clone(), &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
+            })
+            .await?;
+
+        info!("----- Execute Batch of {} calls -----", batch.calls.len());
+        let confirmed = batch
+            .execute(tx_args, &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
+            })
+            .await?;
+        for event in confirmed.events() {
+            info!("{:?}", event);
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,38 @@
 use crate::execute::Execute;
+use alloy::primitives::U256;
 use anyhow::{anyhow, Result};
 use clap::Args;
-use rain_orderbook_app_settings::{config_source::ConfigSource, Config};
+use parquet::{
+    basic::{ConvertedType, Repetition, Type as PhysicalType},
+    data_type::{ByteArray, ByteArrayType, DoubleType, FixedLenByteArray, FixedLenByteArrayType},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::types::Type,
+};
+use rain_orderbook_app_settings::{config_source::ConfigSource, fuzz::Decimal18, Config};
 use rain_orderbook_common::dotrain::RainDocument;
-use rain_orderbook_common::fuzz::FuzzRunner;
-use std::fs::read_to_string;
-use std::path::PathBuf;
+use rain_orderbook_common::fuzz::{FuzzResultFlat, FuzzRunner};
+use std::fs::{read_to_string, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::info;
 
+#[derive(clap::ValueEnum, Clone)]
+pub enum ChartOutputFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl ChartOutputFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ChartOutputFormat::Csv => "csv",
+            ChartOutputFormat::Json => "json",
+            ChartOutputFormat::Parquet => "parquet",
+        }
+    }
+}
+
 #[derive(Args, Clone)]
 pub struct Chart {
     #[arg(
@@ -16,6 +41,31 @@ pub struct Chart {
         help = "Path to the .rain file specifying the order"
     )]
     dotrain_file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Directory to write the runs of each scenario to as <scenario>.<format>, the chart data is logged if not specified"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "csv",
+        help = "Format of the written runs"
+    )]
+    format: ChartOutputFormat,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Columns to write by stack path, LHS name or fuzzed binding, defaults to all"
+    )]
+    columns: Vec<String>,
+
+    #[arg(long, help = "Write values as 18 decimals fixed point numbers")]
+    decimal: bool,
 }
 
 impl Execute for Chart {
@@ -24,10 +74,182 @@ impl Execute for Chart {
         let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap();
         let config_string = ConfigSource::try_from_string(frontmatter.to_string()).await?;
         let config: Config = config_string.try_into()?;
-        let fuzzer = FuzzRunner::new(&dotrain, config, None).await;
+        let fuzzer = FuzzRunner::new(&dotrain, config.clone(), None).await;
         let chart_data = fuzzer.make_chart_data().await?;
 
-        info!("{:#?}", chart_data);
+        let Some(output) = &self.output else {
+            info!("{:#?}", chart_data);
+            return Ok(());
+        };
+
+        std::fs::create_dir_all(output)?;
+        let mut scenarios = chart_data.scenarios_data.iter().collect::<Vec<_>>();
+        scenarios.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, result) in scenarios {
+            let selected;
+            let result = if self.columns.is_empty() {
+                result
+            } else {
+                let scenario = config
+                    .scenarios
+                    .get(name)
+                    .ok_or(anyhow!("Scenario {} not found", name))?;
+                let stack_names = fuzzer.scenario_stack_names(scenario)?;
+                selected = result.select_columns(&self.columns, &stack_names)?;
+                &selected
+            };
+
+            let path = output.join(format!("{}.{}", name, self.format.extension()));
+            match self.format {
+                ChartOutputFormat::Csv => write_csv(&path, result, self.decimal)?,
+                ChartOutputFormat::Json => write_json(&path, result, self.decimal)?,
+                ChartOutputFormat::Parquet => write_parquet(&path, result, self.decimal)?,
+            }
+            info!(
+                "Wrote {} runs of {} to {}",
+                result.data.len(),
+                name,
+                path.display()
+            );
+        }
         Ok(())
     }
 }
+
+fn write_csv(path: &Path, result: &FuzzResultFlat, decimal: bool) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(&result.column_names)?;
+    for row in result.to_string_rows(decimal) {
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Runs as an array of objects keyed by column, values are strings as they
+/// can exceed the precision of JSON numbers
+fn write_json(path: &Path, result: &FuzzResultFlat, decimal: bool) -> Result<()> {
+    let rows = result
+        .to_string_rows(decimal)
+        .into_iter()
+        .map(|row| {
+            result
+                .column_names
+                .iter()
+                .cloned()
+                .zip(row.into_iter().map(serde_json::Value::String))
+                .collect::<serde_json::Map<_, _>>()
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_writer_pretty(File::create(path)?, &rows)?;
+    Ok(())
+}
+
+/// Digits of the largest parquet DECIMAL, stored as 32 bytes
+const PARQUET_DECIMAL_PRECISION: i32 = 76;
+
+/// Parquet type of a column of the runs
+enum ParquetColumn {
+    /// 18 decimals fixed point values, which lose precision beyond that of
+    /// a double
+    Double,
+    /// Raw values that fit the precision of a parquet DECIMAL
+    Decimal,
+    /// Raw values that exceed the precision of a parquet DECIMAL
+    Utf8,
+}
+
+/// Runs in a single row group, as DOUBLE columns if written as decimals and
+/// otherwise as exact DECIMAL columns. Columns with values beyond the 76
+/// digits of a DECIMAL are written as UTF8 instead
+fn write_parquet(path: &Path, result: &FuzzResultFlat, decimal: bool) -> Result<()> {
+    let max_decimal = U256::from(10).pow(U256::from(PARQUET_DECIMAL_PRECISION)) - U256::from(1);
+    let columns = (0..result.column_names.len())
+        .map(|index| result.data.iter().map(|row| row[index]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let kinds = columns
+        .iter()
+        .map(|values| {
+            if decimal {
+                ParquetColumn::Double
+            } else if values.iter().all(|v| *v <= max_decimal) {
+                ParquetColumn::Decimal
+            } else {
+                ParquetColumn::Utf8
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let fields = result
+        .column_names
+        .iter()
+        .zip(kinds.iter())
+        .map(|(name, kind)| {
+            match kind {
+                ParquetColumn::Double => Type::primitive_type_builder(name, PhysicalType::DOUBLE),
+                ParquetColumn::Decimal => {
+                    Type::primitive_type_builder(name, PhysicalType::FIXED_LEN_BYTE_ARRAY)
+                        .with_length(32)
+                        .with_converted_type(ConvertedType::DECIMAL)
+                        .with_precision(PARQUET_DECIMAL_PRECISION)
+                        .with_scale(0)
+                }
+                ParquetColumn::Utf8 => Type::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+                    .with_converted_type(ConvertedType::UTF8),
+            }
+            .with_repetition(Repetition::REQUIRED)
+            .build()
+            .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = Type::group_type_builder("schema")
+        .with_fields(fields)
+        .build()?;
+
+    let mut writer = SerializedFileWriter::new(
+        File::create(path)?,
+        Arc::new(schema),
+        Arc::new(WriterProperties::builder().build()),
+    )?;
+    let mut row_group = writer.next_row_group()?;
+    for (values, kind) in columns.iter().zip(kinds.iter()) {
+        let mut column = row_group
+            .next_column()?
+            .ok_or(anyhow!("Parquet schema has fewer columns than the runs"))?;
+        match kind {
+            ParquetColumn::Double => {
+                let values = values
+                    .iter()
+                    .map(|v| Decimal18(*v).to_string().parse::<f64>())
+                    .collect::<Result<Vec<_>, _>>()?;
+                column
+                    .typed::<DoubleType>()
+                    .write_batch(&values, None, None)?;
+            }
+            ParquetColumn::Decimal => {
+                // big endian two's complement, values below the max decimal
+                // are below 2^255 so their U256 bytes are already that
+                let values = values
+                    .iter()
+                    .map(|v| FixedLenByteArray::from(ByteArray::from(v.to_be_bytes_vec())))
+                    .collect::<Vec<_>>();
+                column
+                    .typed::<FixedLenByteArrayType>()
+                    .write_batch(&values, None, None)?;
+            }
+            ParquetColumn::Utf8 => {
+                let values = values
+                    .iter()
+                    .map(|v| ByteArray::from(v.to_string().as_str()))
+                    .collect::<Vec<_>>();
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)?;
+            }
+        }
+        column.close()?;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
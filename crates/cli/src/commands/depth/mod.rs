@@ -0,0 +1,94 @@
+use crate::{execute::Execute, subgraph::CliSubgraphArgs};
+use alloy::primitives::{utils::format_units, Address};
+use anyhow::Result;
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_quote::{get_depth, DepthLadder};
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct Depth {
+    #[clap(flatten)]
+    pub subgraph_args: CliSubgraphArgs,
+
+    #[arg(short, long, help = "RPC URL")]
+    pub rpc_url: String,
+
+    #[arg(short, long, help = "Orderbook contract address")]
+    pub orderbook_address: Address,
+
+    #[arg(
+        short,
+        long,
+        help = "Token the orders receive, ie the token a taker sells"
+    )]
+    pub input_token: Address,
+
+    #[arg(
+        short = 't',
+        long,
+        help = "Token the orders give, ie the token a taker buys"
+    )]
+    pub output_token: Address,
+
+    #[arg(short, long, help = "Block number to quote at, defaults to latest")]
+    pub block_number: Option<u64>,
+
+    #[arg(short, long, help = "Multicall3 address to use when quoting")]
+    pub multicall_address: Option<Address>,
+
+    #[arg(long, help = "Output the ladder as JSON")]
+    pub json: bool,
+}
+
+impl Execute for Depth {
+    async fn execute(&self) -> Result<()> {
+        let ladder = get_depth(
+            &self.subgraph_args.subgraph_url,
+            &self.rpc_url,
+            self.orderbook_address,
+            self.input_token,
+            self.output_token,
+            self.block_number,
+            self.multicall_address,
+        )
+        .await?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&ladder)?);
+        } else {
+            info!("\n{}", build_table(&ladder)?);
+        }
+        Ok(())
+    }
+}
+
+fn build_table(ladder: &DepthLadder) -> Result<Table> {
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Order Hash",
+            "Owner",
+            "Ratio",
+            "Max Output",
+            "Max Input",
+            "Cumulative Output",
+            "Cumulative Input",
+        ]);
+
+    for level in ladder.levels.iter() {
+        table.add_row(vec![
+            level.order_hash.to_string(),
+            level.owner.to_string(),
+            format_units(level.ratio, 18)?,
+            format_units(level.max_output, ladder.output_decimals)?,
+            format_units(level.max_input, ladder.input_decimals)?,
+            format_units(level.cumulative_output, ladder.output_decimals)?,
+            format_units(level.cumulative_input, ladder.input_decimals)?,
+        ]);
+    }
+
+    Ok(table)
+}
@@ -0,0 +1,79 @@
+use super::CliJournalArgs;
+use crate::execute::Execute;
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_common::journal::{JournalEntry, JournalStatus};
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct CliJournalListArgs {
+    #[arg(long, help = "Only list pending transactions")]
+    pending: bool,
+
+    #[clap(flatten)]
+    journal_args: CliJournalArgs,
+}
+
+impl Execute for CliJournalListArgs {
+    async fn execute(&self) -> Result<()> {
+        let journal = self.journal_args.open()?;
+        let entries = if self.pending {
+            journal.pending()?
+        } else {
+            journal.entries()?
+        };
+
+        let table = build_table(entries)?;
+        info!("\n{}", table);
+        Ok(())
+    }
+}
+
+fn build_table(entries: Vec<JournalEntry>) -> Result<Table> {
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "ID",
+            "Created At",
+            "Chain",
+            "Call",
+            "To",
+            "Status",
+            "Tx Hash",
+        ]);
+
+    for entry in entries.into_iter() {
+        table.add_row(vec![
+            entry.id,
+            Utc.timestamp_millis_opt(entry.created_at as i64)
+                .single()
+                .map(|v| v.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default(),
+            entry.chain_id.map(|v| v.to_string()).unwrap_or_default(),
+            entry.call,
+            entry.to.to_string(),
+            format_status(&entry.status),
+            entry
+                .transaction_hash
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        ]);
+    }
+
+    Ok(table)
+}
+
+fn format_status(status: &JournalStatus) -> String {
+    match status {
+        JournalStatus::PendingPrepare => "Pending (preparing)".to_string(),
+        JournalStatus::PendingSign => "Pending (signing)".to_string(),
+        JournalStatus::PendingSend => "Pending (sent)".to_string(),
+        JournalStatus::Confirmed => "Confirmed".to_string(),
+        JournalStatus::Replaced => "Replaced".to_string(),
+        JournalStatus::Failed(e) => format!("Failed: {}", e),
+    }
+}
@@ -0,0 +1,46 @@
+mod list;
+mod wait;
+
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::{Args, Parser};
+use list::CliJournalListArgs;
+use rain_orderbook_common::journal::Journal as TransactionJournal;
+use std::path::PathBuf;
+use wait::CliJournalWaitArgs;
+
+#[derive(Parser)]
+pub enum Journal {
+    #[command(about = "List the journaled write transactions", alias = "ls")]
+    List(CliJournalListArgs),
+
+    #[command(about = "Re-attach to pending transactions and wait for their receipts")]
+    Wait(CliJournalWaitArgs),
+}
+
+impl Execute for Journal {
+    async fn execute(&self) -> Result<()> {
+        match self {
+            Journal::List(list) => list.execute().await,
+            Journal::Wait(wait) => wait.execute().await,
+        }
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct CliJournalArgs {
+    #[arg(
+        long,
+        help = "Path to the journal file, defaults to the journal in the user data directory"
+    )]
+    journal_file: Option<PathBuf>,
+}
+
+impl CliJournalArgs {
+    pub fn open(&self) -> Result<TransactionJournal> {
+        Ok(match &self.journal_file {
+            Some(path) => TransactionJournal::new(path.clone()),
+            None => TransactionJournal::open_default()?,
+        })
+    }
+}
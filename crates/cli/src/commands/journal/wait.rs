@@ -0,0 +1,97 @@
+use super::CliJournalArgs;
+use crate::execute::Execute;
+use alloy_ethers_typecast::transaction::ReadableClientHttp;
+use anyhow::Result;
+use clap::Args;
+use std::time::Duration;
+use tracing::{info, warn};
+
+#[derive(Args, Clone)]
+pub struct CliJournalWaitArgs {
+    #[arg(
+        short = 'i',
+        long,
+        help = "ID of the transaction to wait for, defaults to all pending transactions of the chain of the RPC"
+    )]
+    id: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "RPC URL of the chain the transactions were sent to"
+    )]
+    rpc_url: String,
+
+    #[arg(
+        long,
+        help = "Seconds to wait for each transaction",
+        default_value = "300"
+    )]
+    timeout: u64,
+
+    #[arg(long, help = "Seconds between receipt polls", default_value = "5")]
+    poll_interval: u64,
+
+    #[clap(flatten)]
+    journal_args: CliJournalArgs,
+}
+
+impl Execute for CliJournalWaitArgs {
+    async fn execute(&self) -> Result<()> {
+        let journal = self.journal_args.open()?;
+        let chain_id: u64 = ReadableClientHttp::new_from_url(self.rpc_url.clone())?
+            .get_chainid()
+            .await?
+            .try_into()?;
+        let entries = match &self.id {
+            // the journal refuses to wait for it on another chain
+            Some(id) => vec![journal.get(id)?],
+            None => journal
+                .pending()?
+                .into_iter()
+                .filter(|v| v.chain_id == Some(chain_id))
+                .collect(),
+        };
+        if entries.is_empty() {
+            info!("No pending transactions on chain {}", chain_id);
+            return Ok(());
+        }
+
+        for entry in entries {
+            info!("Waiting for transaction {} ({})", entry.id, entry.call);
+            let entry = journal
+                .wait(
+                    &entry,
+                    &self.rpc_url,
+                    Duration::from_secs(self.poll_interval),
+                    Duration::from_secs(self.timeout),
+                )
+                .await;
+            let entry = match entry {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("Could not wait for transaction: {}", e);
+                    continue;
+                }
+            };
+            if entry.status.is_pending() {
+                warn!("Transaction {} is still pending", entry.id);
+            } else {
+                info!(
+                    "Transaction {} {:?} in block {} with hash {}",
+                    entry.id,
+                    entry.status,
+                    entry
+                        .block_number
+                        .map(|v| v.to_string())
+                        .unwrap_or_default(),
+                    entry
+                        .transaction_hash
+                        .map(|v| v.to_string())
+                        .unwrap_or_default()
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,15 @@
+mod batch;
 mod chart;
+mod depth;
+mod journal;
 mod order;
 mod order_take;
 mod quote;
+mod scenario_test;
+mod take_orders;
 mod vault;
 
-pub use self::{chart::Chart, order::Order, order_take::OrderTake, vault::Vault};
+pub use self::{
+    batch::Batch, chart::Chart, depth::Depth, journal::Journal, order::Order,
+    order_take::OrderTake, scenario_test::Test, take_orders::TakeOrders, vault::Vault,
+};
@@ -1,9 +1,13 @@
 use crate::{
-    execute::Execute, status::display_write_transaction_status, transaction::CliTransactionArgs,
+    execute::Execute,
+    output::output_safe_batch,
+    status::{display_fee_report, display_write_simulations, display_write_transaction_status},
+    transaction::CliTransactionArgs,
 };
+use alloy::primitives::{B256, U256};
 use anyhow::{anyhow, Result};
 use clap::Args;
-use rain_orderbook_app_settings::Config;
+use rain_orderbook_app_settings::{network::Network, Config};
 use rain_orderbook_common::add_order::AddOrderArgs;
 use rain_orderbook_common::frontmatter::parse_frontmatter;
 use rain_orderbook_common::transaction::TransactionArgs;
@@ -24,12 +28,32 @@ pub struct CliOrderAddArgs {
     #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
     deployment: String,
 
+    #[arg(
+        long,
+        help = "Nonce of the order, derived from the salt if not specified"
+    )]
+    nonce: Option<B256>,
+
+    #[arg(
+        long,
+        help = "Secret of the order, derived from the salt if not specified"
+    )]
+    secret: Option<B256>,
+
+    #[arg(
+        long,
+        help = "Salt to derive the nonce and secret from, adding the same order with the same salt results in the same order hash. Random if not specified"
+    )]
+    salt: Option<B256>,
+
     #[clap(flatten)]
     pub transaction_args: CliTransactionArgs,
 }
 
 impl CliOrderAddArgs {
-    async fn to_add_order_args(&self) -> Result<AddOrderArgs> {
+    /// Builds the add order args and returns them along with the network of
+    /// the deployment
+    async fn to_add_order_args(&self) -> Result<(AddOrderArgs, Network)> {
         let text = read_to_string(&self.dotrain_file).map_err(|e| anyhow!(e))?;
         let config: Config = parse_frontmatter(text.clone()).await?.try_into()?;
         let config_deployment = config
@@ -37,25 +61,78 @@ impl CliOrderAddArgs {
             .get(&self.deployment)
             .ok_or(anyhow!("specified deployment is undefined!"))?;
 
-        Ok(
+        let mut add_order_args =
             AddOrderArgs::new_from_deployment(text.clone(), config_deployment.deref().clone())
-                .await?,
-        )
+                .await?;
+        add_order_args.nonce = self.nonce;
+        add_order_args.secret = self.secret;
+        // a random salt rather than a random nonce makes the order hash
+        // known before submission
+        add_order_args.salt = match (self.nonce, self.salt) {
+            (None, None) => Some(B256::random()),
+            (_, salt) => salt,
+        };
+        Ok((
+            add_order_args,
+            config_deployment.order.network.deref().clone(),
+        ))
     }
 }
 
 impl Execute for CliOrderAddArgs {
     async fn execute(&self) -> Result<()> {
-        let add_order_args: AddOrderArgs = self.clone().to_add_order_args().await?;
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let (add_order_args, network) = self.clone().to_add_order_args().await?;
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
 
+        if let Some(path) = &self.transaction_args.safe_batch {
+            let add_order_call = add_order_args
+                .try_into_call(tx_args.rpc_url.clone())
+                .await?;
+            return output_safe_batch(path, &tx_args, Some(&network), "Add Order", |batch| {
+                batch.add_call(tx_args.orderbook_address, U256::ZERO, &add_order_call);
+                Ok(())
+            });
+        }
+
+        let owner = self
+            .transaction_args
+            .try_into_sender_address(&tx_args)
+            .await?;
+        let prepared_order = add_order_args
+            .try_into_prepared_order(tx_args.rpc_url.clone(), owner, tx_args.orderbook_address)
+            .await?;
+        info!("Order hash: {}", prepared_order.order_hash);
+        info!("Order id: {}", prepared_order.order_id);
+
+        info!("----- Simulate Add Order -----");
+        let simulations = [add_order_args.simulate(tx_args.clone(), owner).await?];
+        display_write_simulations(&simulations)?;
+        display_fee_report(
+            &mut tx_args,
+            &simulations,
+            Some(&network),
+            self.transaction_args.max_cost,
+        )
+        .await?;
+        if self.transaction_args.dry_run {
+            return Ok(());
+        }
+        let signer_client = self
+            .transaction_args
+            .try_into_signer_client(&tx_args, owner)
+            .await?;
+
         info!("----- Add Order -----");
-        add_order_args
-            .execute(tx_args, |status| {
-                display_write_transaction_status(status);
+        let confirmed = add_order_args
+            .execute(tx_args, &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
             })
             .await?;
+        match confirmed.added_order() {
+            Some(event) => info!("Order {} added", event.orderHash),
+            None => info!("Order was not added, it already exists"),
+        }
 
         Ok(())
     }
@@ -2,7 +2,9 @@ mod add;
 mod compose;
 mod detail;
 mod list;
+mod quote;
 mod remove;
+mod update;
 
 use crate::execute::Execute;
 use add::CliOrderAddArgs;
@@ -11,7 +13,9 @@ use clap::Parser;
 use compose::Compose;
 use detail::CliOrderDetailArgs;
 use list::CliOrderListArgs;
+use quote::CliOrderQuoteArgs;
 use remove::CliOrderRemoveArgs;
+use update::CliOrderUpdateArgs;
 
 #[derive(Parser)]
 pub enum Order {
@@ -27,8 +31,16 @@ pub enum Order {
     #[command(about = "Remove an Order", alias = "rm")]
     Remove(CliOrderRemoveArgs),
 
+    #[command(
+        about = "Replace an Order with a new .rain deployment in one transaction, keeping its vaults"
+    )]
+    Update(CliOrderUpdateArgs),
+
     #[command(about = "Compose a .rain order file to Rainlang", alias = "comp")]
     Compose(Compose),
+
+    #[command(about = "Quote a .rain order deployment on a fork before deploying it")]
+    Quote(CliOrderQuoteArgs),
 }
 
 impl Execute for Order {
@@ -38,7 +50,9 @@ impl Execute for Order {
             Order::Detail(detail) => detail.execute().await,
             Order::Create(create) => create.execute().await,
             Order::Remove(remove) => remove.execute().await,
+            Order::Update(update) => update.execute().await,
             Order::Compose(compose) => compose.execute().await,
+            Order::Quote(quote) => quote.execute().await,
         }
     }
 }
@@ -0,0 +1,140 @@
+use crate::execute::Execute;
+use alloy::primitives::{Address, U256};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_app_settings::Config;
+use rain_orderbook_common::add_order::AddOrderArgs;
+use rain_orderbook_common::deployment_quote::{DeploymentQuote, DeploymentQuoteArgs};
+use rain_orderbook_common::frontmatter::parse_frontmatter;
+use std::fs::read_to_string;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct CliOrderQuoteArgs {
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file specifying the order"
+    )]
+    dotrain_file: PathBuf,
+
+    #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
+    deployment: String,
+
+    #[arg(
+        long,
+        help = "Orderbook address to quote against, defaults to the deployment's orderbook"
+    )]
+    orderbook_address: Option<Address>,
+
+    #[arg(long, help = "Owner of the order", default_value_t = Address::ZERO)]
+    owner: Address,
+
+    #[arg(long, help = "Counterparty of the quote", default_value_t = Address::ZERO)]
+    counterparty: Address,
+
+    #[arg(
+        long = "vault-balance",
+        value_name = "TOKEN=AMOUNT",
+        value_parser = parse_vault_balance,
+        help = "Hypothetical vault balance of a token in its decimals, vaults of unspecified tokens are treated as empty"
+    )]
+    vault_balances: Vec<(Address, U256)>,
+
+    #[arg(long, help = "Block number to fork at, defaults to latest")]
+    block_number: Option<u64>,
+}
+
+fn parse_vault_balance(value: &str) -> Result<(Address, U256)> {
+    let (token, amount) = value
+        .split_once('=')
+        .ok_or(anyhow!("expected TOKEN=AMOUNT"))?;
+    Ok((Address::from_str(token)?, U256::from_str(amount)?))
+}
+
+impl Execute for CliOrderQuoteArgs {
+    async fn execute(&self) -> Result<()> {
+        let text = read_to_string(&self.dotrain_file).map_err(|e| anyhow!(e))?;
+        let config: Config = parse_frontmatter(text.clone()).await?.try_into()?;
+        let config_deployment = config
+            .deployments
+            .get(&self.deployment)
+            .ok_or(anyhow!("specified deployment is undefined!"))?;
+
+        let orderbook = match self.orderbook_address {
+            Some(v) => v,
+            None => {
+                config_deployment
+                    .order
+                    .orderbook
+                    .as_ref()
+                    .ok_or(anyhow!("deployment has no orderbook, specify one"))?
+                    .address
+            }
+        };
+        let rpc_url = config_deployment.order.network.rpc.to_string();
+
+        let add_order_args =
+            AddOrderArgs::new_from_deployment(text.clone(), config_deployment.deref().clone())
+                .await?;
+        let quotes = add_order_args
+            .quote_on_fork(
+                rpc_url,
+                DeploymentQuoteArgs {
+                    orderbook,
+                    owner: self.owner,
+                    counterparty: self.counterparty,
+                    vault_balances: self.vault_balances.iter().cloned().collect(),
+                    block_number: self.block_number,
+                },
+            )
+            .await?;
+
+        info!("\n{}", build_table(quotes));
+        Ok(())
+    }
+}
+
+fn build_table(quotes: Vec<DeploymentQuote>) -> Table {
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Input",
+            "Output",
+            "Input Token",
+            "Output Token",
+            "Max Output",
+            "Ratio",
+            "Missing Vault Balances",
+            "Error",
+        ]);
+
+    for quote in quotes.into_iter() {
+        table.add_row(vec![
+            quote.input_io_index.to_string(),
+            quote.output_io_index.to_string(),
+            quote.input_token.to_string(),
+            quote.output_token.to_string(),
+            quote
+                .quote
+                .map(|v| v.max_output.to_string())
+                .unwrap_or_default(),
+            quote.quote.map(|v| v.ratio.to_string()).unwrap_or_default(),
+            quote
+                .missing_vault_balances
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            quote.error.unwrap_or_default(),
+        ]);
+    }
+
+    table
+}
@@ -1,9 +1,14 @@
 use crate::{
-    execute::Execute, status::display_write_transaction_status, subgraph::CliSubgraphArgs,
+    execute::Execute,
+    output::output_safe_batch,
+    status::{display_fee_report, display_write_simulations, display_write_transaction_status},
+    subgraph::CliSubgraphArgs,
     transaction::CliTransactionArgs,
 };
+use alloy::primitives::U256;
 use anyhow::Result;
 use clap::Args;
+use rain_orderbook_bindings::IOrderBookV4::removeOrder2Call;
 use rain_orderbook_common::remove_order::RemoveOrderArgs;
 use rain_orderbook_common::subgraph::SubgraphArgs;
 use rain_orderbook_common::transaction::TransactionArgs;
@@ -31,15 +36,57 @@ impl Execute for CliOrderRemoveArgs {
             .await?;
         let remove_order_args: RemoveOrderArgs = order.into();
 
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
 
+        if let Some(path) = &self.transaction_args.safe_batch {
+            let remove_order_call: removeOrder2Call = remove_order_args.try_into()?;
+            return output_safe_batch(path, &tx_args, None, "Remove Order", |batch| {
+                batch.add_call(tx_args.orderbook_address, U256::ZERO, &remove_order_call);
+                Ok(())
+            });
+        }
+
+        let sender = self
+            .transaction_args
+            .try_into_sender_address(&tx_args)
+            .await?;
+
+        info!("----- Simulate Remove Order -----");
+        let simulations = [remove_order_args
+            .clone()
+            .simulate(tx_args.clone(), sender)
+            .await?];
+        display_write_simulations(&simulations)?;
+        let network = self
+            .transaction_args
+            .resolve_network(tx_args.chain_id, None)
+            .await;
+        display_fee_report(
+            &mut tx_args,
+            &simulations,
+            network.as_ref(),
+            self.transaction_args.max_cost,
+        )
+        .await?;
+        if self.transaction_args.dry_run {
+            return Ok(());
+        }
+        let signer_client = self
+            .transaction_args
+            .try_into_signer_client(&tx_args, sender)
+            .await?;
+
         info!("----- Remove Order -----");
-        remove_order_args
-            .execute(tx_args, |status| {
-                display_write_transaction_status(status);
+        let confirmed = remove_order_args
+            .execute(tx_args, &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
             })
             .await?;
+        match confirmed.removed_order() {
+            Some(event) => info!("Order {} removed", event.orderHash),
+            None => info!("Order was not removed, it is not active"),
+        }
 
         Ok(())
     }
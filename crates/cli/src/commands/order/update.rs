@@ -0,0 +1,132 @@
+use crate::{
+    execute::Execute,
+    output::output_safe_batch,
+    status::{display_fee_report, display_write_simulations, display_write_transaction_status},
+    subgraph::CliSubgraphArgs,
+    transaction::CliTransactionArgs,
+};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rain_orderbook_app_settings::Config;
+use rain_orderbook_common::add_order::AddOrderArgs;
+use rain_orderbook_common::frontmatter::parse_frontmatter;
+use rain_orderbook_common::subgraph::SubgraphArgs;
+use rain_orderbook_common::transaction::TransactionArgs;
+use rain_orderbook_common::update_order::UpdateOrderArgs;
+use std::fs::read_to_string;
+use std::ops::Deref;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+#[derive(Args, Clone)]
+pub struct CliOrderUpdateArgs {
+    #[arg(short = 'i', long, help = "ID of the Order to replace")]
+    order_id: String,
+
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file specifying the new order"
+    )]
+    dotrain_file: PathBuf,
+
+    #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
+    deployment: String,
+
+    #[arg(
+        long,
+        help = "Use the vault ids of the deployment instead of reusing the vault ids of the replaced order"
+    )]
+    new_vault_ids: bool,
+
+    #[clap(flatten)]
+    pub subgraph_args: CliSubgraphArgs,
+
+    #[clap(flatten)]
+    pub transaction_args: CliTransactionArgs,
+}
+
+impl Execute for CliOrderUpdateArgs {
+    async fn execute(&self) -> Result<()> {
+        let subgraph_args: SubgraphArgs = self.subgraph_args.clone().into();
+        let order = subgraph_args
+            .to_subgraph_client()
+            .await?
+            .order_detail(self.order_id.clone().into())
+            .await?;
+
+        let text = read_to_string(&self.dotrain_file).map_err(|e| anyhow!(e))?;
+        let config: Config = parse_frontmatter(text.clone()).await?.try_into()?;
+        let config_deployment = config
+            .deployments
+            .get(&self.deployment)
+            .ok_or(anyhow!("specified deployment is undefined!"))?;
+        let add_order_args =
+            AddOrderArgs::new_from_deployment(text, config_deployment.deref().clone()).await?;
+        let update_order_args = UpdateOrderArgs::new(order, add_order_args, !self.new_vault_ids)?;
+
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
+        tx_args.try_fill_chain_id().await?;
+
+        info!("----- Rainlang Diff -----");
+        match update_order_args.get_rainlang_diff() {
+            Ok(rainlang_diff) if rainlang_diff.diff.is_empty() => info!("Rainlang is unchanged"),
+            Ok(rainlang_diff) => info!("\n{}", rainlang_diff.diff),
+            Err(e) => warn!("Could not diff the rainlang of the old order: {}", e),
+        }
+
+        if let Some(path) = &self.transaction_args.safe_batch {
+            let batch_args = update_order_args
+                .try_into_batch(tx_args.rpc_url.clone(), tx_args.orderbook_address)
+                .await?;
+            return output_safe_batch(
+                path,
+                &tx_args,
+                Some(config_deployment.order.network.as_ref()),
+                "Update Order",
+                |batch| {
+                    batch.add_batch(tx_args.orderbook_address, &batch_args)?;
+                    Ok(())
+                },
+            );
+        }
+
+        let sender = self
+            .transaction_args
+            .try_into_sender_address(&tx_args)
+            .await?;
+
+        info!("----- Simulate Update Order -----");
+        let simulations = update_order_args.simulate(tx_args.clone(), sender).await?;
+        display_write_simulations(&simulations)?;
+        display_fee_report(
+            &mut tx_args,
+            &simulations,
+            Some(config_deployment.order.network.as_ref()),
+            self.transaction_args.max_cost,
+        )
+        .await?;
+        if self.transaction_args.dry_run {
+            return Ok(());
+        }
+        let signer_client = self
+            .transaction_args
+            .try_into_signer_client(&tx_args, sender)
+            .await?;
+
+        info!("----- Update Order -----");
+        let confirmed = update_order_args
+            .execute(tx_args, &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
+            })
+            .await?;
+        if let Some(event) = confirmed.removed_order() {
+            info!("Order {} removed", event.orderHash);
+        }
+        if let Some(event) = confirmed.added_order() {
+            info!("Order {} added", event.orderHash);
+        }
+
+        Ok(())
+    }
+}
@@ -3,6 +3,6 @@ use rain_orderbook_quote::cli::Quoter;
 
 impl Execute for Quoter {
     async fn execute(&self) -> anyhow::Result<()> {
-        self.run().await.map(|_| ())
+        self.run_cli().await
     }
 }
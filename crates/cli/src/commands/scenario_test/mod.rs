@@ -0,0 +1,148 @@
+use crate::execute::Execute;
+use alloy::primitives::{B256, U256};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_app_settings::{config_source::ConfigSource, fuzz::Decimal18, Config};
+use rain_orderbook_common::dotrain::RainDocument;
+use rain_orderbook_common::fuzz::{FuzzFailure, FuzzResult, FuzzRunner, FuzzRunnerError};
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+#[derive(Args, Clone)]
+pub struct Test {
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file specifying the order"
+    )]
+    dotrain_file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Scenario to test, defaults to all scenarios with expectations"
+    )]
+    scenario: Option<String>,
+
+    #[arg(long, help = "Seed of the fuzzed values, random if not specified")]
+    seed: Option<B256>,
+}
+
+impl Execute for Test {
+    async fn execute(&self) -> Result<()> {
+        let dotrain = read_to_string(self.dotrain_file.clone()).map_err(|e| anyhow!(e))?;
+        let frontmatter =
+            RainDocument::get_front_matter(&dotrain).ok_or(FuzzRunnerError::EmptyFrontmatter)?;
+        let config_string = ConfigSource::try_from_string(frontmatter.to_string()).await?;
+        let config: Config = config_string.try_into()?;
+
+        let mut scenarios = match &self.scenario {
+            Some(name) => {
+                let scenario = config
+                    .scenarios
+                    .get(name)
+                    .ok_or(FuzzRunnerError::ScenarioNotFound(name.clone()))?;
+                if scenario.expect.is_empty() {
+                    return Err(FuzzRunnerError::ScenarioNotTestable(name.clone()).into());
+                }
+                vec![name.clone()]
+            }
+            None => config
+                .scenarios
+                .iter()
+                .filter(|(_, v)| !v.expect.is_empty())
+                .map(|(k, _)| k.clone())
+                .collect(),
+        };
+        scenarios.sort();
+        if scenarios.is_empty() {
+            warn!("No scenarios with expectations to test");
+            return Ok(());
+        }
+
+        let seed = self.seed.unwrap_or_else(B256::random);
+        let mut failed = vec![];
+        for name in scenarios.iter() {
+            // each scenario is fuzzed from the seed so that it can be
+            // reproduced on its own
+            let mut runner = FuzzRunner::new(&dotrain, config.clone(), Some(seed.0)).await;
+            runner.shrink = true;
+            let result = runner.run_scenario_by_name(name).await?;
+            let failures = result.check_expectations(&config.scenarios[name].expect)?;
+
+            if failures.is_empty() && result.reverts.is_empty() {
+                info!("PASS {}", name);
+                continue;
+            }
+            info!(
+                "FAIL {}: {} failed expectations, {} reverts\n{}",
+                name,
+                failures.len(),
+                result.reverts.len(),
+                build_table(&result, &failures)
+            );
+            for counterexample in result.counterexamples.iter() {
+                info!(
+                    "Smallest revert of {} at block {}: {} with {}",
+                    counterexample.entrypoint,
+                    counterexample.block_number,
+                    counterexample.error,
+                    format_inputs(&counterexample.inputs).replace('\n', ", ")
+                );
+            }
+            info!("Reproduce with: --seed {} --scenario {}", seed, name);
+            failed.push(name.clone());
+        }
+
+        info!(
+            "{} passed, {} failed",
+            scenarios.len() - failed.len(),
+            failed.len()
+        );
+        if !failed.is_empty() {
+            return Err(anyhow!("Failed scenarios: {}", failed.join(", ")));
+        }
+        Ok(())
+    }
+}
+
+fn build_table(result: &FuzzResult, failures: &[FuzzFailure]) -> Table {
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec!["Run", "Entrypoint", "Failure", "Inputs"]);
+
+    for failure in failures.iter() {
+        table.add_row(vec![
+            failure.run.to_string(),
+            failure.entrypoint.clone(),
+            format!("{}: {}", failure.expectation, failure.message),
+            format_inputs(&failure.inputs),
+        ]);
+    }
+    for revert in result.reverts.iter() {
+        table.add_row(vec![
+            revert.run.to_string(),
+            revert.entrypoint.clone(),
+            format!(
+                "reverted at block {}: {}",
+                revert.block_number, revert.error
+            ),
+            format_inputs(&revert.inputs),
+        ]);
+    }
+
+    table
+}
+
+fn format_inputs(inputs: &BTreeMap<String, U256>) -> String {
+    inputs
+        .iter()
+        .map(|(k, v)| format!("{}: {}", k, Decimal18(*v)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
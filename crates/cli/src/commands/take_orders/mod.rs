@@ -0,0 +1,147 @@
+use crate::{execute::Execute, subgraph::CliSubgraphArgs};
+use alloy::primitives::{
+    hex::encode_prefixed,
+    utils::{format_units, parse_units},
+    Address, U256,
+};
+use alloy_ethers_typecast::transaction::{ReadContractParameters, ReadableClientHttp};
+use anyhow::Result;
+use clap::Args;
+use rain_orderbook_bindings::ERC20::decimalsCall;
+use rain_orderbook_quote::{get_take_orders_route, TakeOrdersRoute, TakeOrdersRouteArgs};
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct TakeOrders {
+    #[clap(flatten)]
+    pub subgraph_args: CliSubgraphArgs,
+
+    #[arg(short, long, help = "RPC URL")]
+    pub rpc_url: String,
+
+    #[arg(short, long, help = "Orderbook contract address")]
+    pub orderbook_address: Address,
+
+    #[arg(long, help = "Token to sell to the orders")]
+    pub sell_token: Address,
+
+    #[arg(long, help = "Token to buy from the orders")]
+    pub buy_token: Address,
+
+    #[arg(
+        short = 'a',
+        long,
+        help = "Amount of buy token to buy, as a decimal number eg 1.5"
+    )]
+    pub buy_amount: String,
+
+    #[arg(
+        long,
+        help = "How much less than the route's total the take can buy, in basis points",
+        default_value_t = 50
+    )]
+    pub slippage_bps: u64,
+
+    #[arg(
+        long,
+        help = "Max average price to pay in sell token per buy token, as a decimal number eg 2500.5"
+    )]
+    pub max_average_price: String,
+
+    #[arg(short, long, help = "Block number to quote at, defaults to latest")]
+    pub block_number: Option<u64>,
+
+    #[arg(short, long, help = "Multicall3 address to use when quoting")]
+    pub multicall_address: Option<Address>,
+
+    #[arg(long, help = "Output the route as JSON")]
+    pub json: bool,
+}
+
+impl Execute for TakeOrders {
+    async fn execute(&self) -> Result<()> {
+        // the amount can't be more precise than the buy token
+        let buy_decimals = ReadableClientHttp::new_from_url(self.rpc_url.clone())?
+            .read(ReadContractParameters {
+                address: self.buy_token,
+                call: decimalsCall {},
+                block_number: self.block_number,
+            })
+            .await?
+            ._0;
+        let buy_amount = parse_units(&self.buy_amount, buy_decimals)?.get_absolute();
+
+        let args = TakeOrdersRouteArgs {
+            orderbook: self.orderbook_address,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            buy_amount: scale_to18(buy_amount, buy_decimals),
+            max_average_ratio: parse_units(&self.max_average_price, 18)?.get_absolute(),
+            slippage_bps: self.slippage_bps,
+            block_number: self.block_number,
+            multicall_address: self.multicall_address,
+        };
+        let route =
+            get_take_orders_route(&self.subgraph_args.subgraph_url, &self.rpc_url, &args).await?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&route)?);
+        } else {
+            info!("\n{}", build_report(&route, args.buy_amount)?);
+            info!("takeOrders2 calldata: {}", encode_prefixed(&route.calldata));
+        }
+        Ok(())
+    }
+}
+
+/// Scales an amount in the given decimals to 18 decimals
+fn scale_to18(value: U256, decimals: u8) -> U256 {
+    if decimals > 18 {
+        value / U256::from(10).pow(U256::from(decimals - 18))
+    } else {
+        value.saturating_mul(U256::from(10).pow(U256::from(18 - decimals)))
+    }
+}
+
+/// Dry-run report of the orders a route would take, no transaction is sent
+fn build_report(route: &TakeOrdersRoute, buy_amount: U256) -> Result<String> {
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Order Hash",
+            "Input",
+            "Output",
+            "Ratio",
+            "Max Output",
+            "Buy",
+            "Sell",
+        ]);
+
+    for leg in route.legs.iter() {
+        table.add_row(vec![
+            leg.order_hash.to_string(),
+            leg.input_io_index.to_string(),
+            leg.output_io_index.to_string(),
+            format_units(leg.ratio, 18)?,
+            format_units(leg.max_output, 18)?,
+            format_units(leg.output, 18)?,
+            format_units(leg.input, 18)?,
+        ]);
+    }
+
+    Ok(format!(
+        "{}\nBuy: {} of {} requested{}\nSell: {}\nAverage price: {}",
+        table,
+        format_units(route.total_output, 18)?,
+        format_units(buy_amount, 18)?,
+        if route.partial {
+            ", partially filled within the price limit"
+        } else {
+            ""
+        },
+        format_units(route.total_input, 18)?,
+        format_units(route.average_ratio, 18)?,
+    ))
+}
@@ -1,5 +1,9 @@
+use super::post_task::CliPostTaskArgs;
 use crate::{
-    execute::Execute, status::display_write_transaction_status, transaction::CliTransactionArgs,
+    execute::Execute,
+    output::output_safe_batch,
+    status::{display_fee_report, display_write_simulations, display_write_transaction_status},
+    transaction::CliTransactionArgs,
 };
 use alloy::primitives::{Address, U256};
 use anyhow::Result;
@@ -18,6 +22,9 @@ pub struct CliVaultDepositArgs {
     #[arg(short, long, help = "The amount to deposit")]
     amount: U256,
 
+    #[clap(flatten)]
+    post_task_args: CliPostTaskArgs,
+
     #[clap(flatten)]
     pub transaction_args: CliTransactionArgs,
 }
@@ -28,29 +35,76 @@ impl From<CliVaultDepositArgs> for DepositArgs {
             token: val.token,
             vault_id: val.vault_id,
             amount: val.amount,
+            tasks: vec![],
         }
     }
 }
 
 impl Execute for CliVaultDepositArgs {
     async fn execute(&self) -> Result<()> {
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
-        let deposit_args: DepositArgs = self.clone().into();
+        let mut deposit_args: DepositArgs = self.clone().into();
+        let mut network = None;
+        if let Some((post_task, post_task_network)) =
+            self.post_task_args.try_into_post_task_args().await?
+        {
+            deposit_args
+                .try_add_post_task(&post_task, tx_args.rpc_url.clone())
+                .await?;
+            network = Some(post_task_network);
+        }
+
+        if let Some(path) = &self.transaction_args.safe_batch {
+            return output_safe_batch(path, &tx_args, None, "Deposit", |batch| {
+                batch.add_deposit(tx_args.orderbook_address, &deposit_args);
+                Ok(())
+            });
+        }
+
+        let sender = self
+            .transaction_args
+            .try_into_sender_address(&tx_args)
+            .await?;
+
+        info!("----- Simulate Approve and Deposit -----");
+        let simulations = deposit_args.simulate(tx_args.clone(), sender).await?;
+        display_write_simulations(&simulations)?;
+        let network = self
+            .transaction_args
+            .resolve_network(tx_args.chain_id, network)
+            .await;
+        display_fee_report(
+            &mut tx_args,
+            &simulations,
+            network.as_ref(),
+            self.transaction_args.max_cost,
+        )
+        .await?;
+        if self.transaction_args.dry_run {
+            return Ok(());
+        }
+        let signer_client = self
+            .transaction_args
+            .try_into_signer_client(&tx_args, sender)
+            .await?;
 
         info!("----- Approve ERC20 token spend -----");
         deposit_args
-            .execute_approve(tx_args.clone(), |status| {
-                display_write_transaction_status(status);
+            .execute_approve(tx_args.clone(), &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
             })
             .await?;
 
         info!("----- Deposit tokens into Orderbook -----");
-        deposit_args
-            .execute_deposit(tx_args, |status| {
-                display_write_transaction_status(status);
+        let confirmed = deposit_args
+            .execute_deposit(tx_args, &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
             })
             .await?;
+        if let Some(event) = confirmed.deposit() {
+            info!("Deposited {} into vault {}", event.amount, event.vaultId);
+        }
         Ok(())
     }
 }
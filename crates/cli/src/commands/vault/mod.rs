@@ -2,6 +2,7 @@ mod deposit;
 mod detail;
 mod list;
 mod list_balance_changes;
+mod post_task;
 mod withdraw;
 
 use crate::execute::Execute;
@@ -0,0 +1,48 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rain_orderbook_app_settings::{network::Network, Config};
+use rain_orderbook_common::frontmatter::parse_frontmatter;
+use rain_orderbook_common::task::PostTaskArgs;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+#[derive(Args, Clone)]
+pub struct CliPostTaskArgs {
+    #[arg(
+        long,
+        help = "Path to a .rain file whose post-deposit or post-withdraw entrypoint is run after the vault change",
+        requires = "post_task_deployment"
+    )]
+    post_task_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Deployment key to select the deployer and bindings of the post task from frontmatter",
+        requires = "post_task_file"
+    )]
+    post_task_deployment: Option<String>,
+}
+
+impl CliPostTaskArgs {
+    /// Reads the post task file with the deployer and bindings of the selected
+    /// deployment, along with the network of the deployment, if a post task
+    /// file is specified
+    pub async fn try_into_post_task_args(&self) -> Result<Option<(PostTaskArgs, Network)>> {
+        let (Some(post_task_file), Some(post_task_deployment)) =
+            (&self.post_task_file, &self.post_task_deployment)
+        else {
+            return Ok(None);
+        };
+
+        let text = read_to_string(post_task_file).map_err(|e| anyhow!(e))?;
+        let config: Config = parse_frontmatter(text.clone()).await?.try_into()?;
+        let config_deployment = config
+            .deployments
+            .get(post_task_deployment)
+            .ok_or(anyhow!("specified deployment is undefined!"))?;
+        Ok(Some((
+            PostTaskArgs::new_from_deployment(text, config_deployment),
+            config_deployment.order.network.as_ref().clone(),
+        )))
+    }
+}
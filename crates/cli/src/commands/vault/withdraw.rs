@@ -1,4 +1,8 @@
-use crate::status::display_write_transaction_status;
+use super::post_task::CliPostTaskArgs;
+use crate::output::output_safe_batch;
+use crate::status::{
+    display_fee_report, display_write_simulations, display_write_transaction_status,
+};
 use crate::{execute::Execute, transaction::CliTransactionArgs};
 use alloy::primitives::{Address, U256};
 use anyhow::Result;
@@ -18,6 +22,9 @@ pub struct CliVaultWithdrawArgs {
     #[arg(short = 'a', long, help = "The target amount to withdraw")]
     target_amount: U256,
 
+    #[clap(flatten)]
+    post_task_args: CliPostTaskArgs,
+
     #[clap(flatten)]
     transaction_args: CliTransactionArgs,
 }
@@ -28,22 +35,72 @@ impl From<CliVaultWithdrawArgs> for WithdrawArgs {
             token: val.token,
             vault_id: val.vault_id,
             target_amount: val.target_amount,
+            tasks: vec![],
         }
     }
 }
 
 impl Execute for CliVaultWithdrawArgs {
     async fn execute(&self) -> Result<()> {
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
-        let withdraw_args: WithdrawArgs = self.clone().into();
+        let mut withdraw_args: WithdrawArgs = self.clone().into();
+        let mut network = None;
+        if let Some((post_task, post_task_network)) =
+            self.post_task_args.try_into_post_task_args().await?
+        {
+            withdraw_args
+                .try_add_post_task(&post_task, tx_args.rpc_url.clone())
+                .await?;
+            network = Some(post_task_network);
+        }
+
+        if let Some(path) = &self.transaction_args.safe_batch {
+            return output_safe_batch(path, &tx_args, None, "Withdraw", |batch| {
+                batch.add_withdraw(tx_args.orderbook_address, &withdraw_args.clone().into());
+                Ok(())
+            });
+        }
+
+        let sender = self
+            .transaction_args
+            .try_into_sender_address(&tx_args)
+            .await?;
+
+        info!("----- Simulate Withdraw -----");
+        let simulations = [withdraw_args.simulate(tx_args.clone(), sender).await?];
+        display_write_simulations(&simulations)?;
+        let network = self
+            .transaction_args
+            .resolve_network(tx_args.chain_id, network)
+            .await;
+        display_fee_report(
+            &mut tx_args,
+            &simulations,
+            network.as_ref(),
+            self.transaction_args.max_cost,
+        )
+        .await?;
+        if self.transaction_args.dry_run {
+            return Ok(());
+        }
+        let signer_client = self
+            .transaction_args
+            .try_into_signer_client(&tx_args, sender)
+            .await?;
 
         info!("----- Withdraw tokens from Vault -----");
-        withdraw_args
-            .execute(tx_args, |status| {
-                display_write_transaction_status(status);
+        let confirmed = withdraw_args
+            .execute(tx_args, &signer_client, |status| {
+                display_write_transaction_status(status, &signer_client);
             })
             .await?;
+        if let Some(event) = confirmed.withdrawal() {
+            info!(
+                "Withdrew {} of target {} from vault {}",
+                event.amount, event.targetAmount, event.vaultId
+            );
+        }
         Ok(())
     }
 }
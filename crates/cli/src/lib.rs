@@ -1,4 +1,4 @@
-use crate::commands::{Chart, Order, OrderTake, Vault};
+use crate::commands::{Batch, Chart, Depth, Journal, Order, OrderTake, TakeOrders, Test, Vault};
 use crate::execute::Execute;
 use anyhow::Result;
 use clap::Subcommand;
@@ -25,6 +25,24 @@ pub enum Orderbook {
     Chart(Chart),
 
     Quote(Quoter),
+
+    Depth(Depth),
+
+    TakeOrders(TakeOrders),
+
+    #[command(
+        about = "Execute a plan of deposits, withdrawals, added and removed orders in one transaction"
+    )]
+    Batch(Batch),
+
+    #[command(
+        subcommand,
+        about = "List and re-attach to journaled write transactions"
+    )]
+    Journal(Journal),
+
+    #[command(about = "Fuzz the scenarios with expectations and report the runs that fail them")]
+    Test(Test),
 }
 
 impl Orderbook {
@@ -35,6 +53,11 @@ impl Orderbook {
             Orderbook::OrderTake(order_take) => (order_take).execute().await,
             Orderbook::Chart(chart) => chart.execute().await,
             Orderbook::Quote(quote) => quote.execute().await,
+            Orderbook::Depth(depth) => depth.execute().await,
+            Orderbook::TakeOrders(take_orders) => take_orders.execute().await,
+            Orderbook::Batch(batch) => batch.execute().await,
+            Orderbook::Journal(journal) => journal.execute().await,
+            Orderbook::Test(test) => test.execute().await,
         }
     }
 }
@@ -1,5 +1,9 @@
+use anyhow::anyhow;
+use rain_orderbook_app_settings::network::Network;
+use rain_orderbook_common::{safe::SafeTransactionBatch, transaction::TransactionArgs};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::info;
 
 #[derive(clap::ValueEnum, Clone)]
 pub enum SupportedOutputEncoding {
@@ -27,3 +31,32 @@ pub fn output(
     }
     Ok(())
 }
+
+/// Writes the Safe Transaction Builder batch of the calls added by
+/// `add_calls` to the given file, for the chain of the given network, eg of
+/// the deployment, or otherwise for the chain of the transaction args
+pub fn output_safe_batch(
+    path: &Path,
+    transaction_args: &TransactionArgs,
+    network: Option<&Network>,
+    name: &str,
+    add_calls: impl FnOnce(&mut SafeTransactionBatch) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut batch = match network {
+        Some(network) => SafeTransactionBatch::new_from_network(network, name.to_string()),
+        None => {
+            let chain_id = transaction_args
+                .chain_id
+                .ok_or(anyhow!("chain id of the Safe batch is unknown"))?;
+            SafeTransactionBatch::new(chain_id, name.to_string())
+        }
+    };
+    add_calls(&mut batch)?;
+    std::fs::write(path, batch.to_json()?)?;
+    info!(
+        "Wrote Safe transaction batch of {} calls to {}",
+        batch.transactions.len(),
+        path.display()
+    );
+    Ok(())
+}
@@ -1,16 +1,32 @@
+use alloy::primitives::U256;
 use alloy::sol_types::SolCall;
 use alloy_ethers_typecast::transaction::WriteTransactionStatus;
+use anyhow::{anyhow, Result};
+use rain_orderbook_app_settings::network::Network;
+use rain_orderbook_common::{
+    fee::FeeReport,
+    simulate::WriteSimulation,
+    transaction::{SignerClient, TransactionArgs},
+};
 use std::fmt::Debug;
-use tracing::info;
+use tracing::{info, warn};
 
-pub fn display_write_transaction_status<T: SolCall + Debug>(status: WriteTransactionStatus<T>) {
+pub fn display_write_transaction_status<T: SolCall + Debug>(
+    status: WriteTransactionStatus<T>,
+    signer_client: &SignerClient,
+) {
     match status {
         WriteTransactionStatus::PendingPrepare(_) => {
             info!("⏳  Preparing transaction. Please wait.");
         }
-        WriteTransactionStatus::PendingSign(_) => {
-            info!("🖋   Please sign the transaction on your Ledger device.");
-        }
+        WriteTransactionStatus::PendingSign(_) => match signer_client {
+            SignerClient::Ledger(_) => {
+                info!("🖋   Please sign the transaction on your Ledger device.");
+            }
+            SignerClient::Local(_) => {
+                info!("🖋   Signing transaction.");
+            }
+        },
         WriteTransactionStatus::PendingSend(_) => {
             info!("⏳  Awaiting transaction confirmation. Please wait.");
         }
@@ -19,3 +35,71 @@ pub fn display_write_transaction_status<T: SolCall + Debug>(status: WriteTransac
         }
     }
 }
+
+/// Displays the outcome of the given simulated calls, errors if any of them reverted
+pub fn display_write_simulations(simulations: &[WriteSimulation]) -> Result<()> {
+    for simulation in simulations {
+        info!(
+            "🔎  Simulated call to {} at block {}",
+            simulation.to, simulation.block_number
+        );
+        if let Some(error) = &simulation.error {
+            info!("❌  Reverted: {}", error);
+            continue;
+        }
+        info!("⛽  Gas used: {}", simulation.gas_used);
+        for event in &simulation.events {
+            info!("📣  Event of {}: {}", event.address, event.description);
+        }
+        for delta in &simulation.vault_deltas {
+            info!(
+                "🏦  Vault {} of token {}: {} -> {}",
+                delta.vault.vault_id, delta.vault.token, delta.before, delta.after
+            );
+        }
+    }
+    if simulations.iter().any(|v| v.reverted()) {
+        return Err(anyhow!(
+            "Simulated transaction reverted, nothing was signed"
+        ));
+    }
+    Ok(())
+}
+
+/// Displays the projected fees of the simulated calls, in the native currency
+/// of the network if known, and sets the max fees of the args that are not
+/// specified to the estimated ones. Errors if the projected max cost exceeds
+/// the given max cost, fees that cannot be estimated are only warned about
+/// unless a max cost is given
+pub async fn display_fee_report(
+    transaction_args: &mut TransactionArgs,
+    simulations: &[WriteSimulation],
+    network: Option<&Network>,
+    max_cost: Option<U256>,
+) -> Result<()> {
+    let report = match FeeReport::from_simulations(transaction_args, simulations, network).await {
+        Ok(report) => report,
+        Err(e) if max_cost.is_none() => {
+            warn!("Could not estimate the transaction fees: {}", e);
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    info!(
+        "💸  Base fee: {} wei, max priority fee: {} wei, max fee: {} wei",
+        report.fees.base_fee_per_gas,
+        report.fees.max_priority_fee_per_gas,
+        report.fees.max_fee_per_gas
+    );
+    info!(
+        "💸  Projected cost of {} gas: {}, at most {}",
+        report.total_gas(),
+        report.format_cost(report.expected_cost())?,
+        report.format_cost(report.max_cost())?
+    );
+    if let Some(max_cost) = max_cost {
+        report.check_max_cost(max_cost)?;
+    }
+    report.fill_transaction_args(transaction_args);
+    Ok(())
+}
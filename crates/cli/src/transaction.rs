@@ -1,7 +1,21 @@
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{utils::parse_units, Address, U256};
 use alloy_ethers_typecast::gas_fee_middleware::GasFeeSpeed;
+use anyhow::{anyhow, Result};
 use clap::Args;
-use rain_orderbook_common::transaction::TransactionArgs;
+use rain_orderbook_app_settings::{
+    config_source::{NetworkConfigSource, RemoteNetworksConfigSource},
+    network::Network,
+    remote::chains::RemoteNetworks,
+};
+use rain_orderbook_common::{
+    fee::NATIVE_CURRENCY_DECIMALS,
+    transaction::{SignerClient, TransactionArgs, TransactionSigner},
+};
+use std::path::PathBuf;
+use tracing::warn;
+
+/// List of known chains that the network of a chain id is looked up in
+const CHAIN_LIST_URL: &str = "https://chainid.network/chains.json";
 
 #[derive(Args, Clone)]
 pub struct CliTransactionArgs {
@@ -16,6 +30,27 @@ pub struct CliTransactionArgs {
     )]
     pub derivation_index: Option<usize>,
 
+    #[arg(
+        long,
+        value_name = "ENV_VAR",
+        help = "Sign with the hex private key held by the given environment variable instead of a Ledger",
+        conflicts_with("keystore")
+    )]
+    pub private_key_env: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Sign with the given encrypted JSON keystore instead of a Ledger, its password is prompted"
+    )]
+    pub keystore: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Address that sends the transactions, to simulate them without asking the signer for it"
+    )]
+    pub from: Option<Address>,
+
     #[arg(short, long, help = "Chain ID of the network")]
     pub chain_id: Option<u64>,
 
@@ -45,20 +80,148 @@ pub struct CliTransactionArgs {
         default_value = "medium"
     )]
     pub gas_fee_speed: Option<CliGasFeeSpeed>,
+
+    #[arg(
+        long,
+        value_name = "AMOUNT",
+        value_parser = parse_native_amount,
+        help = "Refuse to send the transactions if their projected max cost exceeds the given amount of the network's native currency, eg 0.01"
+    )]
+    pub max_cost: Option<U256>,
+
+    #[arg(
+        long,
+        help = "Only simulate the transactions on a fork and report the outcome, without signing"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the calls as a Safe Transaction Builder JSON batch to the given file instead of simulating and signing them",
+        conflicts_with("dry_run")
+    )]
+    pub safe_batch: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Look up the native currency of the chain in the chainid.network list of chains for the fee report, if the command has no deployment network"
+    )]
+    pub lookup_network: bool,
 }
 
-impl From<CliTransactionArgs> for TransactionArgs {
-    fn from(val: CliTransactionArgs) -> Self {
-        TransactionArgs {
+impl TryFrom<CliTransactionArgs> for TransactionArgs {
+    type Error = anyhow::Error;
+
+    fn try_from(val: CliTransactionArgs) -> Result<Self> {
+        let signer = match (val.private_key_env, val.keystore) {
+            (Some(env_var), _) => TransactionSigner::PrivateKeyEnv { env_var },
+            // the password is only prompted once the signer is opened
+            (None, Some(path)) => TransactionSigner::Keystore {
+                path,
+                password: Default::default(),
+            },
+            (None, None) => TransactionSigner::Ledger,
+        };
+        Ok(TransactionArgs {
             orderbook_address: val.orderbook_address,
+            signer,
             derivation_index: val.derivation_index,
             chain_id: val.chain_id,
             rpc_url: val.rpc_url,
             max_priority_fee_per_gas: val.max_priority_fee_per_gas,
             max_fee_per_gas: val.max_fee_per_gas,
             gas_fee_speed: val.gas_fee_speed.map(|g| g.into()),
+        })
+    }
+}
+
+impl CliTransactionArgs {
+    /// Resolves the address that sends the transactions without unlocking
+    /// the signer, so that they can be simulated before signing or on a dry
+    /// run. Only a Ledger without a `--from` address is connected to for it
+    pub async fn try_into_sender_address(&self, tx_args: &TransactionArgs) -> Result<Address> {
+        if let Some(from) = self.from {
+            return Ok(from);
+        }
+        match tx_args.signer {
+            TransactionSigner::Ledger => {
+                Ok(tx_args.clone().try_into_signer_client().await?.address())
+            }
+            _ => Ok(tx_args.try_into_local_address()?),
         }
     }
+
+    /// Opens the signer of the given args, prompting for the keystore
+    /// password if signing with a keystore, and checks that it signs for the
+    /// sender the transactions were simulated with
+    pub async fn try_into_signer_client(
+        &self,
+        tx_args: &TransactionArgs,
+        sender: Address,
+    ) -> Result<SignerClient> {
+        let mut tx_args = tx_args.clone();
+        if let TransactionSigner::Keystore { path, password } = &mut tx_args.signer {
+            *password =
+                rpassword::prompt_password(format!("Password of keystore {}: ", path.display()))?
+                    .into();
+        }
+        let signer_client = tx_args.try_into_signer_client().await?;
+        if signer_client.address() != sender {
+            return Err(anyhow!(
+                "Signer address {} differs from the simulated sender {}",
+                signer_client.address(),
+                sender
+            ));
+        }
+        Ok(signer_client)
+    }
+
+    /// Resolves the network of the fee report, the given network of the
+    /// command's deployment if it has one on the chain, otherwise the chain
+    /// is looked up in the list of known chains if `--lookup-network` is set
+    pub async fn resolve_network(
+        &self,
+        chain_id: Option<u64>,
+        network: Option<Network>,
+    ) -> Option<Network> {
+        let chain_id = chain_id?;
+        match network.filter(|v| v.chain_id == chain_id) {
+            Some(network) => Some(network),
+            None if self.lookup_network => lookup_network(chain_id).await,
+            None => None,
+        }
+    }
+}
+
+/// Looks up the network of the given chain id in the list of known chains,
+/// eg for the native currency of commands that have no deployment. Failing
+/// lookups are only warned about
+async fn lookup_network(chain_id: u64) -> Option<Network> {
+    let source = RemoteNetworksConfigSource {
+        url: CHAIN_LIST_URL.to_string(),
+        format: "chainid".to_string(),
+    };
+    let chains = match RemoteNetworks::try_from_remote_network_config_source(source).await {
+        Ok(RemoteNetworks::ChainId(chains)) => chains,
+        Err(e) => {
+            warn!("Could not look up the network of chain {}: {}", chain_id, e);
+            return None;
+        }
+    };
+    let chain = chains.into_iter().find(|v| v.chain_id == chain_id)?;
+    let name = chain.short_name.clone();
+    NetworkConfigSource::try_from(chain)
+        .ok()?
+        .try_into_network(name)
+        .ok()
+}
+
+/// Parses a decimal amount of the native currency into wei
+fn parse_native_amount(value: &str) -> Result<U256, String> {
+    parse_units(value, NATIVE_CURRENCY_DECIMALS)
+        .map(Into::into)
+        .map_err(|e| e.to_string())
 }
 
 #[derive(clap::ValueEnum, Clone)]
@@ -1,26 +1,34 @@
 use crate::{
     rainlang::compose_to_rainlang,
-    transaction::{TransactionArgs, TransactionArgsError},
+    task::{PostTaskArgs, PostTaskArgsError},
+    transaction::{TransactionArgs, TransactionArgsError, WritableTransactionExecuteError},
 };
-use alloy::primitives::{hex::FromHexError, private::rand, Address, U256};
-use alloy::sol_types::SolCall;
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    receipt::ConfirmedTransaction,
+    simulate::{SimulationError, WriteSimulation, WriteSimulator},
+    transaction::SignerClient,
+};
+use alloy::primitives::{hex::FromHexError, keccak256, private::rand, Address, B256, U256};
+use alloy::sol_types::{SolCall, SolValue};
+#[cfg(not(target_family = "wasm"))]
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use alloy_ethers_typecast::transaction::{
     ReadContractParameters, ReadableClientError, ReadableClientHttp, WritableClientError,
 };
-#[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::transaction::{WriteTransaction, WriteTransactionStatus};
 use dotrain::error::ComposeError;
-use rain_interpreter_dispair::{DISPair, DISPairError};
-use rain_interpreter_parser::{Parser2, ParserError, ParserV2};
+use rain_interpreter_dispair::DISPairError;
+use rain_interpreter_parser::ParserError;
 use rain_metadata::{
     ContentEncoding, ContentLanguage, ContentType, Error as RainMetaError, KnownMagic,
     RainMetaDocumentV1Item,
 };
 use rain_orderbook_app_settings::deployment::Deployment;
 use rain_orderbook_bindings::{
-    IOrderBookV4::{addOrder2Call, EvaluableV3, OrderConfigV3, TaskV1, IO},
+    IOrderBookV4::{addOrder2Call, OrderConfigV3, OrderV3, IO},
     ERC20::decimalsCall,
 };
+use rain_orderbook_subgraph_client::utils::make_order_id;
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
 use std::collections::HashMap;
@@ -49,6 +57,13 @@ pub enum AddOrderArgsError {
     RainMetaError(#[from] RainMetaError),
     #[error(transparent)]
     ComposeError(#[from] ComposeError),
+    #[error(transparent)]
+    PostTaskArgsError(#[from] PostTaskArgsError),
+    #[error(transparent)]
+    WritableTransactionExecuteError(#[from] WritableTransactionExecuteError),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    SimulationError(#[from] SimulationError),
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -59,6 +74,47 @@ pub struct AddOrderArgs {
     pub outputs: Vec<IO>,
     pub deployer: Address,
     pub bindings: HashMap<String, String>,
+    /// Nonce of the order, derived from the salt if not specified
+    #[serde(default)]
+    pub nonce: Option<B256>,
+    /// Secret of the order, derived from the salt if not specified
+    #[serde(default)]
+    pub secret: Option<B256>,
+    /// Salt the nonce and secret are derived from, so that adding the same
+    /// order with the same salt results in the same order hash. Nonce and
+    /// secret that are neither specified nor derived are random
+    #[serde(default)]
+    pub salt: Option<B256>,
+}
+
+/// An order as it would be added to an orderbook by its owner, known before
+/// the add order transaction is submitted
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedOrder {
+    pub call: addOrder2Call,
+    pub order: OrderV3,
+    pub order_hash: B256,
+    /// Subgraph id of the order, see [make_order_id]
+    pub order_id: B256,
+}
+
+impl PreparedOrder {
+    pub fn new(call: addOrder2Call, owner: Address, orderbook: Address) -> Self {
+        let order = OrderV3 {
+            owner,
+            evaluable: call.config.evaluable.clone(),
+            validInputs: call.config.validInputs.clone(),
+            validOutputs: call.config.validOutputs.clone(),
+            nonce: call.config.nonce,
+        };
+        let order_hash = keccak256(order.abi_encode());
+        Self {
+            order_id: make_order_id(orderbook, order_hash.into()),
+            call,
+            order,
+            order_hash,
+        }
+    }
 }
 
 impl AddOrderArgs {
@@ -123,30 +179,12 @@ impl AddOrderArgs {
             outputs,
             deployer: deployment.scenario.deployer.address,
             bindings: deployment.scenario.bindings.to_owned(),
+            nonce: None,
+            secret: None,
+            salt: None,
         })
     }
 
-    /// Read parser address from deployer contract, then call parser to parse rainlang into bytecode and constants
-    async fn try_parse_rainlang(
-        &self,
-        rpc_url: String,
-        rainlang: String,
-    ) -> Result<Vec<u8>, AddOrderArgsError> {
-        let client = ReadableClientHttp::new_from_url(rpc_url)
-            .map_err(AddOrderArgsError::ReadableClientError)?;
-        let dispair = DISPair::from_deployer(self.deployer, client.clone())
-            .await
-            .map_err(AddOrderArgsError::DISPairError)?;
-
-        let parser: ParserV2 = dispair.clone().into();
-        let rainlang_parsed = parser
-            .parse_text(rainlang.as_str(), client)
-            .await
-            .map_err(AddOrderArgsError::ParserError)?;
-
-        Ok(rainlang_parsed.bytecode.into())
-    }
-
     /// Generate RainlangSource meta
     fn try_generate_meta(&self, rainlang: String) -> Result<Vec<u8>, AddOrderArgsError> {
         let meta_doc = RainMetaDocumentV1Item {
@@ -175,82 +213,111 @@ impl AddOrderArgs {
         Ok(res)
     }
 
-    /// Compose the addOrder2 post action
-    pub fn compose_addorder_post_task(&self) -> Result<String, AddOrderArgsError> {
-        let res = compose_to_rainlang(
-            self.dotrain.clone(),
-            self.bindings.clone(),
-            &ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS,
-        )?;
-        Ok(res)
+    /// Task args of the order's dotrain, deployer and bindings, the order
+    /// itself and its post action are parsed the same way as tasks are
+    fn to_task_args(&self) -> PostTaskArgs {
+        PostTaskArgs {
+            dotrain: self.dotrain.clone(),
+            deployer: self.deployer,
+            bindings: self.bindings.clone(),
+        }
+    }
+
+    /// Nonce and secret of the order, explicitly specified, derived from the
+    /// salt or otherwise random
+    pub fn get_nonce_and_secret(&self) -> (B256, B256) {
+        let derive = |domain: &str| {
+            self.salt
+                .map(|salt| keccak256((salt, keccak256(domain)).abi_encode()))
+                .unwrap_or_else(|| rand::random::<U256>().into())
+        };
+        (
+            self.nonce.unwrap_or_else(|| derive("nonce")),
+            self.secret.unwrap_or_else(|| derive("secret")),
+        )
     }
 
     /// Generate an addOrder call from given dotrain
     pub async fn try_into_call(&self, rpc_url: String) -> Result<addOrder2Call, AddOrderArgsError> {
         let rainlang = self.compose_to_rainlang()?;
-        let bytecode = self
-            .try_parse_rainlang(rpc_url.clone(), rainlang.clone())
-            .await?;
-
         let meta = self.try_generate_meta(rainlang)?;
 
-        let deployer = self.deployer;
-        let dispair =
-            DISPair::from_deployer(deployer, ReadableClientHttp::new_from_url(rpc_url.clone())?)
-                .await?;
-
-        // get the evaluable for the post action
-        let post_rainlang = self.compose_addorder_post_task()?;
-        let post_bytecode = self
-            .try_parse_rainlang(rpc_url.clone(), post_rainlang.clone())
+        let task_args = self.to_task_args();
+        let order_task = task_args
+            .try_into_task(rpc_url.clone(), &ORDERBOOK_ORDER_ENTRYPOINTS)
+            .await?;
+        let post_task = task_args
+            .try_into_task(rpc_url, &ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS)
             .await?;
 
-        let post_evaluable = EvaluableV3 {
-            interpreter: dispair.interpreter,
-            store: dispair.store,
-            bytecode: post_bytecode.into(),
-        };
-
-        let post_task = TaskV1 {
-            evaluable: post_evaluable,
-            signedContext: vec![],
-        };
+        let (nonce, secret) = self.get_nonce_and_secret();
 
         Ok(addOrder2Call {
             config: OrderConfigV3 {
                 validInputs: self.inputs.clone(),
                 validOutputs: self.outputs.clone(),
-                evaluable: EvaluableV3 {
-                    interpreter: dispair.interpreter,
-                    store: dispair.store,
-                    bytecode: bytecode.into(),
-                },
+                evaluable: order_task.evaluable,
                 meta: meta.into(),
-                nonce: alloy::primitives::private::rand::random::<U256>().into(),
-                secret: alloy::primitives::private::rand::random::<U256>().into(),
+                nonce,
+                secret,
             },
             tasks: vec![post_task],
         })
     }
 
+    /// Generate the addOrder call along with the order it would add for the
+    /// given owner and its hash and subgraph id. The order is only the one
+    /// that is eventually added if the nonce and secret are not random, ie
+    /// they are specified or derived from a salt
+    pub async fn try_into_prepared_order(
+        &self,
+        rpc_url: String,
+        owner: Address,
+        orderbook: Address,
+    ) -> Result<PreparedOrder, AddOrderArgsError> {
+        let call = self.try_into_call(rpc_url).await?;
+        Ok(PreparedOrder::new(call, owner, orderbook))
+    }
+
+    /// Execute the add order call, the confirmed transaction has the
+    /// `AddOrderV2` event of the added order
     #[cfg(not(target_family = "wasm"))]
     pub async fn execute<S: Fn(WriteTransactionStatus<addOrder2Call>)>(
         &self,
         transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
         transaction_status_changed: S,
-    ) -> Result<(), AddOrderArgsError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
+    ) -> Result<ConfirmedTransaction, AddOrderArgsError> {
         let add_order_call = self.try_into_call(transaction_args.clone().rpc_url).await?;
         let params = transaction_args
             .try_into_write_contract_parameters(add_order_call, transaction_args.orderbook_address)
             .await?;
 
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        let receipt = signer_client
+            .write(params, transaction_status_changed)
             .await?;
 
-        Ok(())
+        Ok(ConfirmedTransaction::from_receipt(
+            &receipt,
+            transaction_args.orderbook_address,
+        ))
+    }
+
+    /// Simulates the add order call from the signer on a fork of the latest
+    /// block without signing
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn simulate(
+        &self,
+        transaction_args: TransactionArgs,
+        from: Address,
+    ) -> Result<WriteSimulation, AddOrderArgsError> {
+        let orderbook = transaction_args.orderbook_address;
+        let mut simulator = WriteSimulator::new(&transaction_args.rpc_url, from, None).await?;
+
+        let add_order_call = self.try_into_call(transaction_args.rpc_url.clone()).await?;
+        Ok(simulator
+            .simulate(orderbook, add_order_call, orderbook, &[])
+            .await?)
     }
 
     pub async fn get_add_order_calldata(
@@ -277,6 +344,7 @@ mod tests {
     use url::Url;
 
     use super::*;
+    use rain_orderbook_bindings::IOrderBookV4::EvaluableV3;
 
     #[test]
     fn test_try_generate_meta() {
@@ -297,6 +365,9 @@ price: 2e18;
             outputs: vec![],
             bindings: HashMap::new(),
             deployer: Address::default(),
+            nonce: None,
+            secret: None,
+            salt: None,
         };
 
         let meta_bytes = args.try_generate_meta(dotrain_body).unwrap();
@@ -315,6 +386,64 @@ price: 2e18;
         );
     }
 
+    #[test]
+    fn test_get_nonce_and_secret() {
+        let mut args = AddOrderArgs {
+            dotrain: "".into(),
+            inputs: vec![],
+            outputs: vec![],
+            bindings: HashMap::new(),
+            deployer: Address::default(),
+            nonce: None,
+            secret: None,
+            salt: Some(B256::from(U256::from(1))),
+        };
+
+        let (nonce, secret) = args.get_nonce_and_secret();
+        assert_eq!(args.get_nonce_and_secret(), (nonce, secret));
+        assert_ne!(nonce, secret);
+
+        args.salt = Some(B256::from(U256::from(2)));
+        let (other_nonce, other_secret) = args.get_nonce_and_secret();
+        assert_ne!(other_nonce, nonce);
+        assert_ne!(other_secret, secret);
+
+        args.nonce = Some(B256::from(U256::from(3)));
+        args.secret = Some(B256::from(U256::from(4)));
+        assert_eq!(
+            args.get_nonce_and_secret(),
+            (B256::from(U256::from(3)), B256::from(U256::from(4)))
+        );
+    }
+
+    #[test]
+    fn test_prepared_order() {
+        let owner = Address::random();
+        let orderbook = Address::random();
+        let call = addOrder2Call {
+            config: OrderConfigV3 {
+                validInputs: vec![IO {
+                    token: Address::random(),
+                    vaultId: U256::from(1),
+                    decimals: 18,
+                }],
+                nonce: B256::from(U256::from(5)),
+                ..Default::default()
+            },
+            tasks: vec![],
+        };
+
+        let result = PreparedOrder::new(call.clone(), owner, orderbook);
+        assert_eq!(result.order.owner, owner);
+        assert_eq!(result.order.validInputs, call.config.validInputs);
+        assert_eq!(result.order.nonce, call.config.nonce);
+        assert_eq!(result.order_hash, keccak256(result.order.abi_encode()));
+        assert_eq!(
+            result.order_id,
+            make_order_id(orderbook, result.order_hash.into())
+        );
+    }
+
     #[test]
     fn test_order_config_v2_validity() {
         let inputs = vec![
@@ -393,6 +522,10 @@ price: 2e18;
             bindings: HashMap::new(),
             runs: None,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
             deployer: deployer_arc.clone(),
         };
         let token1 = Token {
@@ -486,6 +619,10 @@ _ _: 0 0;
             bindings: HashMap::new(),
             runs: None,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
             deployer: deployer_arc.clone(),
         };
         let token1 = Token {
@@ -622,6 +759,10 @@ _ _: 0 0;
             bindings: HashMap::new(),
             runs: None,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
             deployer: deployer_arc.clone(),
         };
         let token1 = Token {
@@ -687,7 +828,10 @@ _ _: 0 0;
             .await
             .unwrap();
 
-        let post_action = result.compose_addorder_post_task().unwrap();
+        let post_action = result
+            .to_task_args()
+            .compose_to_rainlang(&ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS)
+            .unwrap();
 
         assert_eq!(post_action, "/* 0. post-add-order */ \n_ _: 0 0;");
     }
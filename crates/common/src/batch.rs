@@ -0,0 +1,322 @@
+use crate::{
+    deposit::DepositArgs,
+    transaction::{TransactionArgs, TransactionArgsError, WritableTransactionExecuteError},
+    withdraw::WithdrawArgs,
+};
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    receipt::ConfirmedTransaction,
+    simulate::{SimulationError, WatchedVault, WriteSimulation, WriteSimulator},
+    transaction::SignerClient,
+};
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol_types::SolCall;
+#[cfg(not(target_family = "wasm"))]
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
+use alloy_ethers_typecast::transaction::{
+    ReadContractParametersBuilder, ReadContractParametersBuilderError, ReadableClient,
+    ReadableClientError,
+};
+use rain_orderbook_bindings::{
+    IOrderBookV4::{addOrder2Call, deposit2Call, removeOrder2Call, withdraw2Call},
+    OrderBook::multicallCall,
+    IERC20::{allowanceCall, approveCall},
+};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("Batch has no calls")]
+    EmptyBatch,
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+    #[error(transparent)]
+    ReadContractParametersBuilderError(#[from] ReadContractParametersBuilderError),
+    #[error(transparent)]
+    WritableTransactionExecuteError(#[from] WritableTransactionExecuteError),
+    #[error(transparent)]
+    TransactionArgsError(#[from] TransactionArgsError),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    SimulationError(#[from] SimulationError),
+}
+
+/// A single orderbook call of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchCall {
+    Deposit(deposit2Call),
+    Withdraw(withdraw2Call),
+    AddOrder(addOrder2Call),
+    RemoveOrder(removeOrder2Call),
+}
+
+impl BatchCall {
+    pub fn abi_encode(&self) -> Vec<u8> {
+        match self {
+            BatchCall::Deposit(call) => call.abi_encode(),
+            BatchCall::Withdraw(call) => call.abi_encode(),
+            BatchCall::AddOrder(call) => call.abi_encode(),
+            BatchCall::RemoveOrder(call) => call.abi_encode(),
+        }
+    }
+}
+
+/// ERC20 approval required by the deposits of a batch for a single token
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchApproval {
+    pub token: Address,
+    pub current_allowance: U256,
+    pub call: approveCall,
+}
+
+/// Builds a batch of orderbook calls that are executed in a single
+/// transaction through the orderbook's multicall, in the order they are added
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchArgs {
+    pub calls: Vec<BatchCall>,
+}
+
+impl BatchArgs {
+    pub fn deposit(&mut self, args: DepositArgs) -> &mut Self {
+        self.calls.push(BatchCall::Deposit(args.into()));
+        self
+    }
+
+    pub fn withdraw(&mut self, args: WithdrawArgs) -> &mut Self {
+        self.calls.push(BatchCall::Withdraw(args.into()));
+        self
+    }
+
+    pub fn add_order(&mut self, call: addOrder2Call) -> &mut Self {
+        self.calls.push(BatchCall::AddOrder(call));
+        self
+    }
+
+    pub fn remove_order(&mut self, call: removeOrder2Call) -> &mut Self {
+        self.calls.push(BatchCall::RemoveOrder(call));
+        self
+    }
+
+    /// Total amount deposited by the batch for each token
+    pub fn get_deposit_totals(&self) -> BTreeMap<Address, U256> {
+        let mut totals = BTreeMap::new();
+        for call in &self.calls {
+            if let BatchCall::Deposit(deposit) = call {
+                let total: &mut U256 = totals.entry(deposit.token).or_default();
+                *total = total.saturating_add(deposit.amount);
+            }
+        }
+        totals
+    }
+
+    /// Builds the multicall of all calls of the batch
+    pub fn get_multicall(&self) -> Result<multicallCall, BatchError> {
+        if self.calls.is_empty() {
+            return Err(BatchError::EmptyBatch);
+        }
+        Ok(multicallCall {
+            data: self
+                .calls
+                .iter()
+                .map(|call| Bytes::from(call.abi_encode()))
+                .collect(),
+        })
+    }
+
+    pub fn get_multicall_calldata(&self) -> Result<Vec<u8>, BatchError> {
+        Ok(self.get_multicall()?.abi_encode())
+    }
+
+    /// Builds a single approval per deposited token whose current allowance
+    /// of the owner for the orderbook is less than its total deposit amount
+    pub async fn get_approvals(
+        &self,
+        owner: Address,
+        transaction_args: &TransactionArgs,
+    ) -> Result<Vec<BatchApproval>, BatchError> {
+        let readable_client = ReadableClient::new_from_url(transaction_args.rpc_url.clone())?;
+        let mut approvals = vec![];
+        for (token, total) in self.get_deposit_totals() {
+            let parameters = ReadContractParametersBuilder::<allowanceCall>::default()
+                .address(token)
+                .call(allowanceCall {
+                    owner,
+                    spender: transaction_args.orderbook_address,
+                })
+                .build()?;
+            let current_allowance = readable_client.read(parameters).await?._0;
+            if current_allowance < total {
+                approvals.push(BatchApproval {
+                    token,
+                    current_allowance,
+                    // approve sets rather than increases the allowance
+                    call: approveCall {
+                        spender: transaction_args.orderbook_address,
+                        amount: total,
+                    },
+                });
+            }
+        }
+        Ok(approvals)
+    }
+
+    /// Executes the approvals required by the deposits of the batch, one
+    /// transaction per token
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn execute_approvals<S: Fn(WriteTransactionStatus<approveCall>)>(
+        &self,
+        transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
+        transaction_status_changed: S,
+    ) -> Result<(), BatchError> {
+        for approval in self
+            .get_approvals(signer_client.address(), &transaction_args)
+            .await?
+        {
+            let params = transaction_args
+                .try_into_write_contract_parameters(approval.call, approval.token)
+                .await?;
+            signer_client
+                .write(params, &transaction_status_changed)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Executes all calls of the batch in a single multicall transaction
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn execute<S: Fn(WriteTransactionStatus<multicallCall>)>(
+        &self,
+        transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
+        transaction_status_changed: S,
+    ) -> Result<ConfirmedTransaction, BatchError> {
+        let params = transaction_args
+            .try_into_write_contract_parameters(
+                self.get_multicall()?,
+                transaction_args.orderbook_address,
+            )
+            .await?;
+        let receipt = signer_client
+            .write(params, transaction_status_changed)
+            .await?;
+
+        Ok(ConfirmedTransaction::from_receipt(
+            &receipt,
+            transaction_args.orderbook_address,
+        ))
+    }
+
+    /// Simulates the approvals and the multicall from the signer on a fork of
+    /// the latest block without signing, reporting the balance changes of
+    /// every vault the batch deposits into or withdraws from
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn simulate(
+        &self,
+        transaction_args: TransactionArgs,
+        from: Address,
+    ) -> Result<Vec<WriteSimulation>, BatchError> {
+        let orderbook = transaction_args.orderbook_address;
+        let approvals = self.get_approvals(from, &transaction_args).await?;
+        let mut simulator = WriteSimulator::new(&transaction_args.rpc_url, from, None).await?;
+
+        let mut simulations = vec![];
+        for approval in approvals {
+            simulations.push(
+                simulator
+                    .simulate(approval.token, approval.call, orderbook, &[])
+                    .await?,
+            );
+        }
+
+        let mut vaults = vec![];
+        for call in &self.calls {
+            let vault = match call {
+                BatchCall::Deposit(v) => (v.token, v.vaultId),
+                BatchCall::Withdraw(v) => (v.token, v.vaultId),
+                _ => continue,
+            };
+            let vault = WatchedVault {
+                owner: from,
+                token: vault.0,
+                vault_id: vault.1,
+            };
+            if !vaults.contains(&vault) {
+                vaults.push(vault);
+            }
+        }
+        simulations.push(
+            simulator
+                .simulate(orderbook, self.get_multicall()?, orderbook, &vaults)
+                .await?,
+        );
+
+        Ok(simulations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_multicall() {
+        let token_a = Address::random();
+        let token_b = Address::random();
+        let mut batch = BatchArgs::default();
+        assert!(matches!(batch.get_multicall(), Err(BatchError::EmptyBatch)));
+
+        batch
+            .deposit(DepositArgs {
+                token: token_a,
+                vault_id: U256::from(1),
+                amount: U256::from(10),
+                tasks: vec![],
+            })
+            .withdraw(WithdrawArgs {
+                token: token_b,
+                vault_id: U256::from(2),
+                target_amount: U256::from(5),
+                tasks: vec![],
+            })
+            .deposit(DepositArgs {
+                token: token_a,
+                vault_id: U256::from(3),
+                amount: U256::from(20),
+                tasks: vec![],
+            });
+
+        let result = batch.get_multicall().unwrap();
+        assert_eq!(result.data.len(), 3);
+        assert_eq!(
+            result.data[0],
+            Bytes::from(
+                deposit2Call {
+                    token: token_a,
+                    vaultId: U256::from(1),
+                    amount: U256::from(10),
+                    tasks: vec![],
+                }
+                .abi_encode()
+            )
+        );
+        assert_eq!(
+            result.data[1],
+            Bytes::from(
+                withdraw2Call {
+                    token: token_b,
+                    vaultId: U256::from(2),
+                    targetAmount: U256::from(5),
+                    tasks: vec![],
+                }
+                .abi_encode()
+            )
+        );
+
+        assert_eq!(
+            batch.get_deposit_totals(),
+            BTreeMap::from([(token_a, U256::from(30))])
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol_types::SolCall;
+use alloy_ethers_typecast::transaction::{ReadableClientError, ReadableClientHttp};
+use rain_interpreter_eval::{
+    error::ForkCallError,
+    fork::{Forker, NewForkedEvm},
+};
+use rain_orderbook_bindings::IOrderBookV4::{clear2Call, vaultBalanceCall, ClearConfig};
+use rain_orderbook_quote::{
+    error::Error as QuoteError, quote_token_pair, QuoteResult, QuoteTarget,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClearError {
+    #[error(transparent)]
+    QuoteError(#[from] QuoteError),
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+    #[error(transparent)]
+    ForkCallError(#[from] ForkCallError),
+}
+
+/// Specifies the token pair to scan an orderbook for clears and the
+/// clearer that would collect the bounties
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClearScanArgs {
+    pub orderbook: Address,
+    pub token_a: Address,
+    pub token_b: Address,
+    /// Account that would call clear2 and receive the bounties
+    pub clearer: Address,
+    /// Clearer's vault receiving the bounty of alice's output token
+    pub alice_bounty_vault_id: U256,
+    /// Clearer's vault receiving the bounty of bob's output token
+    pub bob_bounty_vault_id: U256,
+    /// Block number to scan at, latest block if not specified
+    pub block_number: Option<u64>,
+    pub multicall_address: Option<Address>,
+}
+
+/// Two quoted orders whose ratios cross so they can be cleared against each
+/// other, with the amounts orderbook would move and the resulting bounties.
+/// Amounts are 18 decimals fixed point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearOpportunity {
+    pub alice: QuoteTarget,
+    pub bob: QuoteTarget,
+    pub alice_ratio: U256,
+    pub bob_ratio: U256,
+    pub alice_output: U256,
+    pub alice_input: U256,
+    pub bob_output: U256,
+    pub bob_input: U256,
+    /// Bounty in alice's output token
+    pub alice_bounty: U256,
+    /// Bounty in bob's output token
+    pub bob_bounty: U256,
+    pub config: ClearConfig,
+    pub calldata: Bytes,
+}
+
+/// Result of clearing an opportunity on a fork, bounties are the actual
+/// changes of the clearer's bounty vaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearSimulation {
+    pub block_number: u64,
+    pub alice_bounty: U256,
+    pub bob_bounty: U256,
+    pub error: Option<String>,
+}
+
+/// Finds the crossing pairs between alice orders, that sell token B for
+/// token A, and bob orders, that sell token A for token B, from their quotes.
+/// Clear amounts are calculated the same way orderbook's `clear2` does, ie
+/// each side outputs at most what the other side can take at its ratio.
+/// Results are sorted by how deep the ratios cross, deepest first
+pub fn find_clears(
+    alice_targets: &[QuoteTarget],
+    alice_results: &[QuoteResult],
+    bob_targets: &[QuoteTarget],
+    bob_results: &[QuoteResult],
+    alice_bounty_vault_id: U256,
+    bob_bounty_vault_id: U256,
+) -> Vec<ClearOpportunity> {
+    let one = U256::from(10).pow(U256::from(18));
+    let mut clears = vec![];
+    for (alice, alice_result) in alice_targets.iter().zip(alice_results) {
+        let Ok(alice_quote) = alice_result else {
+            continue;
+        };
+        for (bob, bob_result) in bob_targets.iter().zip(bob_results) {
+            let Ok(bob_quote) = bob_result else {
+                continue;
+            };
+            // orderbook doesnt clear an owner's orders against each other
+            if alice.quote_config.order.owner == bob.quote_config.order.owner {
+                continue;
+            }
+            // ratios cross when the product of them is at most one
+            if alice_quote.ratio.saturating_mul(bob_quote.ratio) > one * one {
+                continue;
+            }
+
+            let alice_output =
+                alice_quote
+                    .max_output
+                    .min(mul_up(bob_quote.max_output, bob_quote.ratio, one));
+            let bob_output =
+                bob_quote
+                    .max_output
+                    .min(mul_up(alice_quote.max_output, alice_quote.ratio, one));
+            if alice_output.is_zero() || bob_output.is_zero() {
+                continue;
+            }
+            let alice_input = mul_up(alice_output, alice_quote.ratio, one);
+            let bob_input = mul_up(bob_output, bob_quote.ratio, one);
+
+            let config = ClearConfig {
+                aliceInputIOIndex: alice.quote_config.inputIOIndex,
+                aliceOutputIOIndex: alice.quote_config.outputIOIndex,
+                bobInputIOIndex: bob.quote_config.inputIOIndex,
+                bobOutputIOIndex: bob.quote_config.outputIOIndex,
+                aliceBountyVaultId: alice_bounty_vault_id,
+                bobBountyVaultId: bob_bounty_vault_id,
+            };
+            let calldata = clear2Call {
+                alice: alice.quote_config.order.clone(),
+                bob: bob.quote_config.order.clone(),
+                clearConfig: config.clone(),
+                aliceSignedContext: alice.quote_config.signedContext.clone(),
+                bobSignedContext: bob.quote_config.signedContext.clone(),
+            }
+            .abi_encode()
+            .into();
+
+            clears.push(ClearOpportunity {
+                alice: alice.clone(),
+                bob: bob.clone(),
+                alice_ratio: alice_quote.ratio,
+                bob_ratio: bob_quote.ratio,
+                alice_output,
+                alice_input,
+                bob_output,
+                bob_input,
+                alice_bounty: alice_output.saturating_sub(bob_input),
+                bob_bounty: bob_output.saturating_sub(alice_input),
+                config,
+                calldata,
+            });
+        }
+    }
+    clears.sort_by_key(|v| v.alice_ratio.saturating_mul(v.bob_ratio));
+    clears
+}
+
+/// A clear opportunity along with its result on a fork
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedClear {
+    pub opportunity: ClearOpportunity,
+    pub simulation: ClearSimulation,
+}
+
+/// Pulls the active orders of both directions of the token pair from the
+/// subgraph, quotes them in batch, finds the crossing pairs and simulates
+/// clearing each of them from the clearer on a fork of the same block
+pub async fn scan_clears(
+    subgraph_url: &str,
+    rpc_url: &str,
+    args: &ClearScanArgs,
+) -> Result<Vec<ScannedClear>, ClearError> {
+    let block_number = match args.block_number {
+        Some(v) => v,
+        None => {
+            ReadableClientHttp::new_from_url(rpc_url.to_string())?
+                .get_block_number()
+                .await?
+        }
+    };
+    let (alice_targets, alice_results) = quote_token_pair(
+        subgraph_url,
+        rpc_url,
+        args.orderbook,
+        args.token_a,
+        args.token_b,
+        Some(block_number),
+        args.multicall_address,
+    )
+    .await?;
+    let (bob_targets, bob_results) = quote_token_pair(
+        subgraph_url,
+        rpc_url,
+        args.orderbook,
+        args.token_b,
+        args.token_a,
+        Some(block_number),
+        args.multicall_address,
+    )
+    .await?;
+
+    let opportunities = find_clears(
+        &alice_targets,
+        &alice_results,
+        &bob_targets,
+        &bob_results,
+        args.alice_bounty_vault_id,
+        args.bob_bounty_vault_id,
+    );
+    let mut scanned = vec![];
+    for opportunity in opportunities {
+        let simulation = opportunity
+            .simulate(rpc_url, args.orderbook, args.clearer, Some(block_number))
+            .await?;
+        scanned.push(ScannedClear {
+            opportunity,
+            simulation,
+        });
+    }
+    Ok(scanned)
+}
+
+impl ClearOpportunity {
+    /// Calls `clear2` from the clearer on a fork and measures the bounties
+    /// by the change of the clearer's bounty vault balances.
+    /// A reverting clear is reported in the simulation's error
+    pub async fn simulate(
+        &self,
+        rpc_url: &str,
+        orderbook: Address,
+        clearer: Address,
+        block_number: Option<u64>,
+    ) -> Result<ClearSimulation, ClearError> {
+        let block_number = match block_number {
+            Some(v) => v,
+            None => {
+                ReadableClientHttp::new_from_url(rpc_url.to_string())?
+                    .get_block_number()
+                    .await?
+            }
+        };
+        let mut forker = Forker::new();
+        forker
+            .add_or_select(
+                NewForkedEvm {
+                    fork_url: rpc_url.to_string(),
+                    fork_block_number: Some(block_number),
+                },
+                None,
+            )
+            .await?;
+
+        let alice_output_token = self.alice_output_token();
+        let bob_output_token = self.bob_output_token();
+        let alice_bounty_before = vault_balance(
+            &forker,
+            orderbook,
+            clearer,
+            alice_output_token,
+            self.config.aliceBountyVaultId,
+        )
+        .await?;
+        let bob_bounty_before = vault_balance(
+            &forker,
+            orderbook,
+            clearer,
+            bob_output_token,
+            self.config.bobBountyVaultId,
+        )
+        .await?;
+
+        let call = clear2Call {
+            alice: self.alice.quote_config.order.clone(),
+            bob: self.bob.quote_config.order.clone(),
+            clearConfig: self.config.clone(),
+            aliceSignedContext: self.alice.quote_config.signedContext.clone(),
+            bobSignedContext: self.bob.quote_config.signedContext.clone(),
+        };
+        if let Err(e) = forker.alloy_call_committing(clearer, orderbook, call, U256::ZERO, true) {
+            return Ok(ClearSimulation {
+                block_number,
+                alice_bounty: U256::ZERO,
+                bob_bounty: U256::ZERO,
+                error: Some(e.to_string()),
+            });
+        }
+
+        let alice_bounty_after = vault_balance(
+            &forker,
+            orderbook,
+            clearer,
+            alice_output_token,
+            self.config.aliceBountyVaultId,
+        )
+        .await?;
+        let bob_bounty_after = vault_balance(
+            &forker,
+            orderbook,
+            clearer,
+            bob_output_token,
+            self.config.bobBountyVaultId,
+        )
+        .await?;
+
+        Ok(ClearSimulation {
+            block_number,
+            alice_bounty: alice_bounty_after.saturating_sub(alice_bounty_before),
+            bob_bounty: bob_bounty_after.saturating_sub(bob_bounty_before),
+            error: None,
+        })
+    }
+
+    fn alice_output_token(&self) -> Address {
+        let config = &self.alice.quote_config;
+        config.order.validOutputs[config.outputIOIndex.to::<usize>()].token
+    }
+
+    fn bob_output_token(&self) -> Address {
+        let config = &self.bob.quote_config;
+        config.order.validOutputs[config.outputIOIndex.to::<usize>()].token
+    }
+}
+
+async fn vault_balance(
+    forker: &Forker,
+    orderbook: Address,
+    owner: Address,
+    token: Address,
+    vault_id: U256,
+) -> Result<U256, ClearError> {
+    Ok(forker
+        .alloy_call(
+            Address::ZERO,
+            orderbook,
+            vaultBalanceCall {
+                owner,
+                token,
+                vaultId: vault_id,
+            },
+            true,
+        )
+        .await?
+        .typed_return
+        ._0)
+}
+
+fn mul_up(a: U256, b: U256, one: U256) -> U256 {
+    let product = a.saturating_mul(b);
+    let result = product / one;
+    if (product % one).is_zero() {
+        result
+    } else {
+        result + U256::from(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote, IO};
+    use rain_orderbook_quote::{error::FailedQuote, OrderQuoteValue};
+
+    fn target(owner: Address, input_token: Address, output_token: Address) -> QuoteTarget {
+        QuoteTarget {
+            orderbook: Address::ZERO,
+            quote_config: Quote {
+                order: OrderV3 {
+                    owner,
+                    validInputs: vec![IO {
+                        token: input_token,
+                        ..Default::default()
+                    }],
+                    validOutputs: vec![IO {
+                        token: output_token,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    fn quote(max_output: u64, ratio_percent: u64) -> QuoteResult {
+        let one = U256::from(10).pow(U256::from(18));
+        Ok(OrderQuoteValue {
+            max_output: U256::from(max_output) * one,
+            ratio: U256::from(ratio_percent) * one / U256::from(100),
+        })
+    }
+
+    #[test]
+    fn test_find_clears() {
+        let token_a = Address::random();
+        let token_b = Address::random();
+        let alice_owner = Address::random();
+        let bob_owner = Address::random();
+        let one = U256::from(10).pow(U256::from(18));
+
+        // alice sells 10 B for A at 0.5 A per B
+        let alice_targets = vec![
+            target(alice_owner, token_a, token_b),
+            target(alice_owner, token_a, token_b),
+        ];
+        let alice_results = vec![quote(10, 50), Err(FailedQuote::NonExistent)];
+        // bob sells 4 A for B at 1.6 B per A, crossing alice, and a second
+        // bob order at 2.5 B per A that doesnt cross, and an order of alice's
+        // owner that is skipped
+        let bob_targets = vec![
+            target(bob_owner, token_b, token_a),
+            target(bob_owner, token_b, token_a),
+            target(alice_owner, token_b, token_a),
+        ];
+        let bob_results = vec![quote(4, 160), quote(4, 250), quote(4, 100)];
+
+        let result = find_clears(
+            &alice_targets,
+            &alice_results,
+            &bob_targets,
+            &bob_results,
+            U256::from(1),
+            U256::from(2),
+        );
+
+        assert_eq!(result.len(), 1);
+        let clear = &result[0];
+        assert_eq!(clear.bob, bob_targets[0]);
+        // bob can take at most 4 * 1.6 = 6.4 B
+        assert_eq!(clear.alice_output, U256::from(64) * one / U256::from(10));
+        assert_eq!(clear.alice_input, U256::from(32) * one / U256::from(10));
+        // alice can take at most 10 * 0.5 = 5 A but bob only has 4
+        assert_eq!(clear.bob_output, U256::from(4) * one);
+        assert_eq!(clear.bob_input, U256::from(64) * one / U256::from(10));
+        assert_eq!(clear.alice_bounty, U256::ZERO);
+        assert_eq!(clear.bob_bounty, U256::from(8) * one / U256::from(10));
+        assert_eq!(clear.config.aliceBountyVaultId, U256::from(1));
+        assert_eq!(clear.config.bobBountyVaultId, U256::from(2));
+        assert_eq!(
+            clear2Call::abi_decode(&clear.calldata, true).unwrap().bob,
+            bob_targets[0].quote_config.order
+        );
+    }
+}
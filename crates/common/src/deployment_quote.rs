@@ -0,0 +1,275 @@
+use crate::{
+    add_order::{AddOrderArgs, AddOrderArgsError},
+    order_context::{qualify_namespace, OrderContext, OrderContextError},
+};
+use alloy::primitives::{Address, U256};
+use alloy_ethers_typecast::transaction::{ReadableClientError, ReadableClientHttp};
+use rain_interpreter_eval::{
+    error::ForkCallError,
+    eval::ForkEvalArgs,
+    fork::{Forker, NewForkedEvm},
+    trace::RainEvalResult,
+};
+use rain_orderbook_quote::OrderQuoteValue;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DeploymentQuoteError {
+    #[error(transparent)]
+    AddOrderArgsError(#[from] AddOrderArgsError),
+    #[error(transparent)]
+    ForkCallError(#[from] ForkCallError),
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+    #[error(transparent)]
+    OrderContextError(#[from] OrderContextError),
+}
+
+/// Hypothetical state a not yet deployed order is quoted against
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeploymentQuoteArgs {
+    /// Orderbook the order would be added to
+    pub orderbook: Address,
+    /// Owner of the order, ie the account that would add it
+    pub owner: Address,
+    /// Counterparty of the quote, ie the taker
+    pub counterparty: Address,
+    /// Hypothetical vault balances keyed by token address in token decimals,
+    /// vaults of tokens that are not specified are treated as empty, as they
+    /// would be for a newly added order
+    pub vault_balances: HashMap<Address, U256>,
+    /// Block number to fork at, latest block if not specified
+    pub block_number: Option<u64>,
+}
+
+/// Quote of a single IO pair of a not yet deployed order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentQuote {
+    pub input_io_index: usize,
+    pub output_io_index: usize,
+    pub input_token: Address,
+    pub output_token: Address,
+    pub quote: Option<OrderQuoteValue>,
+    pub error: Option<String>,
+    /// Tokens of the pair whose vault balance wasnt specified and so was
+    /// quoted as empty
+    pub missing_vault_balances: Vec<Address>,
+}
+
+impl AddOrderArgs {
+    /// Builds the order as it would be added to the orderbook and quotes each
+    /// of its IO pairs on a fork with the given hypothetical vault balances.
+    /// calculate-io is evaluated with the context and namespace orderbook
+    /// would use and the resulting max output is capped by the output vault
+    /// balance, the same way orderbook's `quote` does
+    pub async fn quote_on_fork(
+        &self,
+        rpc_url: String,
+        args: DeploymentQuoteArgs,
+    ) -> Result<Vec<DeploymentQuote>, DeploymentQuoteError> {
+        let order = self
+            .try_into_prepared_order(rpc_url.clone(), args.owner, args.orderbook)
+            .await?
+            .order;
+        let rainlang_string = self.compose_to_rainlang()?;
+
+        let block_number = match args.block_number {
+            Some(v) => v,
+            None => {
+                ReadableClientHttp::new_from_url(rpc_url.clone())?
+                    .get_block_number()
+                    .await?
+            }
+        };
+        let mut forker = Forker::new();
+        forker
+            .add_or_select(
+                NewForkedEvm {
+                    fork_url: rpc_url,
+                    fork_block_number: Some(block_number),
+                },
+                None,
+            )
+            .await?;
+
+        let namespace = qualify_namespace(args.owner, args.orderbook);
+
+        let mut quotes = vec![];
+        for (input_io_index, input) in order.validInputs.iter().enumerate() {
+            for (output_io_index, output) in order.validOutputs.iter().enumerate() {
+                // orderbook doesnt allow trading a token for itself
+                if input.token == output.token {
+                    continue;
+                }
+                let missing_vault_balances = [input.token, output.token]
+                    .into_iter()
+                    .filter(|v| !args.vault_balances.contains_key(v))
+                    .collect::<Vec<_>>();
+                let order_context = OrderContext {
+                    order: order.clone(),
+                    input_io_index,
+                    output_io_index,
+                    orderbook: args.orderbook,
+                    counterparty: args.counterparty,
+                    input_vault_balance: args
+                        .vault_balances
+                        .get(&input.token)
+                        .copied()
+                        .unwrap_or_default(),
+                    output_vault_balance: args
+                        .vault_balances
+                        .get(&output.token)
+                        .copied()
+                        .unwrap_or_default(),
+                    signed_context: vec![],
+                };
+                let eval_args = ForkEvalArgs {
+                    rainlang_string: rainlang_string.clone(),
+                    // calculate-io
+                    source_index: 0,
+                    deployer: self.deployer,
+                    namespace,
+                    context: order_context.build()?,
+                    decode_errors: true,
+                };
+                let (quote, error) = match forker.fork_eval(eval_args).await {
+                    Ok(res) => {
+                        let result: RainEvalResult = res.into();
+                        match (result.stack.first(), result.stack.get(1)) {
+                            (Some(ratio), Some(max_output)) => (
+                                Some(OrderQuoteValue {
+                                    ratio: *ratio,
+                                    max_output: order_context.cap_max_output(*max_output),
+                                }),
+                                None,
+                            ),
+                            _ => (
+                                None,
+                                Some(format!(
+                                    "calculate-io has {} outputs, expected at least 2",
+                                    result.stack.len()
+                                )),
+                            ),
+                        }
+                    }
+                    Err(e) => (None, Some(e.to_string())),
+                };
+                quotes.push(DeploymentQuote {
+                    input_io_index,
+                    output_io_index,
+                    input_token: input.token,
+                    output_token: output.token,
+                    quote,
+                    error,
+                    missing_vault_balances,
+                });
+            }
+        }
+        Ok(quotes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_orderbook_bindings::IOrderBookV4::IO;
+    use rain_orderbook_env::CI_DEPLOY_POLYGON_RPC_URL;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_quote_on_fork() {
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let args = AddOrderArgs {
+            dotrain: r#"
+some front matter
+---
+#calculate-io
+max-output: 100e18,
+io-ratio: 2e18;
+#handle-io
+:;
+#post-add-order
+:;
+"#
+            .to_string(),
+            inputs: vec![
+                IO {
+                    token: input_token,
+                    vaultId: U256::from(1),
+                    decimals: 18,
+                },
+                IO {
+                    token: output_token,
+                    vaultId: U256::from(1),
+                    decimals: 18,
+                },
+            ],
+            outputs: vec![IO {
+                token: output_token,
+                vaultId: U256::from(2),
+                decimals: 6,
+            }],
+            deployer: "0xF14E09601A47552De6aBd3A0B165607FaFd2B5Ba"
+                .parse::<Address>()
+                .unwrap(),
+            bindings: HashMap::new(),
+            nonce: None,
+            secret: None,
+            salt: None,
+        };
+
+        let result = args
+            .quote_on_fork(
+                CI_DEPLOY_POLYGON_RPC_URL.to_string(),
+                DeploymentQuoteArgs {
+                    orderbook: Address::random(),
+                    owner: Address::random(),
+                    // 50 units of a 6 decimals token
+                    vault_balances: HashMap::from([(output_token, U256::from(50_000_000))]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // the self trading pair is skipped
+        assert_eq!(result.len(), 1);
+        let one = U256::from(10).pow(U256::from(18));
+        assert_eq!(
+            result[0],
+            DeploymentQuote {
+                input_io_index: 0,
+                output_io_index: 0,
+                input_token,
+                output_token,
+                quote: Some(OrderQuoteValue {
+                    max_output: one * U256::from(50),
+                    ratio: one * U256::from(2),
+                }),
+                error: None,
+                missing_vault_balances: vec![input_token],
+            }
+        );
+
+        // an unspecified output vault is empty so nothing can be taken
+        let result = args
+            .quote_on_fork(
+                CI_DEPLOY_POLYGON_RPC_URL.to_string(),
+                DeploymentQuoteArgs {
+                    orderbook: Address::random(),
+                    owner: Address::random(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result[0].quote.unwrap().max_output, U256::ZERO);
+        assert_eq!(
+            result[0].missing_vault_balances,
+            vec![input_token, output_token]
+        );
+    }
+}
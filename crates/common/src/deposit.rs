@@ -1,17 +1,23 @@
-use crate::transaction::{TransactionArgs, TransactionArgsError, WritableTransactionExecuteError};
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    receipt::ConfirmedTransaction,
+    simulate::{SimulationError, WatchedVault, WriteSimulation, WriteSimulator},
+    transaction::SignerClient,
+};
+use crate::{
+    task::{PostTaskArgs, PostTaskArgsError, ORDERBOOK_DEPOSIT_POST_TASK_ENTRYPOINTS},
+    transaction::{TransactionArgs, TransactionArgsError, WritableTransactionExecuteError},
+};
 use alloy::primitives::{Address, U256};
 use alloy::sol_types::SolCall;
+#[cfg(not(target_family = "wasm"))]
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use alloy_ethers_typecast::transaction::{
     ReadContractParametersBuilder, ReadContractParametersBuilderError, ReadableClient,
     ReadableClientError, WritableClientError,
 };
-#[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::{
-    ethers_address_to_alloy,
-    transaction::{WriteTransaction, WriteTransactionStatus},
-};
 use rain_orderbook_bindings::{
-    IOrderBookV4::deposit2Call,
+    IOrderBookV4::{deposit2Call, TaskV1},
     IERC20::{allowanceCall, approveCall},
 };
 use serde::{Deserialize, Serialize};
@@ -33,6 +39,10 @@ pub enum DepositError {
 
     #[error(transparent)]
     TransactionArgsError(#[from] TransactionArgsError),
+
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    SimulationError(#[from] SimulationError),
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -40,6 +50,10 @@ pub struct DepositArgs {
     pub token: Address,
     pub vault_id: U256,
     pub amount: U256,
+    /// Tasks the orderbook evaluates after the deposit, eg the composed
+    /// `post-deposit` entrypoint of a dotrain
+    #[serde(default)]
+    pub tasks: Vec<TaskV1>,
 }
 
 impl From<DepositArgs> for deposit2Call {
@@ -48,12 +62,26 @@ impl From<DepositArgs> for deposit2Call {
             token: val.token,
             vaultId: val.vault_id,
             amount: val.amount,
-            tasks: vec![],
+            tasks: val.tasks,
         }
     }
 }
 
 impl DepositArgs {
+    /// Compose the `post-deposit` entrypoint of the post task dotrain and add it
+    /// to the tasks of the deposit
+    pub async fn try_add_post_task(
+        &mut self,
+        post_task: &PostTaskArgs,
+        rpc_url: String,
+    ) -> Result<(), PostTaskArgsError> {
+        let task = post_task
+            .try_into_task(rpc_url, &ORDERBOOK_DEPOSIT_POST_TASK_ENTRYPOINTS)
+            .await?;
+        self.tasks.push(task);
+        Ok(())
+    }
+
     /// Execute read IERC20 allowance call
     pub async fn read_allowance(
         &self,
@@ -78,16 +106,12 @@ impl DepositArgs {
     pub async fn execute_approve<S: Fn(WriteTransactionStatus<approveCall>)>(
         &self,
         transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
         transaction_status_changed: S,
     ) -> Result<(), DepositError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
         // Check allowance already granted for this token and contract
         let current_allowance = self
-            .read_allowance(
-                ethers_address_to_alloy(ledger_client.client.address()),
-                transaction_args.clone(),
-            )
+            .read_allowance(signer_client.address(), transaction_args.clone())
             .await?;
 
         // If more allowance is required, then call approve for the difference
@@ -100,8 +124,8 @@ impl DepositArgs {
                 .try_into_write_contract_parameters(approve_call, self.token)
                 .await?;
 
-            WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-                .execute()
+            signer_client
+                .write(params, transaction_status_changed)
                 .await?;
         }
 
@@ -120,25 +144,77 @@ impl DepositArgs {
         Ok(approve_call.abi_encode())
     }
 
-    /// Execute OrderbookV3 deposit call
+    /// Execute OrderbookV3 deposit call, the confirmed transaction has the
+    /// `Deposit` event
     #[cfg(not(target_family = "wasm"))]
     pub async fn execute_deposit<S: Fn(WriteTransactionStatus<deposit2Call>)>(
         &self,
         transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
         transaction_status_changed: S,
-    ) -> Result<(), DepositError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
+    ) -> Result<ConfirmedTransaction, DepositError> {
         let deposit_call: deposit2Call = self.clone().into();
         let params = transaction_args
             .try_into_write_contract_parameters(deposit_call, transaction_args.orderbook_address)
             .await?;
 
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        let receipt = signer_client
+            .write(params, transaction_status_changed)
             .await?;
 
-        Ok(())
+        Ok(ConfirmedTransaction::from_receipt(
+            &receipt,
+            transaction_args.orderbook_address,
+        ))
+    }
+
+    /// Simulates the approve, if more allowance is required, and the deposit
+    /// calls from the signer on a fork of the latest block without signing
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn simulate(
+        &self,
+        transaction_args: TransactionArgs,
+        from: Address,
+    ) -> Result<Vec<WriteSimulation>, DepositError> {
+        let orderbook = transaction_args.orderbook_address;
+        let mut simulator = WriteSimulator::new(&transaction_args.rpc_url, from, None).await?;
+
+        let mut simulations = vec![];
+        let current_allowance = simulator
+            .read(
+                self.token,
+                allowanceCall {
+                    owner: simulator.from(),
+                    spender: orderbook,
+                },
+            )
+            .await?
+            ._0;
+        if current_allowance < self.amount {
+            let approve_call = approveCall {
+                spender: orderbook,
+                amount: self.amount - current_allowance,
+            };
+            simulations.push(
+                simulator
+                    .simulate(self.token, approve_call, orderbook, &[])
+                    .await?,
+            );
+        }
+
+        let deposit_call: deposit2Call = self.clone().into();
+        let vault = WatchedVault {
+            owner: simulator.from(),
+            token: self.token,
+            vault_id: self.vault_id,
+        };
+        simulations.push(
+            simulator
+                .simulate(orderbook, deposit_call, orderbook, &[vault])
+                .await?,
+        );
+
+        Ok(simulations)
     }
 
     pub async fn get_deposit_calldata(&self) -> Result<Vec<u8>, WritableTransactionExecuteError> {
@@ -160,6 +236,7 @@ mod tests {
                 .unwrap(),
             vault_id: U256::from(42),
             amount: U256::from(100),
+            tasks: vec![TaskV1::default()],
         };
 
         let deposit_call: deposit2Call = args.into();
@@ -172,5 +249,6 @@ mod tests {
         );
         assert_eq!(deposit_call.vaultId, U256::from(42));
         assert_eq!(deposit_call.amount, U256::from(100));
+        assert_eq!(deposit_call.tasks, vec![TaskV1::default()]);
     }
 }
@@ -0,0 +1,287 @@
+use crate::{simulate::WriteSimulation, transaction::TransactionArgs};
+use alloy::primitives::{
+    utils::{format_units, UnitsError},
+    U256,
+};
+use alloy_ethers_typecast::gas_fee_middleware::GasFeeSpeed;
+use ethers::{
+    providers::{Http, Middleware, Provider, ProviderError},
+    types::{
+        transaction::eip2718::TypedTransaction, BlockNumber, Eip1559TransactionRequest, FeeHistory,
+        H160,
+    },
+};
+use rain_orderbook_app_settings::network::Network;
+use thiserror::Error;
+
+/// Number of latest blocks whose fees are considered
+pub static FEE_HISTORY_BLOCK_COUNT: u64 = 10;
+
+/// Decimals of the native currency of all EVM networks
+pub static NATIVE_CURRENCY_DECIMALS: u8 = 18;
+
+#[derive(Error, Debug)]
+pub enum FeeEstimateError {
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+    #[error(transparent)]
+    UnitsError(#[from] UnitsError),
+    #[error("Fee history has no base fee, the network does not support EIP-1559")]
+    MissingBaseFee,
+    #[error("Projected max cost of {cost} exceeds the max cost of {max_cost}")]
+    ExceedsMaxCost { cost: String, max_cost: String },
+}
+
+/// Reward percentile of the fee history blocks used as the priority fee of
+/// the given speed
+pub fn reward_percentile(speed: &GasFeeSpeed) -> f64 {
+    match speed {
+        GasFeeSpeed::Slow => 10.0,
+        GasFeeSpeed::Medium => 25.0,
+        GasFeeSpeed::Fast => 50.0,
+        GasFeeSpeed::Fastest => 90.0,
+    }
+}
+
+/// EIP-1559 fees per gas of the next block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasFees {
+    pub base_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+}
+
+impl GasFees {
+    /// Fees of the next block from the fee history, the priority fee is the
+    /// mean reward of the requested percentile over the history and the max
+    /// fee leaves room for the base fee to double. Specified fees take
+    /// precedence over the estimated ones
+    pub fn from_fee_history(
+        history: &FeeHistory,
+        max_priority_fee_per_gas: Option<U256>,
+        max_fee_per_gas: Option<U256>,
+    ) -> Result<Self, FeeEstimateError> {
+        // the last base fee of the history is the one of the next block
+        let base_fee_per_gas = history
+            .base_fee_per_gas
+            .last()
+            .map(|v| U256::from_limbs(v.0))
+            .ok_or(FeeEstimateError::MissingBaseFee)?;
+
+        let rewards: Vec<U256> = history
+            .reward
+            .iter()
+            .filter_map(|v| v.first().map(|v| U256::from_limbs(v.0)))
+            .collect();
+        let estimated_priority_fee = if rewards.is_empty() {
+            U256::ZERO
+        } else {
+            rewards.iter().fold(U256::ZERO, |acc, v| acc + *v) / U256::from(rewards.len())
+        };
+
+        let max_priority_fee_per_gas = max_priority_fee_per_gas.unwrap_or(estimated_priority_fee);
+        let max_fee_per_gas =
+            max_fee_per_gas.unwrap_or(base_fee_per_gas * U256::from(2) + max_priority_fee_per_gas);
+        Ok(Self {
+            base_fee_per_gas,
+            max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+            max_fee_per_gas,
+        })
+    }
+
+    /// Reads `eth_feeHistory` of the latest blocks at the reward percentile
+    /// of the gas fee speed of the args
+    pub async fn fetch(transaction_args: &TransactionArgs) -> Result<Self, FeeEstimateError> {
+        let provider = Provider::<Http>::try_from(transaction_args.rpc_url.as_str())?;
+        let speed = transaction_args
+            .gas_fee_speed
+            .clone()
+            .unwrap_or(GasFeeSpeed::Medium);
+        let history = provider
+            .fee_history(
+                FEE_HISTORY_BLOCK_COUNT,
+                BlockNumber::Latest,
+                &[reward_percentile(&speed)],
+            )
+            .await?;
+        Self::from_fee_history(
+            &history,
+            transaction_args.max_priority_fee_per_gas,
+            transaction_args.max_fee_per_gas,
+        )
+    }
+
+    /// Fee per gas paid at the current base fee
+    pub fn expected_fee_per_gas(&self) -> U256 {
+        (self.base_fee_per_gas + self.max_priority_fee_per_gas).min(self.max_fee_per_gas)
+    }
+}
+
+/// Projected fees of a sequence of calls, ie the calls a command sends
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeReport {
+    pub fees: GasFees,
+    /// Gas estimate of each call
+    pub gas: Vec<U256>,
+    /// Symbol of the native currency of the network, if known
+    pub currency: Option<String>,
+}
+
+impl FeeReport {
+    /// Estimates the gas of the simulated calls and the fees of the next
+    /// block. Calls that depend on an earlier call of the sequence, eg a
+    /// deposit following its approval, cannot be estimated against the
+    /// latest block so the gas they used on the fork is taken instead
+    pub async fn from_simulations(
+        transaction_args: &TransactionArgs,
+        simulations: &[WriteSimulation],
+        network: Option<&Network>,
+    ) -> Result<Self, FeeEstimateError> {
+        let provider = Provider::<Http>::try_from(transaction_args.rpc_url.as_str())?;
+        let mut gas = vec![];
+        for simulation in simulations {
+            let tx = TypedTransaction::Eip1559(
+                Eip1559TransactionRequest::new()
+                    .from(H160::from(simulation.from.0 .0))
+                    .to(H160::from(simulation.to.0 .0))
+                    .data(simulation.calldata.to_vec()),
+            );
+            gas.push(match provider.estimate_gas(&tx, None).await {
+                Ok(v) => U256::from_limbs(v.0),
+                Err(_) => U256::from(simulation.gas_used),
+            });
+        }
+        Ok(Self {
+            fees: GasFees::fetch(transaction_args).await?,
+            gas,
+            currency: network.and_then(|v| v.currency.clone()),
+        })
+    }
+
+    pub fn total_gas(&self) -> U256 {
+        self.gas.iter().fold(U256::ZERO, |acc, v| acc + *v)
+    }
+
+    /// Cost at the current base fee
+    pub fn expected_cost(&self) -> U256 {
+        self.total_gas() * self.fees.expected_fee_per_gas()
+    }
+
+    /// Cost if the base fee rises up to the max fee, the most that can be paid
+    pub fn max_cost(&self) -> U256 {
+        self.total_gas() * self.fees.max_fee_per_gas
+    }
+
+    /// Formats a cost in wei in the native currency of the network
+    pub fn format_cost(&self, cost: U256) -> Result<String, FeeEstimateError> {
+        let amount = format_units(cost, NATIVE_CURRENCY_DECIMALS)?;
+        Ok(match &self.currency {
+            Some(currency) => format!("{} {}", amount, currency),
+            None => amount,
+        })
+    }
+
+    /// Errors if the max cost exceeds the given cap in wei
+    pub fn check_max_cost(&self, max_cost: U256) -> Result<(), FeeEstimateError> {
+        if self.max_cost() > max_cost {
+            return Err(FeeEstimateError::ExceedsMaxCost {
+                cost: self.format_cost(self.max_cost())?,
+                max_cost: self.format_cost(max_cost)?,
+            });
+        }
+        Ok(())
+    }
+
+    /// Sets the max fees of the args that are not specified to the estimated
+    /// ones, so that the sent transactions are bound by the reported max cost
+    pub fn fill_transaction_args(&self, transaction_args: &mut TransactionArgs) {
+        transaction_args
+            .max_priority_fee_per_gas
+            .get_or_insert(self.fees.max_priority_fee_per_gas);
+        transaction_args
+            .max_fee_per_gas
+            .get_or_insert(self.fees.max_fee_per_gas);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_history(base_fees: &[u64], rewards: &[u64]) -> FeeHistory {
+        FeeHistory {
+            base_fee_per_gas: base_fees.iter().map(|v| (*v).into()).collect(),
+            gas_used_ratio: vec![0.5; rewards.len()],
+            oldest_block: 1.into(),
+            reward: rewards.iter().map(|v| vec![(*v).into()]).collect(),
+        }
+    }
+
+    #[test]
+    fn test_gas_fees_from_fee_history() {
+        let history = fee_history(&[90, 95, 100], &[1, 3]);
+
+        let result = GasFees::from_fee_history(&history, None, None).unwrap();
+        assert_eq!(result.base_fee_per_gas, U256::from(100));
+        assert_eq!(result.max_priority_fee_per_gas, U256::from(2));
+        assert_eq!(result.max_fee_per_gas, U256::from(202));
+        assert_eq!(result.expected_fee_per_gas(), U256::from(102));
+
+        let result =
+            GasFees::from_fee_history(&history, Some(U256::from(50)), Some(U256::from(120)))
+                .unwrap();
+        assert_eq!(result.max_priority_fee_per_gas, U256::from(50));
+        assert_eq!(result.max_fee_per_gas, U256::from(120));
+        assert_eq!(result.expected_fee_per_gas(), U256::from(120));
+
+        assert!(matches!(
+            GasFees::from_fee_history(&fee_history(&[], &[]), None, None),
+            Err(FeeEstimateError::MissingBaseFee)
+        ));
+    }
+
+    #[test]
+    fn test_fee_report_max_cost() {
+        let mut report = FeeReport {
+            fees: GasFees {
+                base_fee_per_gas: U256::from(10_000_000_000u64),
+                max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+                max_fee_per_gas: U256::from(21_000_000_000u64),
+            },
+            gas: vec![U256::from(50_000), U256::from(50_000)],
+            currency: Some("MATIC".to_string()),
+        };
+
+        assert_eq!(report.expected_cost(), U256::from(1_100_000_000_000_000u64));
+        assert_eq!(report.max_cost(), U256::from(2_100_000_000_000_000u64));
+        assert_eq!(
+            report.format_cost(report.max_cost()).unwrap(),
+            "0.002100000000000000 MATIC"
+        );
+        assert!(report
+            .check_max_cost(U256::from(2_100_000_000_000_000u64))
+            .is_ok());
+        assert!(matches!(
+            report.check_max_cost(U256::from(2_000_000_000_000_000u64)),
+            Err(FeeEstimateError::ExceedsMaxCost { .. })
+        ));
+
+        let mut args = TransactionArgs {
+            max_fee_per_gas: Some(U256::from(30_000_000_000u64)),
+            ..Default::default()
+        };
+        report.currency = None;
+        report.fill_transaction_args(&mut args);
+        assert_eq!(
+            args.max_priority_fee_per_gas,
+            Some(U256::from(1_000_000_000u64))
+        );
+        assert_eq!(args.max_fee_per_gas, Some(U256::from(30_000_000_000u64)));
+        assert_eq!(
+            report.format_cost(U256::from(1)).unwrap(),
+            "0.000000000000000001"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use alloy::primitives::{
+    utils::{format_units, parse_units, UnitsError},
+    U256,
+};
+use proptest::prelude::RngCore;
+use rain_orderbook_app_settings::fuzz::{Decimal18, FuzzDistribution};
+use std::collections::HashMap;
+use std::num::ParseFloatError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FuzzDistributionError {
+    #[error("Fuzz distribution min {min} is greater than max {max}")]
+    InvalidRange { min: Decimal18, max: Decimal18 },
+    #[error("Log uniform fuzz distribution min must be greater than zero")]
+    ZeroLogUniformMin,
+    #[error("Fuzz distribution set is empty")]
+    EmptySet,
+    #[error(transparent)]
+    UnitsError(#[from] UnitsError),
+    #[error(transparent)]
+    ParseFloatError(#[from] ParseFloatError),
+}
+
+/// Samples the values of fuzzed bindings from their distributions with the
+/// given rng, so that the same seed samples the same values. Keeps the
+/// position of each random walk between runs
+#[derive(Debug, Clone, Default)]
+pub struct FuzzSampler {
+    walks: HashMap<String, U256>,
+}
+
+impl FuzzSampler {
+    pub fn sample(
+        &mut self,
+        binding: &str,
+        distribution: &FuzzDistribution,
+        rng: &mut impl RngCore,
+    ) -> Result<U256, FuzzDistributionError> {
+        match distribution {
+            FuzzDistribution::Uniform { min, max } => uniform(min.0, max.0, rng),
+            FuzzDistribution::LogUniform { min, max } => {
+                if min.0.is_zero() {
+                    return Err(FuzzDistributionError::ZeroLogUniformMin);
+                }
+                if min > max {
+                    return Err(FuzzDistributionError::InvalidRange {
+                        min: *min,
+                        max: *max,
+                    });
+                }
+                let (ln_min, ln_max) = (to_f64(min.0)?.ln(), to_f64(max.0)?.ln());
+                let unit = rng.next_u64() as f64 / u64::MAX as f64;
+                let value = from_f64((ln_min + unit * (ln_max - ln_min)).exp())?;
+                // float conversions are inexact so the value may fall just
+                // outside the range
+                Ok(value.clamp(min.0, max.0))
+            }
+            FuzzDistribution::Set(values) => {
+                if values.is_empty() {
+                    return Err(FuzzDistributionError::EmptySet);
+                }
+                Ok(values[(rng.next_u64() % values.len() as u64) as usize].0)
+            }
+            FuzzDistribution::Walk { base, step } => {
+                let current = self.walks.get(binding).copied().unwrap_or(base.0);
+                let delta = uniform(U256::ZERO, step.0.saturating_mul(U256::from(2)), rng)?;
+                let next = current.saturating_add(delta).saturating_sub(step.0);
+                self.walks.insert(binding.to_string(), next);
+                Ok(next)
+            }
+        }
+    }
+}
+
+fn uniform(min: U256, max: U256, rng: &mut impl RngCore) -> Result<U256, FuzzDistributionError> {
+    if min > max {
+        return Err(FuzzDistributionError::InvalidRange {
+            min: Decimal18(min),
+            max: Decimal18(max),
+        });
+    }
+    let mut bytes = [0u8; 32];
+    rng.fill_bytes(&mut bytes);
+    let random = U256::from_be_bytes(bytes);
+    let span = max - min;
+    if span == U256::MAX {
+        return Ok(random);
+    }
+    Ok(min + random % (span + U256::from(1)))
+}
+
+fn to_f64(value: U256) -> Result<f64, FuzzDistributionError> {
+    Ok(format_units(value, 18)?.parse::<f64>()?)
+}
+
+fn from_f64(value: f64) -> Result<U256, FuzzDistributionError> {
+    Ok(parse_units(&format!("{:.18}", value), 18)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::test_runner::{RngAlgorithm, TestRng};
+
+    fn decimal(value: &str) -> Decimal18 {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_sample_distributions() {
+        let mut rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[0; 32]);
+        let mut sampler = FuzzSampler::default();
+
+        let uniform = FuzzDistribution::Uniform {
+            min: decimal("0.9"),
+            max: decimal("1.1"),
+        };
+        let log_uniform = FuzzDistribution::LogUniform {
+            min: decimal("1"),
+            max: decimal("1000000"),
+        };
+        let set = FuzzDistribution::Set(vec![decimal("1"), decimal("2")]);
+        for _ in 0..100 {
+            let value = sampler.sample("a", &uniform, &mut rng).unwrap();
+            assert!(value >= decimal("0.9").0 && value <= decimal("1.1").0);
+
+            let value = sampler.sample("b", &log_uniform, &mut rng).unwrap();
+            assert!(value >= decimal("1").0 && value <= decimal("1000000").0);
+
+            let value = sampler.sample("c", &set, &mut rng).unwrap();
+            assert!(value == decimal("1").0 || value == decimal("2").0);
+        }
+
+        // each step of a walk moves by at most the step from the last one
+        let walk = FuzzDistribution::Walk {
+            base: decimal("1"),
+            step: decimal("0.01"),
+        };
+        let mut last = decimal("1").0;
+        for _ in 0..100 {
+            let value = sampler.sample("d", &walk, &mut rng).unwrap();
+            let moved = if value > last {
+                value - last
+            } else {
+                last - value
+            };
+            assert!(moved <= decimal("0.01").0);
+            last = value;
+        }
+
+        assert!(matches!(
+            sampler.sample(
+                "e",
+                &FuzzDistribution::Uniform {
+                    min: decimal("2"),
+                    max: decimal("1"),
+                },
+                &mut rng
+            ),
+            Err(FuzzDistributionError::InvalidRange { .. })
+        ));
+        assert!(matches!(
+            sampler.sample("f", &FuzzDistribution::Set(vec![]), &mut rng),
+            Err(FuzzDistributionError::EmptySet)
+        ));
+
+        // same seed samples the same values
+        let mut first = TestRng::from_seed(RngAlgorithm::ChaCha, &[1; 32]);
+        let mut second = TestRng::from_seed(RngAlgorithm::ChaCha, &[1; 32]);
+        assert_eq!(
+            FuzzSampler::default()
+                .sample("a", &log_uniform, &mut first)
+                .unwrap(),
+            FuzzSampler::default()
+                .sample("a", &log_uniform, &mut second)
+                .unwrap()
+        );
+    }
+}
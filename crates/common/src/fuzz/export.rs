@@ -0,0 +1,246 @@
+use super::{FuzzResultFlat, FuzzRunner, FuzzRunnerError};
+use crate::add_order::ORDERBOOK_ORDER_ENTRYPOINTS;
+use alloy::primitives::U256;
+use dotrain::{RainDocument, Rebind};
+use rain_orderbook_app_settings::{fuzz::Decimal18, scenario::Scenario};
+
+impl FuzzResultFlat {
+    /// Only the given columns in the given order. A column is picked by its
+    /// stack path, the name of a fuzzed binding or the LHS name of its stack
+    /// item, a name picks the first column with that name
+    pub fn select_columns(
+        &self,
+        columns: &[String],
+        stack_names: &[Vec<String>],
+    ) -> Result<FuzzResultFlat, FuzzRunnerError> {
+        let indexes = columns
+            .iter()
+            .map(|column| {
+                self.column_names
+                    .iter()
+                    .position(|v| v == column)
+                    .or_else(|| {
+                        self.column_names
+                            .iter()
+                            .position(|v| stack_name(v, stack_names) == Some(column.as_str()))
+                    })
+                    .ok_or(FuzzRunnerError::ColumnNotFound(column.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(FuzzResultFlat {
+            scenario: self.scenario.clone(),
+            column_names: indexes
+                .iter()
+                .map(|i| self.column_names[*i].clone())
+                .collect(),
+            data: self
+                .data
+                .iter()
+                .map(|row| indexes.iter().map(|i| row[*i]).collect())
+                .collect(),
+            reverts: self.reverts.clone(),
+            counterexamples: self.counterexamples.clone(),
+        })
+    }
+
+    /// Values of the runs as integers, or as 18 decimals fixed point numbers
+    /// if decimal
+    pub fn to_string_rows(&self, decimal: bool) -> Vec<Vec<String>> {
+        let format = |v: &U256| {
+            if decimal {
+                Decimal18(*v).to_string()
+            } else {
+                v.to_string()
+            }
+        };
+        self.data
+            .iter()
+            .map(|row| row.iter().map(format).collect())
+            .collect()
+    }
+}
+
+impl FuzzRunner {
+    /// LHS names of the stack items of each source of the scenario's
+    /// calculate-io and handle-io, by source index. Elided bindings are
+    /// composed as zero as only the names are of interest
+    pub fn scenario_stack_names(
+        &self,
+        scenario: &Scenario,
+    ) -> Result<Vec<Vec<String>>, FuzzRunnerError> {
+        let mut bindings: Vec<Rebind> = scenario
+            .bindings
+            .iter()
+            .map(|(k, v)| Rebind(k.clone(), v.clone()))
+            .collect();
+        let rain_document =
+            RainDocument::create(self.dotrain.clone(), None, None, Some(bindings.clone()));
+        bindings.extend(
+            rain_document
+                .namespace()
+                .iter()
+                .filter(|(_, v)| v.is_elided_binding())
+                .map(|(k, _)| Rebind(k.clone(), "0".to_string())),
+        );
+
+        let rainlang = RainDocument::compose_text(
+            &self.dotrain,
+            &ORDERBOOK_ORDER_ENTRYPOINTS,
+            None,
+            Some(bindings),
+        )?;
+        Ok(lhs_names(&rainlang))
+    }
+}
+
+/// LHS name of the stack item at the stack path, the last two parts of the
+/// path are the index of its source and its index in the stack
+fn stack_name<'a>(path: &str, stack_names: &'a [Vec<String>]) -> Option<&'a str> {
+    let mut parts = path.rsplit('.').map(|v| v.parse::<usize>());
+    let index = parts.next()?.ok()?;
+    let source_index = parts.next()?.ok()?;
+    stack_names
+        .get(source_index)?
+        .get(index)
+        .map(|v| v.as_str())
+        .filter(|v| *v != "_")
+}
+
+/// LHS names of each source of composed rainlang, in the order of the stack
+/// items they name
+pub fn lhs_names(rainlang: &str) -> Vec<Vec<String>> {
+    let mut sources = vec![];
+    let mut names = vec![];
+    let mut line = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut in_comment = false;
+
+    let mut chars = rainlang.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                in_comment = false;
+            }
+            continue;
+        }
+        if in_string {
+            in_string = c != '"';
+            continue;
+        }
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                in_comment = true;
+            }
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' | ';' if depth == 0 => {
+                names.extend(line_lhs_names(&line));
+                line.clear();
+                if c == ';' {
+                    sources.push(std::mem::take(&mut names));
+                }
+            }
+            _ => line.push(c),
+        }
+    }
+    sources
+}
+
+fn line_lhs_names(line: &str) -> Vec<String> {
+    match line.split_once(':') {
+        // the pragma, if any, precedes the first line of the first source
+        Some((lhs, _)) => lhs
+            .split_whitespace()
+            .filter(|v| *v != "using-words-from" && !v.starts_with("0x"))
+            .map(|v| v.to_string())
+            .collect(),
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_columns() {
+        let rainlang = r#"using-words-from 0x1234
+/* 0. calculate-io */
+max-output io-ratio: 1 2,
+_: call<2>(),
+:ensure(equal-to(1 1) "a, b: c");
+/* 1. handle-io */
+:;
+/* 2. nested */
+nested-value: 5;"#;
+        let stack_names = lhs_names(rainlang);
+        assert_eq!(
+            stack_names,
+            vec![
+                vec![
+                    "max-output".to_string(),
+                    "io-ratio".to_string(),
+                    "_".to_string()
+                ],
+                vec![],
+                vec!["nested-value".to_string()],
+            ]
+        );
+
+        let one = U256::from(10).pow(U256::from(18));
+        let flat = FuzzResultFlat {
+            scenario: "scenario".to_string(),
+            column_names: vec![
+                "0.0".to_string(),
+                "0.1".to_string(),
+                "0.2".to_string(),
+                "0.2.2.0".to_string(),
+                "fuzzed".to_string(),
+            ],
+            data: vec![vec![
+                one,
+                one * U256::from(2),
+                U256::from(5),
+                U256::from(5),
+                one / U256::from(2),
+            ]],
+            reverts: vec![],
+            counterexamples: vec![],
+        };
+
+        let selected = flat
+            .select_columns(
+                &[
+                    "io-ratio".to_string(),
+                    "nested-value".to_string(),
+                    "fuzzed".to_string(),
+                    "0.0".to_string(),
+                ],
+                &stack_names,
+            )
+            .unwrap();
+        assert_eq!(
+            selected.column_names,
+            vec!["0.1", "0.2.2.0", "fuzzed", "0.0"]
+        );
+        assert_eq!(
+            selected.to_string_rows(true),
+            vec![vec!["2", "0.000000000000000005", "0.5", "1"]]
+        );
+        assert_eq!(
+            selected.to_string_rows(false)[0][0],
+            (one * U256::from(2)).to_string()
+        );
+
+        // anonymous stack items are picked by path only
+        assert!(matches!(
+            flat.select_columns(&["_".to_string()], &stack_names),
+            Err(FuzzRunnerError::ColumnNotFound(_))
+        ));
+    }
+}
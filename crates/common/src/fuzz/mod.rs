@@ -1,9 +1,17 @@
-use crate::add_order::ORDERBOOK_ORDER_ENTRYPOINTS;
+mod distribution;
+mod export;
+mod order;
+mod shrink;
+
+use crate::add_order::{
+    AddOrderArgsError, ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS, ORDERBOOK_ORDER_ENTRYPOINTS,
+};
+use crate::order_context::{OrderContext, OrderContextError, CONTEXT_CALCULATIONS_COLUMN};
 use alloy::primitives::private::rand;
+use alloy::primitives::Address;
 use alloy::primitives::U256;
 use alloy_ethers_typecast::transaction::{ReadableClientError, ReadableClientHttp};
 use dotrain::{error::ComposeError, RainDocument, Rebind};
-use futures::TryFutureExt;
 use proptest::prelude::RngCore;
 use proptest::test_runner::{RngAlgorithm, TestRng};
 use rain_interpreter_bindings::IInterpreterStoreV1::FullyQualifiedNamespace;
@@ -15,24 +23,87 @@ use rain_interpreter_eval::{
 use rain_orderbook_app_settings::blocks::BlockError;
 use rain_orderbook_app_settings::chart::Chart;
 use rain_orderbook_app_settings::config::*;
+use rain_orderbook_app_settings::fuzz::{Decimal18, FuzzExpectation};
 use rain_orderbook_app_settings::scenario::Scenario;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use thiserror::Error;
 use typeshare::typeshare;
 
+pub use distribution::*;
+pub use export::*;
+pub use order::*;
+pub use shrink::*;
+
+pub static CALCULATE_IO_ENTRYPOINT: &str = "calculate-io";
+pub static HANDLE_IO_ENTRYPOINT: &str = "handle-io";
+pub static POST_ADD_ORDER_ENTRYPOINT: &str = "post-add-order";
+/// Entrypoints of an order that scenarios can fuzz
+pub static FUZZ_ENTRYPOINTS: [&str; 3] = [
+    CALCULATE_IO_ENTRYPOINT,
+    HANDLE_IO_ENTRYPOINT,
+    POST_ADD_ORDER_ENTRYPOINT,
+];
+
 #[typeshare]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChartData {
-    scenarios_data: HashMap<String, FuzzResultFlat>,
-    charts: HashMap<String, Chart>,
+    pub scenarios_data: HashMap<String, FuzzResultFlat>,
+    pub charts: HashMap<String, Chart>,
 }
 
 #[derive(Debug)]
 pub struct FuzzResult {
     pub scenario: String,
+    /// calculate-io evaluations that didnt revert
     pub runs: Vec<RainEvalResult>,
+    /// Index of each of the calculate-io evaluations among the runs of the
+    /// scenario
+    pub run_indices: Vec<usize>,
+    /// Values of the fuzzed bindings of every run of the scenario, keyed by
+    /// binding
+    pub inputs: Vec<BTreeMap<String, U256>>,
+    /// Evaluations of the other fuzzed entrypoints that didnt revert along
+    /// with the index of their run, keyed by entrypoint
+    pub entrypoint_runs: HashMap<String, Vec<(usize, RainEvalResult)>>,
+    pub reverts: Vec<FuzzRevert>,
+    /// First revert of each entrypoint with its fuzzed values shrunk to
+    /// the smallest ones that still revert, empty unless the runner shrinks
+    pub counterexamples: Vec<FuzzRevert>,
+}
+
+/// A run of a scenario that doesnt meet one of its expectations
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FuzzFailure {
+    /// Name of the expectation
+    pub expectation: String,
+    pub entrypoint: String,
+    /// Index of the run among the runs of the scenario
+    #[typeshare(typescript(type = "number"))]
+    pub run: usize,
+    /// Values of the fuzzed bindings of the run, keyed by binding
+    #[typeshare(typescript(type = "Record<string, string>"))]
+    pub inputs: BTreeMap<String, U256>,
+    pub message: String,
+}
+
+/// A reverted evaluation of an entrypoint
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FuzzRevert {
+    pub entrypoint: String,
+    /// Index of the run among the runs of the scenario
+    #[typeshare(typescript(type = "number"))]
+    pub run: usize,
+    #[typeshare(typescript(type = "number"))]
+    pub block_number: u64,
+    /// Values of the fuzzed bindings of the run, keyed by binding
+    #[typeshare(typescript(type = "Record<string, string>"))]
+    pub inputs: BTreeMap<String, U256>,
+    /// Decoded error of the revert
+    pub error: String,
 }
 
 #[typeshare]
@@ -42,6 +113,8 @@ pub struct FuzzResultFlat {
     pub column_names: Vec<String>,
     #[typeshare(serialized_as = "Vec<Vec<String>>")]
     pub data: Vec<Vec<U256>>,
+    pub reverts: Vec<FuzzRevert>,
+    pub counterexamples: Vec<FuzzRevert>,
 }
 
 impl FuzzResult {
@@ -91,9 +164,17 @@ impl FuzzResult {
             source_paths.push(current_path);
         }
 
+        // the fuzzed inputs of the runs follow their stacks
+        let input_names = self
+            .inputs
+            .first()
+            .map(|v| v.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        column_names.extend(input_names.iter().cloned());
+
         let mut data: Vec<Vec<U256>> = vec![];
 
-        for run in self.runs.iter() {
+        for (run, run_index) in self.runs.iter().zip(self.run_indices.iter()) {
             let mut run_data: Vec<U256> = vec![];
             for trace in run.traces.iter() {
                 let mut stack = trace.stack.clone();
@@ -102,14 +183,115 @@ impl FuzzResult {
                     run_data.push(*stack_item);
                 }
             }
+            for name in input_names.iter() {
+                run_data.push(
+                    self.inputs
+                        .get(*run_index)
+                        .and_then(|v| v.get(name))
+                        .copied()
+                        .unwrap_or_default(),
+                );
+            }
             data.push(run_data);
         }
         Ok(FuzzResultFlat {
             scenario: self.scenario.clone(),
             column_names,
             data,
+            reverts: self.reverts.clone(),
+            counterexamples: self.counterexamples.clone(),
         })
     }
+
+    /// Checks every evaluation of the expected entrypoints against the
+    /// given expectations, in order of expectation name and run
+    pub fn check_expectations(
+        &self,
+        expectations: &HashMap<String, FuzzExpectation>,
+    ) -> Result<Vec<FuzzFailure>, FuzzRunnerError> {
+        let mut names = expectations.keys().collect::<Vec<_>>();
+        names.sort();
+
+        let mut failures = vec![];
+        for name in names {
+            let expectation = &expectations[name];
+            let entrypoint = expectation
+                .entrypoint
+                .as_deref()
+                .unwrap_or(CALCULATE_IO_ENTRYPOINT);
+            for (run, result) in self.runs_of(entrypoint)? {
+                if let Some(message) = check_expectation(expectation, result)? {
+                    failures.push(FuzzFailure {
+                        expectation: name.clone(),
+                        entrypoint: entrypoint.to_string(),
+                        run,
+                        inputs: self.inputs.get(run).cloned().unwrap_or_default(),
+                        message,
+                    });
+                }
+            }
+        }
+        Ok(failures)
+    }
+
+    /// Evaluations of the entrypoint that didnt revert along with the index
+    /// of their run
+    fn runs_of(&self, entrypoint: &str) -> Result<Vec<(usize, &RainEvalResult)>, FuzzRunnerError> {
+        if entrypoint == CALCULATE_IO_ENTRYPOINT {
+            Ok(self
+                .run_indices
+                .iter()
+                .copied()
+                .zip(self.runs.iter())
+                .collect())
+        } else if FUZZ_ENTRYPOINTS.contains(&entrypoint) {
+            Ok(self
+                .entrypoint_runs
+                .get(entrypoint)
+                .map(|v| v.iter().map(|(run, result)| (*run, result)).collect())
+                .unwrap_or_default())
+        } else {
+            Err(FuzzRunnerError::UnknownEntrypoint(entrypoint.to_string()))
+        }
+    }
+}
+
+/// Description of how the evaluation doesnt meet the expectation, if it
+/// doesnt
+fn check_expectation(
+    expectation: &FuzzExpectation,
+    result: &RainEvalResult,
+) -> Result<Option<String>, FuzzRunnerError> {
+    let value = result.search_trace_by_path(&expectation.path)?;
+    let describe = |bound: &str, other: U256| {
+        format!(
+            "{} is {}, expected {} {}",
+            expectation.path,
+            Decimal18(value),
+            bound,
+            Decimal18(other)
+        )
+    };
+
+    if let Some(min) = expectation.min.filter(|min| value < min.0) {
+        return Ok(Some(describe("at least", min.0)));
+    }
+    if let Some(max) = expectation.max.filter(|max| value > max.0) {
+        return Ok(Some(describe("at most", max.0)));
+    }
+    if let Some(path) = &expectation.lte {
+        let other = result.search_trace_by_path(path)?;
+        if value > other {
+            return Ok(Some(describe(&format!("at most {} of", path), other)));
+        }
+    }
+    if let Some(path) = &expectation.gte {
+        let other = result.search_trace_by_path(path)?;
+        if value < other {
+            return Ok(Some(describe(&format!("at least {} of", path), other)));
+        }
+    }
+    Ok(None)
 }
 
 #[derive(Clone)]
@@ -118,6 +300,9 @@ pub struct FuzzRunner {
     pub dotrain: String,
     pub rng: TestRng,
     pub settings: Config,
+    /// Whether to shrink the first revert of each entrypoint to a
+    /// counterexample, which re-evaluates the scenario many times
+    pub shrink: bool,
 }
 
 #[derive(Error, Debug)]
@@ -144,6 +329,20 @@ pub enum FuzzRunnerError {
     ReadableClientHttpError(#[from] ReadableClientError),
     #[error(transparent)]
     BlockError(#[from] BlockError),
+    #[error(transparent)]
+    AddOrderArgsError(#[from] AddOrderArgsError),
+    #[error(transparent)]
+    OrderContextError(#[from] OrderContextError),
+    #[error("Token {0} of the scenario's vault balances not found")]
+    VaultBalanceTokenNotFound(String),
+    #[error("{0} is not an entrypoint that can be fuzzed")]
+    UnknownEntrypoint(String),
+    #[error(transparent)]
+    FuzzDistributionError(#[from] FuzzDistributionError),
+    #[error("Fuzz distribution of {0} is not of an elided binding")]
+    FuzzBindingNotElided(String),
+    #[error("Column {0} not found")]
+    ColumnNotFound(String),
 }
 
 impl FuzzRunner {
@@ -153,6 +352,7 @@ impl FuzzRunner {
             dotrain: dotrain.into(),
             settings,
             rng: TestRng::from_seed(RngAlgorithm::ChaCha, &seed.unwrap_or([0; 32])),
+            shrink: false,
         }
     }
 
@@ -180,6 +380,26 @@ impl FuzzRunner {
 
         let deployer = scenario.deployer.clone();
 
+        // Entrypoints that are expected on are fuzzed too
+        let mut entrypoints = scenario
+            .entrypoints
+            .clone()
+            .unwrap_or(vec![CALCULATE_IO_ENTRYPOINT.to_string()]);
+        for expectation in scenario.expect.values() {
+            if let Some(entrypoint) = &expectation.entrypoint {
+                if !entrypoints.contains(entrypoint) {
+                    entrypoints.push(entrypoint.clone());
+                }
+            }
+        }
+        let entrypoints = Arc::new(entrypoints);
+        if let Some(entrypoint) = entrypoints
+            .iter()
+            .find(|v| !FUZZ_ENTRYPOINTS.contains(&v.as_str()))
+        {
+            return Err(FuzzRunnerError::UnknownEntrypoint(entrypoint.clone()));
+        }
+
         // Fetch the latest block number
         let block_number = ReadableClientHttp::new_from_url(deployer.network.rpc.to_string())?
             .get_block_number()
@@ -203,6 +423,10 @@ impl FuzzRunner {
             )
             .await?;
 
+        // Evaluate in the context of the scenario's deployment order if it
+        // has one, otherwise in a zeroed context
+        let fuzz_order = self.fuzz_order(scenario).await?;
+
         // Pull out the bindings from the scenario
         let scenario_bindings: Vec<Rebind> = scenario
             .bindings
@@ -229,6 +453,14 @@ impl FuzzRunner {
                 .map(|(k, _)| k.clone())
                 .collect::<Vec<String>>(),
         );
+        if let Some(binding) = scenario
+            .fuzz
+            .keys()
+            .find(|v| !elided_binding_keys.contains(v))
+        {
+            return Err(FuzzRunnerError::FuzzBindingNotElided(binding.clone()));
+        }
+        let mut sampler = FuzzSampler::default();
 
         let dotrain = Arc::new(self.dotrain.clone());
         let mut handles = vec![];
@@ -243,63 +475,137 @@ impl FuzzRunner {
                 let deployer = Arc::clone(&deployer);
                 let scenario_bindings = scenario_bindings.clone();
                 let dotrain = Arc::clone(&dotrain);
+                let entrypoints = Arc::clone(&entrypoints);
 
                 let mut final_bindings: Vec<Rebind> = vec![];
+                let mut inputs = BTreeMap::new();
 
-                // For each scenario.fuzz_binds, add a random value
+                // For each elided binding, add a value from its distribution
+                // or otherwise a random value
                 for elided_binding in elided_binding_keys.as_slice() {
-                    let mut val: [u8; 32] = [0; 32];
-                    self.rng.fill_bytes(&mut val);
-                    let hex = alloy::primitives::hex::encode_prefixed(val);
+                    let value = match scenario.fuzz.get(elided_binding) {
+                        Some(distribution) => {
+                            sampler.sample(elided_binding, distribution, &mut self.rng)?
+                        }
+                        None => {
+                            let mut val: [u8; 32] = [0; 32];
+                            self.rng.fill_bytes(&mut val);
+                            U256::from_be_bytes(val)
+                        }
+                    };
+                    let hex = alloy::primitives::hex::encode_prefixed(value.to_be_bytes::<32>());
                     final_bindings.push(Rebind(elided_binding.to_string(), hex));
+                    inputs.insert(elided_binding.to_string(), value);
                 }
 
-                let handle = tokio::spawn(async move {
-                    final_bindings.extend(scenario_bindings.clone());
-
-                    let rainlang_string = RainDocument::compose_text(
-                        &dotrain,
-                        &ORDERBOOK_ORDER_ENTRYPOINTS,
-                        None,
-                        Some(final_bindings),
-                    )?;
-
-                    // Create a 5x5 grid of zero values for context - later we'll
-                    // replace these with sane values based on Orderbook context
-                    let mut context = vec![vec![U256::from(0); 5]; 5];
-                    // set random hash for context order hash cell
-                    context[1][0] = rand::random();
-
-                    let args = ForkEvalArgs {
-                        rainlang_string,
-                        source_index: 0,
-                        deployer: deployer.address,
-                        namespace: FullyQualifiedNamespace::default(),
-                        context,
-                        decode_errors: true,
-                    };
-                    fork_clone
-                        .fork_eval(args)
-                        .map_err(FuzzRunnerError::ForkCallError)
-                        .await
-                });
-                handles.push(handle);
+                let order_context = fuzz_order
+                    .as_ref()
+                    .map(|v| v.random_order_context(&mut self.rng));
+                let (context, namespace) = match &order_context {
+                    Some(order_context) => (order_context.build()?, order_context.namespace()),
+                    None => {
+                        // 5x5 grid of zero values with a random order hash
+                        let mut context = vec![vec![U256::from(0); 5]; 5];
+                        context[1][0] = rand::random();
+                        (context, FullyQualifiedNamespace::default())
+                    }
+                };
+
+                final_bindings.extend(scenario_bindings);
+                let args = FuzzRunArgs {
+                    dotrain: dotrain.to_string(),
+                    bindings: final_bindings,
+                    deployer: deployer.address,
+                    namespace,
+                    context,
+                    order_context,
+                    entrypoints: entrypoints.to_vec(),
+                };
+
+                // the args are kept to rerun the run if it reverts
+                let handle = {
+                    let args = args.clone();
+                    tokio::spawn(async move { fuzz_run(&fork_clone, args).await })
+                };
+                handles.push((block_number, inputs, args, handle));
             }
         }
 
         let mut runs: Vec<RainEvalResult> = Vec::new();
+        let mut run_indices: Vec<usize> = Vec::new();
+        let mut run_inputs: Vec<BTreeMap<String, U256>> = Vec::new();
+        let mut entrypoint_runs: HashMap<String, Vec<(usize, RainEvalResult)>> = HashMap::new();
+        let mut reverts: Vec<FuzzRevert> = Vec::new();
+        let mut run_args: Vec<FuzzRunArgs> = Vec::new();
+
+        for (run, (block_number, inputs, args, handle)) in handles.into_iter().enumerate() {
+            for (entrypoint, result) in handle.await?? {
+                match result {
+                    Ok(result) if entrypoint == CALCULATE_IO_ENTRYPOINT => {
+                        runs.push(result);
+                        run_indices.push(run);
+                    }
+                    Ok(result) => entrypoint_runs
+                        .entry(entrypoint)
+                        .or_default()
+                        .push((run, result)),
+                    Err(error) => reverts.push(FuzzRevert {
+                        entrypoint,
+                        run,
+                        block_number,
+                        inputs: inputs.clone(),
+                        error,
+                    }),
+                }
+            }
+            run_inputs.push(inputs);
+            run_args.push(args);
+        }
 
-        for handle in handles {
-            let res = handle.await??;
-            runs.push(res.into());
+        let mut counterexamples: Vec<FuzzRevert> = Vec::new();
+        if self.shrink {
+            for entrypoint in entrypoints.iter() {
+                if let Some(revert) = reverts.iter().find(|v| &v.entrypoint == entrypoint) {
+                    counterexamples.push(
+                        self.shrink(revert, &run_args[revert.run], &scenario.fuzz)
+                            .await?,
+                    );
+                }
+            }
         }
 
         Ok(FuzzResult {
             scenario: scenario.name.clone(),
             runs,
+            run_indices,
+            inputs: run_inputs,
+            entrypoint_runs,
+            reverts,
+            counterexamples,
         })
     }
 
+    /// Order of the first deployment of the scenario, by deployment key
+    pub async fn fuzz_order(
+        &self,
+        scenario: &Scenario,
+    ) -> Result<Option<FuzzOrder>, FuzzRunnerError> {
+        let mut deployments = self
+            .settings
+            .deployments
+            .iter()
+            .filter(|(_, v)| v.scenario.name == scenario.name)
+            .collect::<Vec<_>>();
+        deployments.sort_by(|a, b| a.0.cmp(b.0));
+        match deployments.first() {
+            Some((_, deployment)) => Ok(Some(
+                FuzzOrder::try_from_deployment(&self.dotrain, deployment, &self.settings.tokens)
+                    .await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub async fn make_chart_data(&self) -> Result<ChartData, FuzzRunnerError> {
         let charts = self.settings.charts.clone();
         let mut scenarios_data: HashMap<String, FuzzResultFlat> = HashMap::new();
@@ -327,6 +633,111 @@ impl FuzzRunner {
     }
 }
 
+/// Inputs of a single run of a scenario
+#[derive(Clone)]
+struct FuzzRunArgs {
+    dotrain: String,
+    bindings: Vec<Rebind>,
+    deployer: Address,
+    namespace: FullyQualifiedNamespace,
+    context: Vec<Vec<U256>>,
+    /// Orderbook context of the run, if the scenario has a deployment order
+    order_context: Option<OrderContext>,
+    entrypoints: Vec<String>,
+}
+
+/// Evaluates the fuzzed entrypoints of a single run, along with the result
+/// of each or its decoded error if it reverted. calculate-io is always
+/// evaluated as handle-io is evaluated in the context of its outputs, the
+/// same way a take of the full max output would
+async fn fuzz_run(
+    fork: &Forker,
+    args: FuzzRunArgs,
+) -> Result<Vec<(String, Result<RainEvalResult, String>)>, FuzzRunnerError> {
+    let is_fuzzed = |entrypoint: &str| args.entrypoints.iter().any(|v| v == entrypoint);
+    let eval_args = |rainlang_string: String, source_index, context| ForkEvalArgs {
+        rainlang_string,
+        source_index,
+        deployer: args.deployer,
+        namespace: args.namespace,
+        context,
+        decode_errors: true,
+    };
+    let mut results = vec![];
+
+    let rainlang_string = RainDocument::compose_text(
+        &args.dotrain,
+        &ORDERBOOK_ORDER_ENTRYPOINTS,
+        None,
+        Some(args.bindings.clone()),
+    )?;
+    let calculate_io = try_fork_eval(
+        fork,
+        eval_args(rainlang_string.clone(), 0, args.context.clone()),
+    )
+    .await?;
+    // max output and io ratio, unless calculate-io reverted
+    let calculations =
+        calculate_io
+            .as_ref()
+            .ok()
+            .map(|v| match (v.stack.first(), v.stack.get(1)) {
+                (Some(io_ratio), Some(max_output)) => Ok((*max_output, *io_ratio)),
+                _ => Err(format!(
+                    "calculate-io has {} outputs, expected at least 2",
+                    v.stack.len()
+                )),
+            });
+    results.push((CALCULATE_IO_ENTRYPOINT.to_string(), calculate_io));
+
+    if let Some(calculations) = calculations.filter(|_| is_fuzzed(HANDLE_IO_ENTRYPOINT)) {
+        let handle_io = match calculations {
+            Ok((max_output, io_ratio)) => {
+                let context = match &args.order_context {
+                    Some(order_context) => order_context.build_handle_io(max_output, io_ratio)?,
+                    None => {
+                        let mut context = args.context.clone();
+                        context[CONTEXT_CALCULATIONS_COLUMN] = vec![max_output, io_ratio];
+                        context
+                    }
+                };
+                try_fork_eval(fork, eval_args(rainlang_string, 1, context)).await?
+            }
+            Err(e) => Err(e),
+        };
+        results.push((HANDLE_IO_ENTRYPOINT.to_string(), handle_io));
+    }
+
+    if is_fuzzed(POST_ADD_ORDER_ENTRYPOINT) {
+        let rainlang_string = RainDocument::compose_text(
+            &args.dotrain,
+            &ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS,
+            None,
+            Some(args.bindings.clone()),
+        )?;
+        let context = match &args.order_context {
+            Some(order_context) => order_context.build_post_add_order(),
+            None => args.context.clone(),
+        };
+        let post_add_order = try_fork_eval(fork, eval_args(rainlang_string, 0, context)).await?;
+        results.push((POST_ADD_ORDER_ENTRYPOINT.to_string(), post_add_order));
+    }
+
+    Ok(results)
+}
+
+/// Evaluates on the fork, a revert is returned as its decoded error
+async fn try_fork_eval(
+    fork: &Forker,
+    args: ForkEvalArgs,
+) -> Result<Result<RainEvalResult, String>, FuzzRunnerError> {
+    match fork.fork_eval(args).await {
+        Ok(res) => Ok(Ok(res.into())),
+        Err(ForkCallError::AbiDecodedError(e)) => Ok(Err(e.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +759,11 @@ scenarios:
         runs: 50
         bindings:
             bound: 3
+        fuzz:
+            fuzzed:
+                uniform:
+                    min: 1
+                    max: 2
 ---
 #bound !bind it
 #fuzzed !fuzz it
@@ -375,6 +791,18 @@ b: fuzzed;
             .unwrap();
 
         assert!(res.runs.len() == 50);
+
+        // the sampled values are recorded as a column of the runs
+        let flattened = res.flatten_traces().unwrap();
+        let column_index = flattened
+            .column_names
+            .iter()
+            .position(|x| x == "fuzzed")
+            .unwrap();
+        let one = U256::from(10).pow(U256::from(18));
+        for row in flattened.data.iter() {
+            assert!(row[column_index] >= one && row[column_index] <= one * U256::from(2));
+        }
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
@@ -537,7 +965,8 @@ _: context<4 4>();
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
     async fn test_context_unhappy() {
-        // if we try to access a context value that is out of bounds, we should get an error
+        // if we try to access a context value that is out of bounds, the
+        // evaluation reverts
 
         let dotrain = format!(
             r#"
@@ -571,9 +1000,16 @@ _: context<50 50>();
         let res = runner
             .run_scenario_by_name("sepolia")
             .await
-            .map_err(|e| println!("{:#?}", e));
+            .map_err(|e| println!("{:#?}", e))
+            .unwrap();
 
-        assert!(res.is_err());
+        // the reverts are reported per run instead
+        assert!(res.runs.is_empty());
+        assert_eq!(res.reverts.len(), 50);
+        assert!(res
+            .reverts
+            .iter()
+            .all(|v| v.entrypoint == CALCULATE_IO_ENTRYPOINT));
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
@@ -627,4 +1063,191 @@ _: context<1 0>();
             }
         }
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_handle_io_entrypoint() {
+        // handle-io sees the max output and io ratio of calculate-io and
+        // its reverts are reported with the entrypoint
+        let dotrain = format!(
+            r#"
+deployers:
+    sepolia:
+        address: 0x017F5651eB8fa4048BBc17433149c6c035d391A6
+networks:
+    sepolia:
+        rpc: {rpc_url}
+        chain-id: 137
+scenarios:
+    sepolia:
+        runs: 10
+        entrypoints:
+            - calculate-io
+            - handle-io
+---
+#calculate-io
+max-output: 10,
+io-ratio: 2;
+#handle-io
+:ensure(equal-to(context<2 0>() 10) "max output"),
+:ensure(equal-to(context<2 1>() 3) "io ratio");
+    "#,
+            rpc_url = rain_orderbook_env::CI_DEPLOY_SEPOLIA_RPC_URL
+        );
+        let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap();
+        let settings = serde_yaml::from_str::<ConfigSource>(frontmatter).unwrap();
+        let config = settings
+            .try_into()
+            .map_err(|e| println!("{:?}", e))
+            .unwrap();
+
+        let mut runner = FuzzRunner::new(&dotrain, config, None).await;
+
+        let res = runner
+            .run_scenario_by_name("sepolia")
+            .await
+            .map_err(|e| println!("{:#?}", e))
+            .unwrap();
+
+        assert_eq!(res.runs.len(), 10);
+        assert_eq!(res.reverts.len(), 10);
+        assert!(res
+            .reverts
+            .iter()
+            .all(|v| v.entrypoint == HANDLE_IO_ENTRYPOINT));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_shrink_counterexample() {
+        let dotrain = format!(
+            r#"
+deployers:
+    sepolia:
+        address: 0x017F5651eB8fa4048BBc17433149c6c035d391A6
+networks:
+    sepolia:
+        rpc: {rpc_url}
+        chain-id: 137
+scenarios:
+    sepolia:
+        runs: 20
+        fuzz:
+            fuzzed:
+                uniform:
+                    min: 1
+                    max: 2
+---
+#fuzzed !fuzz it
+#calculate-io
+:ensure(less-than(fuzzed 15e17) "too big"),
+max-output: 10,
+io-ratio: 2;
+#handle-io
+:;
+    "#,
+            rpc_url = rain_orderbook_env::CI_DEPLOY_SEPOLIA_RPC_URL
+        );
+        let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap();
+        let settings = serde_yaml::from_str::<ConfigSource>(frontmatter).unwrap();
+        let config = settings
+            .try_into()
+            .map_err(|e| println!("{:?}", e))
+            .unwrap();
+
+        let mut runner = FuzzRunner::new(&dotrain, config, None).await;
+        runner.shrink = true;
+
+        let res = runner
+            .run_scenario_by_name("sepolia")
+            .await
+            .map_err(|e| println!("{:#?}", e))
+            .unwrap();
+
+        // the smallest value that reverts is exactly the bound
+        assert!(!res.reverts.is_empty());
+        assert_eq!(res.counterexamples.len(), 1);
+        let counterexample = &res.counterexamples[0];
+        assert_eq!(counterexample.entrypoint, CALCULATE_IO_ENTRYPOINT);
+        assert_eq!(counterexample.error, res.reverts[0].error);
+        assert_eq!(
+            counterexample.inputs["fuzzed"],
+            U256::from(15) * U256::from(10).pow(U256::from(17))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_check_expectations() {
+        let dotrain = format!(
+            r#"
+deployers:
+    sepolia:
+        address: 0x017F5651eB8fa4048BBc17433149c6c035d391A6
+networks:
+    sepolia:
+        rpc: {rpc_url}
+        chain-id: 137
+scenarios:
+    sepolia:
+        runs: 20
+        fuzz:
+            fuzzed:
+                uniform:
+                    min: 1
+                    max: 2
+        expect:
+            in-range:
+                path: 0.0
+                min: 1
+                max: 2
+            at-most-b:
+                path: 0.0
+                lte: 0.1
+            at-least-b:
+                path: 0.0
+                gte: 0.1
+---
+#fuzzed !fuzz it
+#calculate-io
+a: fuzzed,
+b: 15e17;
+#handle-io
+:;
+    "#,
+            rpc_url = rain_orderbook_env::CI_DEPLOY_SEPOLIA_RPC_URL
+        );
+        let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap();
+        let settings = serde_yaml::from_str::<ConfigSource>(frontmatter).unwrap();
+        let config: Config = settings
+            .try_into()
+            .map_err(|e| println!("{:?}", e))
+            .unwrap();
+        let scenario = config.scenarios["sepolia"].clone();
+
+        let mut runner = FuzzRunner::new(&dotrain, config, None).await;
+
+        let res = runner
+            .run_scenario_by_name("sepolia")
+            .await
+            .map_err(|e| println!("{:#?}", e))
+            .unwrap();
+        let failures = res.check_expectations(&scenario.expect).unwrap();
+
+        // every run is above or below 1.5 so fails exactly one of the
+        // bounds against it, unless it is exactly 1.5
+        let one_and_a_half = U256::from(15) * U256::from(10).pow(U256::from(17));
+        let expected_failures = res
+            .inputs
+            .iter()
+            .filter(|v| v["fuzzed"] != one_and_a_half)
+            .count();
+        assert_eq!(failures.len(), expected_failures);
+        assert!(failures.iter().all(|v| v.expectation != "in-range"));
+        for failure in failures {
+            assert_eq!(failure.entrypoint, CALCULATE_IO_ENTRYPOINT);
+            let below = failure.inputs["fuzzed"] < one_and_a_half;
+            assert_eq!(
+                failure.expectation,
+                if below { "at-least-b" } else { "at-most-b" }
+            );
+        }
+    }
 }
@@ -0,0 +1,191 @@
+use super::FuzzRunnerError;
+use crate::{add_order::AddOrderArgs, order_context::OrderContext};
+use alloy::primitives::{Address, B256, U256};
+use proptest::prelude::RngCore;
+use rain_orderbook_app_settings::{deployment::Deployment, token::Token};
+use rain_orderbook_bindings::IOrderBookV4::{OrderV3, IO};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Upper bound of fuzzed vault balances in whole tokens
+pub static FUZZ_VAULT_BALANCE_MAX_UNITS: u64 = 1_000_000_000;
+
+/// The order of a scenario's deployment, evaluated in the context orderbook
+/// would give it. Owner, nonce and counterparty are random for each run, as
+/// are the vault balances that are not specified by the scenario
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzOrder {
+    pub order: OrderV3,
+    pub orderbook: Address,
+    pub input_io_index: usize,
+    pub output_io_index: usize,
+    /// Vault balances specified by the scenario keyed by token address, in
+    /// token decimals
+    pub vault_balances: HashMap<Address, U256>,
+}
+
+impl FuzzOrder {
+    /// Builds the order of the given deployment, the IO pair that is
+    /// evaluated is the first one that doesnt trade a token for itself
+    pub async fn try_from_deployment(
+        dotrain: &str,
+        deployment: &Deployment,
+        tokens: &HashMap<String, Arc<Token>>,
+    ) -> Result<Self, FuzzRunnerError> {
+        let add_order_args =
+            AddOrderArgs::new_from_deployment(dotrain.to_string(), deployment.clone()).await?;
+
+        let mut vault_balances = HashMap::new();
+        for (key, balance) in &deployment.scenario.vault_balances {
+            let token = tokens
+                .get(key)
+                .ok_or(FuzzRunnerError::VaultBalanceTokenNotFound(key.clone()))?;
+            vault_balances.insert(token.address, *balance);
+        }
+
+        let (input_io_index, output_io_index) = add_order_args
+            .inputs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, input)| {
+                add_order_args
+                    .outputs
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, output)| output.token != input.token)
+                    .map(move |(o, _)| (i, o))
+            })
+            .next()
+            .unwrap_or((0, 0));
+
+        Ok(Self {
+            order: OrderV3 {
+                validInputs: add_order_args.inputs,
+                validOutputs: add_order_args.outputs,
+                ..Default::default()
+            },
+            orderbook: deployment
+                .order
+                .orderbook
+                .as_ref()
+                .map_or(Address::ZERO, |v| v.address),
+            input_io_index,
+            output_io_index,
+            vault_balances,
+        })
+    }
+
+    /// Context of a single run with random owner, nonce, counterparty and
+    /// unspecified vault balances
+    pub fn random_order_context(&self, rng: &mut impl RngCore) -> OrderContext {
+        let mut order = self.order.clone();
+        order.owner = random_address(rng);
+        order.nonce = random_b256(rng);
+        let counterparty = random_address(rng);
+
+        let input_vault_balance =
+            self.vault_balance(order.validInputs.get(self.input_io_index), rng);
+        let output_vault_balance =
+            self.vault_balance(order.validOutputs.get(self.output_io_index), rng);
+
+        OrderContext {
+            order,
+            input_io_index: self.input_io_index,
+            output_io_index: self.output_io_index,
+            orderbook: self.orderbook,
+            counterparty,
+            input_vault_balance,
+            output_vault_balance,
+            signed_context: vec![],
+        }
+    }
+
+    fn vault_balance(&self, io: Option<&IO>, rng: &mut impl RngCore) -> U256 {
+        let Some(io) = io else {
+            return U256::ZERO;
+        };
+        match self.vault_balances.get(&io.token) {
+            Some(balance) => *balance,
+            None => {
+                U256::from(rng.next_u64() % FUZZ_VAULT_BALANCE_MAX_UNITS)
+                    * U256::from(10).pow(U256::from(io.decimals))
+            }
+        }
+    }
+}
+
+fn random_address(rng: &mut impl RngCore) -> Address {
+    let mut bytes = [0u8; 20];
+    rng.fill_bytes(&mut bytes);
+    Address::from(bytes)
+}
+
+fn random_b256(rng: &mut impl RngCore) -> B256 {
+    let mut bytes = [0u8; 32];
+    rng.fill_bytes(&mut bytes);
+    B256::from(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_context::{
+        CONTEXT_CALLING_CONTEXT_COLUMN, CONTEXT_VAULT_INPUTS_COLUMN,
+        CONTEXT_VAULT_IO_BALANCE_BEFORE, CONTEXT_VAULT_OUTPUTS_COLUMN,
+    };
+    use proptest::test_runner::{RngAlgorithm, TestRng};
+
+    #[test]
+    fn test_random_order_context() {
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let fuzz_order = FuzzOrder {
+            order: OrderV3 {
+                validInputs: vec![IO {
+                    token: input_token,
+                    decimals: 6,
+                    vaultId: U256::from(1),
+                }],
+                validOutputs: vec![IO {
+                    token: output_token,
+                    decimals: 18,
+                    vaultId: U256::from(2),
+                }],
+                ..Default::default()
+            },
+            orderbook: Address::random(),
+            input_io_index: 0,
+            output_io_index: 0,
+            vault_balances: HashMap::from([(output_token, U256::from(5))]),
+        };
+        let mut rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[0; 32]);
+
+        let first = fuzz_order.random_order_context(&mut rng);
+        let second = fuzz_order.random_order_context(&mut rng);
+        assert_ne!(first.order.owner, second.order.owner);
+        assert_ne!(first.order_hash(), second.order_hash());
+        assert_eq!(first.output_vault_balance, U256::from(5));
+        assert_eq!(
+            first.input_vault_balance % U256::from(1_000_000),
+            U256::ZERO
+        );
+
+        let context = first.build().unwrap();
+        assert_eq!(
+            context[CONTEXT_CALLING_CONTEXT_COLUMN][0],
+            first.order_hash()
+        );
+        assert_eq!(
+            context[CONTEXT_VAULT_INPUTS_COLUMN][0],
+            U256::from_be_slice(input_token.as_slice())
+        );
+        assert_eq!(
+            context[CONTEXT_VAULT_OUTPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_BEFORE],
+            U256::from(5)
+        );
+
+        // same seed fuzzes the same contexts
+        let mut rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[0; 32]);
+        assert_eq!(fuzz_order.random_order_context(&mut rng), first);
+    }
+}
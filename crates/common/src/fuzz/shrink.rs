@@ -0,0 +1,112 @@
+use super::{fuzz_run, FuzzRevert, FuzzRunArgs, FuzzRunner, FuzzRunnerError};
+use alloy::primitives::{hex::encode_prefixed, U256};
+use dotrain::Rebind;
+use rain_orderbook_app_settings::fuzz::FuzzDistribution;
+use std::collections::{BTreeMap, HashMap};
+
+/// Upper bound of the evaluations spent shrinking a single revert
+pub static FUZZ_SHRINK_MAX_EVALS: usize = 1024;
+
+impl FuzzRunner {
+    /// Shrinks the fuzzed bindings of a reverted run one at a time toward
+    /// the smallest value their distribution could sample, keeping each
+    /// smaller value that still reverts the entrypoint with the same error.
+    /// Values are bisected, so the result is minimal for reverts that are
+    /// monotonic in the value of the binding
+    pub(super) async fn shrink(
+        &mut self,
+        revert: &FuzzRevert,
+        args: &FuzzRunArgs,
+        fuzz: &HashMap<String, FuzzDistribution>,
+    ) -> Result<FuzzRevert, FuzzRunnerError> {
+        self.forker.roll_fork(Some(revert.block_number), None)?;
+
+        let mut inputs = revert.inputs.clone();
+        let mut evals = 0;
+        for (binding, value) in revert.inputs.iter() {
+            // sets are shrunk to their smallest member that still reverts
+            if let Some(FuzzDistribution::Set(values)) = fuzz.get(binding) {
+                let mut candidates = values
+                    .iter()
+                    .map(|v| v.0)
+                    .filter(|v| v < value)
+                    .collect::<Vec<_>>();
+                candidates.sort();
+                for candidate in candidates {
+                    if evals >= FUZZ_SHRINK_MAX_EVALS {
+                        break;
+                    }
+                    evals += 1;
+                    inputs.insert(binding.clone(), candidate);
+                    if self.reverts_with(revert, args, &inputs).await? {
+                        break;
+                    }
+                    inputs.insert(binding.clone(), *value);
+                }
+                continue;
+            }
+
+            // smallest value known to revert is the upper bound
+            let mut lower = fuzz.get(binding).map_or(U256::ZERO, shrink_target);
+            let mut upper = *value;
+            while lower < upper && evals < FUZZ_SHRINK_MAX_EVALS {
+                evals += 1;
+                let middle = lower + (upper - lower) / U256::from(2);
+                inputs.insert(binding.clone(), middle);
+                if self.reverts_with(revert, args, &inputs).await? {
+                    upper = middle;
+                } else {
+                    lower = middle + U256::from(1);
+                }
+            }
+            inputs.insert(binding.clone(), upper);
+        }
+
+        Ok(FuzzRevert {
+            inputs,
+            ..revert.clone()
+        })
+    }
+
+    /// Whether the run with the given fuzzed values reverts the entrypoint
+    /// of the revert with the same error
+    async fn reverts_with(
+        &self,
+        revert: &FuzzRevert,
+        args: &FuzzRunArgs,
+        inputs: &BTreeMap<String, U256>,
+    ) -> Result<bool, FuzzRunnerError> {
+        let mut bindings = inputs
+            .iter()
+            .map(|(k, v)| Rebind(k.clone(), encode_prefixed(v.to_be_bytes::<32>())))
+            .collect::<Vec<_>>();
+        bindings.extend(
+            args.bindings
+                .iter()
+                .filter(|v| !inputs.contains_key(&v.0))
+                .cloned(),
+        );
+
+        let results = fuzz_run(
+            &self.forker,
+            FuzzRunArgs {
+                bindings,
+                entrypoints: vec![revert.entrypoint.clone()],
+                ..args.clone()
+            },
+        )
+        .await?;
+        Ok(results.iter().any(|(entrypoint, result)| {
+            entrypoint == &revert.entrypoint && result.as_ref().err() == Some(&revert.error)
+        }))
+    }
+}
+
+/// Smallest value the distribution samples, walks can wander down to zero
+fn shrink_target(distribution: &FuzzDistribution) -> U256 {
+    match distribution {
+        FuzzDistribution::Uniform { min, .. } | FuzzDistribution::LogUniform { min, .. } => min.0,
+        FuzzDistribution::Set(values) => values.iter().map(|v| v.0).min().unwrap_or_default(),
+        FuzzDistribution::Walk { .. } => U256::ZERO,
+    }
+}
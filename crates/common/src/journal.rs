@@ -0,0 +1,392 @@
+use alloy::primitives::{private::rand, Address, Bytes, B256, U256};
+use alloy_ethers_typecast::ethers_address_to_alloy;
+use ethers::{
+    providers::{Http, Middleware, Provider, ProviderError},
+    types::{BlockNumber, TransactionReceipt, H160, H256},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{create_dir_all, read_to_string, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+pub static JOURNAL_FILE_NAME: &str = "transactions.jsonl";
+
+#[derive(Error, Debug)]
+pub enum JournalError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+    #[error("No user data directory to keep the transaction journal in")]
+    NoDataDir,
+    #[error("Transaction {0} is not in the journal")]
+    EntryNotFound(String),
+    #[error("Transaction {0} has no hash or nonce to re-attach to")]
+    NotReattachable(String),
+    #[error("Transaction {0} was sent on chain {1}, not on chain {2} of the rpc")]
+    ChainMismatch(String, u64, u64),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum JournalStatus {
+    PendingPrepare,
+    PendingSign,
+    PendingSend,
+    Confirmed,
+    /// The nonce of the transaction was used by another transaction
+    Replaced,
+    Failed(String),
+}
+
+impl JournalStatus {
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            JournalStatus::PendingPrepare | JournalStatus::PendingSign | JournalStatus::PendingSend
+        )
+    }
+}
+
+/// A write transaction recorded by the journal along with its latest status
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub id: String,
+    /// Unix timestamps in milliseconds
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub chain_id: Option<u64>,
+    pub from: Address,
+    pub to: Address,
+    /// Signature of the called function
+    pub call: String,
+    pub calldata: Bytes,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    /// Pending nonce of the sender when the transaction was sent, used to
+    /// find the transaction if it was sent but its hash is not known
+    pub nonce: Option<u64>,
+    /// Latest block when the transaction was sent
+    pub sent_at_block: Option<u64>,
+    pub status: JournalStatus,
+    pub transaction_hash: Option<B256>,
+    pub block_number: Option<u64>,
+}
+
+impl JournalEntry {
+    pub fn new(
+        chain_id: Option<u64>,
+        from: Address,
+        to: Address,
+        call: String,
+        calldata: Bytes,
+    ) -> Self {
+        let now = now_millis();
+        Self {
+            id: format!("{:016x}", rand::random::<u64>()),
+            created_at: now,
+            updated_at: now,
+            chain_id,
+            from,
+            to,
+            call,
+            calldata,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            nonce: None,
+            sent_at_block: None,
+            status: JournalStatus::PendingPrepare,
+            transaction_hash: None,
+            block_number: None,
+        }
+    }
+
+    pub fn set_status(&mut self, status: JournalStatus) {
+        self.status = status;
+        self.updated_at = now_millis();
+    }
+
+    pub fn set_confirmed(&mut self, receipt: &TransactionReceipt) {
+        self.transaction_hash = Some(B256::from(receipt.transaction_hash.0));
+        self.block_number = receipt.block_number.map(|v| v.as_u64());
+        self.set_status(JournalStatus::Confirmed);
+    }
+}
+
+/// Append only JSONL journal of write transactions, every status change of a
+/// transaction appends its entry and the last line of an entry is its
+/// current state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Journal {
+    pub path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Journal in the user data directory
+    pub fn open_default() -> Result<Self, JournalError> {
+        let path = dirs::data_dir()
+            .ok_or(JournalError::NoDataDir)?
+            .join("rain-orderbook")
+            .join(JOURNAL_FILE_NAME);
+        Ok(Self::new(path))
+    }
+
+    pub fn record(&self, entry: &JournalEntry) -> Result<(), JournalError> {
+        if let Some(dir) = self.path.parent() {
+            create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Current state of all recorded transactions, oldest first. Lines that
+    /// cannot be parsed, eg truncated by a crash mid write, are skipped
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, JournalError> {
+        if !Path::new(&self.path).exists() {
+            return Ok(vec![]);
+        }
+        let mut entries: Vec<JournalEntry> = vec![];
+        let mut positions = HashMap::new();
+        for line in read_to_string(&self.path)?.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: JournalEntry = match serde_json::from_str(line) {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("skipping corrupt journal line: {}", e);
+                    continue;
+                }
+            };
+            match positions.get(&entry.id) {
+                Some(&i) => entries[i] = entry,
+                None => {
+                    positions.insert(entry.id.clone(), entries.len());
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn pending(&self) -> Result<Vec<JournalEntry>, JournalError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|v| v.status.is_pending())
+            .collect())
+    }
+
+    pub fn get(&self, id: &str) -> Result<JournalEntry, JournalError> {
+        self.entries()?
+            .into_iter()
+            .find(|v| v.id == id)
+            .ok_or(JournalError::EntryNotFound(id.to_string()))
+    }
+
+    /// Re-attaches to a pending transaction through the given rpc of its
+    /// chain and waits for its receipt until the timeout, recording the
+    /// outcome. A transaction whose hash is not known is found by the nonce
+    /// of its sender once the nonce is used, searching the blocks since it
+    /// was sent. The entry is returned as is if it is still pending at the
+    /// timeout. An rpc of another chain than the entry's is refused, as a
+    /// transaction of the same sender and nonce there would mark the entry
+    /// as replaced
+    pub async fn wait(
+        &self,
+        entry: &JournalEntry,
+        rpc_url: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<JournalEntry, JournalError> {
+        let provider = Provider::<Http>::try_from(rpc_url)?;
+        let chain_id = provider.get_chainid().await?.as_u64();
+        if let Some(entry_chain_id) = entry.chain_id.filter(|v| *v != chain_id) {
+            return Err(JournalError::ChainMismatch(
+                entry.id.clone(),
+                entry_chain_id,
+                chain_id,
+            ));
+        }
+        let mut entry = entry.clone();
+        let started = std::time::Instant::now();
+        loop {
+            if let Some(status) = try_resolve(&provider, &entry).await? {
+                match status {
+                    Resolution::Confirmed(receipt) => entry.set_confirmed(&receipt),
+                    Resolution::Replaced(receipt) => {
+                        entry.transaction_hash = Some(B256::from(receipt.transaction_hash.0));
+                        entry.block_number = receipt.block_number.map(|v| v.as_u64());
+                        entry.set_status(JournalStatus::Replaced);
+                    }
+                }
+                self.record(&entry)?;
+                return Ok(entry);
+            }
+            if started.elapsed() >= timeout {
+                return Ok(entry);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+enum Resolution {
+    Confirmed(TransactionReceipt),
+    Replaced(TransactionReceipt),
+}
+
+async fn try_resolve(
+    provider: &Provider<Http>,
+    entry: &JournalEntry,
+) -> Result<Option<Resolution>, JournalError> {
+    if let Some(hash) = entry.transaction_hash {
+        return Ok(provider
+            .get_transaction_receipt(H256::from(hash.0))
+            .await?
+            .map(Resolution::Confirmed));
+    }
+
+    let nonce = entry
+        .nonce
+        .ok_or(JournalError::NotReattachable(entry.id.clone()))?;
+    let from = H160::from(entry.from.0 .0);
+    let latest_nonce = provider
+        .get_transaction_count(from, Some(BlockNumber::Latest.into()))
+        .await?;
+    if latest_nonce.as_u64() <= nonce {
+        return Ok(None);
+    }
+
+    // the nonce is used, find the transaction that used it
+    let latest_block = provider.get_block_number().await?.as_u64();
+    for number in entry.sent_at_block.unwrap_or(latest_block)..=latest_block {
+        let Some(block) = provider.get_block_with_txs(number).await? else {
+            continue;
+        };
+        let Some(tx) = block
+            .transactions
+            .into_iter()
+            .find(|tx| tx.from == from && tx.nonce.as_u64() == nonce)
+        else {
+            continue;
+        };
+        let Some(receipt) = provider.get_transaction_receipt(tx.hash).await? else {
+            continue;
+        };
+        let is_same_call = tx.to.map(ethers_address_to_alloy) == Some(entry.to)
+            && tx.input.as_ref() == entry.calldata.as_ref();
+        return Ok(Some(if is_same_call {
+            Resolution::Confirmed(receipt)
+        } else {
+            Resolution::Replaced(receipt)
+        }));
+    }
+    Ok(None)
+}
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_entries() {
+        let path = std::env::temp_dir()
+            .join(format!("journal-{:016x}", rand::random::<u64>()))
+            .join(JOURNAL_FILE_NAME);
+        let journal = Journal::new(path.clone());
+        assert_eq!(journal.entries().unwrap(), vec![]);
+
+        let mut first = JournalEntry::new(
+            Some(1),
+            Address::random(),
+            Address::random(),
+            "deposit2(address,uint256,uint256,(address,address,bytes)[])".to_string(),
+            Bytes::from(vec![1, 2, 3]),
+        );
+        let mut second = first.clone();
+        second.id = "second".to_string();
+
+        journal.record(&first).unwrap();
+        journal.record(&second).unwrap();
+        first.nonce = Some(7);
+        first.set_status(JournalStatus::PendingSend);
+        journal.record(&first).unwrap();
+        second.set_confirmed(&TransactionReceipt {
+            transaction_hash: H256::repeat_byte(1),
+            block_number: Some(10.into()),
+            ..Default::default()
+        });
+        journal.record(&second).unwrap();
+        // a truncated line is skipped
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"id\":\"trunc")
+            .unwrap();
+
+        let result = journal.entries().unwrap();
+        assert_eq!(result, vec![first.clone(), second.clone()]);
+        assert_eq!(result[1].transaction_hash, Some(B256::repeat_byte(1)));
+        assert_eq!(result[1].block_number, Some(10));
+        assert_eq!(journal.pending().unwrap(), vec![first.clone()]);
+        assert_eq!(journal.get(&first.id).unwrap(), first);
+        assert!(matches!(
+            journal.get("unknown"),
+            Err(JournalError::EntryNotFound(_))
+        ));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_other_chain() {
+        let path = std::env::temp_dir()
+            .join(format!("journal-{:016x}", rand::random::<u64>()))
+            .join(JOURNAL_FILE_NAME);
+        let journal = Journal::new(path);
+        let mut entry = JournalEntry::new(
+            Some(1),
+            Address::random(),
+            Address::random(),
+            "deposit2(address,uint256,uint256,(address,address,bytes)[])".to_string(),
+            Bytes::from(vec![1, 2, 3]),
+        );
+        entry.nonce = Some(0);
+
+        let result = journal
+            .wait(
+                &entry,
+                rain_orderbook_env::CI_DEPLOY_POLYGON_RPC_URL,
+                Duration::from_secs(1),
+                Duration::ZERO,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(JournalError::ChainMismatch(id, 1, 137)) if id == entry.id
+        ));
+        assert_eq!(journal.entries().unwrap(), vec![]);
+    }
+}
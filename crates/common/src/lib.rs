@@ -1,17 +1,34 @@
 pub mod add_order;
+pub mod batch;
+#[cfg(not(target_family = "wasm"))]
+pub mod clear;
 pub mod csv;
+#[cfg(not(target_family = "wasm"))]
+pub mod deployment_quote;
 pub mod deposit;
 pub mod dotrain_add_order_lsp;
 pub mod dotrain_order;
+#[cfg(not(target_family = "wasm"))]
+pub mod fee;
 pub mod frontmatter;
 #[cfg(not(target_family = "wasm"))]
 pub mod fuzz;
+#[cfg(not(target_family = "wasm"))]
+pub mod journal;
 pub mod meta;
+pub mod order_context;
 pub mod rainlang;
+#[cfg(not(target_family = "wasm"))]
+pub mod receipt;
 pub mod remove_order;
+pub mod safe;
+#[cfg(not(target_family = "wasm"))]
+pub mod simulate;
 pub mod subgraph;
+pub mod task;
 pub mod transaction;
 pub mod types;
+pub mod update_order;
 pub mod utils;
 pub mod withdraw;
 
@@ -0,0 +1,331 @@
+use alloy::primitives::{keccak256, Address, U256};
+use alloy::sol_types::SolValue;
+use rain_interpreter_bindings::IInterpreterStoreV1::FullyQualifiedNamespace;
+use rain_orderbook_bindings::IOrderBookV4::{OrderV3, SignedContextV1};
+use thiserror::Error;
+
+/// Column of the orderbook context that holds the calling context
+pub const CONTEXT_CALLING_CONTEXT_COLUMN: usize = 1;
+/// Column of the orderbook context that holds the calculations of calculate-io
+pub const CONTEXT_CALCULATIONS_COLUMN: usize = 2;
+/// Column of the orderbook context that holds the input vault details
+pub const CONTEXT_VAULT_INPUTS_COLUMN: usize = 3;
+/// Column of the orderbook context that holds the output vault details
+pub const CONTEXT_VAULT_OUTPUTS_COLUMN: usize = 4;
+/// Row of the vault columns that holds the vault balance before the trade
+pub const CONTEXT_VAULT_IO_BALANCE_BEFORE: usize = 3;
+/// Row of the vault columns that holds the vault balance change of the trade
+pub const CONTEXT_VAULT_IO_BALANCE_DIFF: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum OrderContextError {
+    #[error("Input IO index {0} out of bounds")]
+    InputIndexOutOfBounds(usize),
+    #[error("Output IO index {0} out of bounds")]
+    OutputIndexOutOfBounds(usize),
+}
+
+/// Builds the context of an order evaluation the same way orderbook contract
+/// does in `calculateOrderIO`, so that calculate-io and handle-io can be
+/// evaluated off the orderbook with the values they would see on it.
+/// Vault balances are in token decimals and are scaled to 18 decimals
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderContext {
+    pub order: OrderV3,
+    pub input_io_index: usize,
+    pub output_io_index: usize,
+    pub orderbook: Address,
+    pub counterparty: Address,
+    pub input_vault_balance: U256,
+    pub output_vault_balance: U256,
+    pub signed_context: Vec<SignedContextV1>,
+}
+
+impl OrderContext {
+    /// Order hash of the context's order
+    pub fn order_hash(&self) -> U256 {
+        keccak256(self.order.abi_encode()).into()
+    }
+
+    /// Builds the context matrix for calculate-io, the calculations column
+    /// is empty as they are not known yet
+    pub fn build(&self) -> Result<Vec<Vec<U256>>, OrderContextError> {
+        let input = self.order.validInputs.get(self.input_io_index).ok_or(
+            OrderContextError::InputIndexOutOfBounds(self.input_io_index),
+        )?;
+        let output = self.order.validOutputs.get(self.output_io_index).ok_or(
+            OrderContextError::OutputIndexOutOfBounds(self.output_io_index),
+        )?;
+
+        let mut context = vec![
+            // base column
+            vec![
+                address_to_u256(self.counterparty),
+                address_to_u256(self.orderbook),
+            ],
+            // calling context
+            vec![
+                self.order_hash(),
+                address_to_u256(self.order.owner),
+                address_to_u256(self.counterparty),
+            ],
+            // calculations
+            vec![],
+            // input vault
+            vec![
+                address_to_u256(input.token),
+                U256::from(input.decimals) * U256::from(10).pow(U256::from(18)),
+                input.vaultId,
+                scale18(self.input_vault_balance, input.decimals),
+                U256::ZERO,
+            ],
+            // output vault
+            vec![
+                address_to_u256(output.token),
+                U256::from(output.decimals) * U256::from(10).pow(U256::from(18)),
+                output.vaultId,
+                scale18(self.output_vault_balance, output.decimals),
+                U256::ZERO,
+            ],
+        ];
+
+        // signers column followed by a column per signed context
+        if !self.signed_context.is_empty() {
+            context.push(
+                self.signed_context
+                    .iter()
+                    .map(|v| address_to_u256(v.signer))
+                    .collect(),
+            );
+            context.extend(self.signed_context.iter().map(|v| v.context.clone()));
+        }
+
+        Ok(context)
+    }
+
+    /// Builds the context matrix for handle-io of a take of the full max
+    /// output at the given io ratio, ie with the calculations of calculate-io
+    /// and the resulting vault balance changes. The max output is capped by
+    /// the output vault balance, also in the calculations column, and the
+    /// input is rounded up, as orderbook does
+    pub fn build_handle_io(
+        &self,
+        max_output: U256,
+        io_ratio: U256,
+    ) -> Result<Vec<Vec<U256>>, OrderContextError> {
+        let mut context = self.build()?;
+        let output = self.cap_max_output(max_output);
+        let one = U256::from(10).pow(U256::from(18));
+        let input = output
+            .saturating_mul(io_ratio)
+            .saturating_add(one - U256::from(1))
+            / one;
+
+        context[CONTEXT_CALCULATIONS_COLUMN] = vec![output, io_ratio];
+        context[CONTEXT_VAULT_INPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_DIFF] = input;
+        context[CONTEXT_VAULT_OUTPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_DIFF] = output;
+        Ok(context)
+    }
+
+    /// Builds the context matrix of the post-add-order task, ie the base
+    /// column followed by the order hash and owner
+    pub fn build_post_add_order(&self) -> Vec<Vec<U256>> {
+        vec![
+            vec![
+                address_to_u256(self.order.owner),
+                address_to_u256(self.orderbook),
+            ],
+            vec![self.order_hash(), address_to_u256(self.order.owner)],
+        ]
+    }
+
+    /// Namespace orderbook evaluates the order in, ie the owner's namespace
+    /// qualified by the orderbook address
+    pub fn namespace(&self) -> FullyQualifiedNamespace {
+        qualify_namespace(self.order.owner, self.orderbook)
+    }
+
+    /// Caps the given max output by the output vault balance, as the order
+    /// owner cant send more than their vault balance
+    pub fn cap_max_output(&self, max_output: U256) -> U256 {
+        let decimals = self
+            .order
+            .validOutputs
+            .get(self.output_io_index)
+            .map_or(18, |v| v.decimals);
+        max_output.min(scale18(self.output_vault_balance, decimals))
+    }
+}
+
+/// Qualifies the namespace of the given order owner by the orderbook address
+/// the same way orderbook does before evaluating the owner's orders
+pub fn qualify_namespace(owner: Address, orderbook: Address) -> FullyQualifiedNamespace {
+    FullyQualifiedNamespace::from(U256::from_be_bytes(
+        keccak256((address_to_u256(owner), orderbook).abi_encode()).0,
+    ))
+}
+
+fn address_to_u256(address: Address) -> U256 {
+    U256::from_be_slice(address.as_slice())
+}
+
+/// Scales the given token amount with the given decimals to 18 decimals,
+/// rounding down and saturating
+pub fn scale18(value: U256, decimals: u8) -> U256 {
+    if decimals > 18 {
+        value / U256::from(10).pow(U256::from(decimals - 18))
+    } else {
+        value.saturating_mul(U256::from(10).pow(U256::from(18 - decimals)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_orderbook_bindings::IOrderBookV4::IO;
+
+    fn order() -> OrderV3 {
+        OrderV3 {
+            owner: Address::random(),
+            validInputs: vec![IO {
+                token: Address::random(),
+                decimals: 6,
+                vaultId: U256::from(1),
+            }],
+            validOutputs: vec![IO {
+                token: Address::random(),
+                decimals: 18,
+                vaultId: U256::from(2),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scale18() {
+        assert_eq!(
+            scale18(U256::from(1), 6),
+            U256::from(10).pow(U256::from(12))
+        );
+        assert_eq!(scale18(U256::from(5), 18), U256::from(5));
+        assert_eq!(scale18(U256::from(1234), 20), U256::from(12));
+        assert_eq!(scale18(U256::MAX, 0), U256::MAX);
+    }
+
+    #[test]
+    fn test_build_context() {
+        let order = order();
+        let signer = Address::random();
+        let order_context = OrderContext {
+            order: order.clone(),
+            input_io_index: 0,
+            output_io_index: 0,
+            orderbook: Address::random(),
+            counterparty: Address::random(),
+            input_vault_balance: U256::from(3),
+            output_vault_balance: U256::from(4),
+            signed_context: vec![SignedContextV1 {
+                signer,
+                context: vec![U256::from(7), U256::from(8)],
+                signature: vec![].into(),
+            }],
+        };
+        let context = order_context.build().unwrap();
+        assert_eq!(context.len(), 7);
+        assert_eq!(
+            context[CONTEXT_CALLING_CONTEXT_COLUMN][0],
+            U256::from_be_bytes(keccak256(order.abi_encode()).0)
+        );
+        assert_eq!(
+            context[CONTEXT_CALLING_CONTEXT_COLUMN][1],
+            address_to_u256(order.owner)
+        );
+        assert!(context[CONTEXT_CALCULATIONS_COLUMN].is_empty());
+        assert_eq!(
+            context[CONTEXT_VAULT_INPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_BEFORE],
+            U256::from(3) * U256::from(10).pow(U256::from(12))
+        );
+        assert_eq!(
+            context[CONTEXT_VAULT_OUTPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_BEFORE],
+            U256::from(4)
+        );
+        assert_eq!(context[5], vec![address_to_u256(signer)]);
+        assert_eq!(context[6], vec![U256::from(7), U256::from(8)]);
+
+        assert_eq!(order_context.cap_max_output(U256::from(10)), U256::from(4));
+        assert_eq!(order_context.cap_max_output(U256::from(1)), U256::from(1));
+
+        let context = order_context
+            .build_handle_io(
+                U256::from(10),
+                U256::from(2) * U256::from(10).pow(U256::from(18)),
+            )
+            .unwrap();
+        assert_eq!(
+            context[CONTEXT_CALCULATIONS_COLUMN],
+            vec![
+                U256::from(4),
+                U256::from(2) * U256::from(10).pow(U256::from(18))
+            ]
+        );
+        assert_eq!(
+            context[CONTEXT_VAULT_OUTPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_DIFF],
+            U256::from(4)
+        );
+        assert_eq!(
+            context[CONTEXT_VAULT_INPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_DIFF],
+            U256::from(8)
+        );
+
+        let context = order_context.build_post_add_order();
+        assert_eq!(context.len(), 2);
+        assert_eq!(context[1][0], order_context.order_hash());
+
+        let order_context = OrderContext {
+            output_io_index: 1,
+            ..order_context
+        };
+        assert!(matches!(
+            order_context.build(),
+            Err(OrderContextError::OutputIndexOutOfBounds(1))
+        ));
+    }
+
+    #[test]
+    fn test_build_handle_io_capped_max_output() {
+        let one = U256::from(10).pow(U256::from(18));
+        let order_context = OrderContext {
+            order: order(),
+            input_io_index: 0,
+            output_io_index: 0,
+            orderbook: Address::random(),
+            counterparty: Address::random(),
+            input_vault_balance: U256::ZERO,
+            // 5 units of an 18 decimals token
+            output_vault_balance: U256::from(5) * one,
+            signed_context: vec![],
+        };
+
+        // the output vault balance is below the max output
+        let context = order_context
+            .build_handle_io(U256::from(100) * one, one)
+            .unwrap();
+        assert_eq!(
+            context[CONTEXT_CALCULATIONS_COLUMN],
+            vec![U256::from(5) * one, one]
+        );
+        assert_eq!(
+            context[CONTEXT_VAULT_OUTPUTS_COLUMN][CONTEXT_VAULT_IO_BALANCE_DIFF],
+            U256::from(5) * one
+        );
+
+        // the max output is below the output vault balance
+        let context = order_context
+            .build_handle_io(U256::from(2) * one, one)
+            .unwrap();
+        assert_eq!(
+            context[CONTEXT_CALCULATIONS_COLUMN],
+            vec![U256::from(2) * one, one]
+        );
+    }
+}
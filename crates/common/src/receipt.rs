@@ -0,0 +1,143 @@
+use alloy::primitives::{Address, Bytes, Log, LogData, B256};
+use alloy::sol_types::{SolEvent, SolEventInterface};
+use alloy_ethers_typecast::ethers_address_to_alloy;
+use ethers::types::TransactionReceipt;
+use rain_orderbook_bindings::IOrderBookV4::{
+    AddOrderV2, Deposit, IOrderBookV4Events, RemoveOrderV2, Withdraw,
+};
+
+/// A confirmed transaction and the logs emitted by the orderbook it was sent to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmedTransaction {
+    pub transaction_hash: B256,
+    pub block_number: Option<u64>,
+    /// Logs emitted by the orderbook, in the order they were emitted
+    pub logs: Vec<Log>,
+}
+
+impl ConfirmedTransaction {
+    /// Keeps the logs of the given receipt that were emitted by the given orderbook
+    pub fn from_receipt(receipt: &TransactionReceipt, orderbook: Address) -> Self {
+        let logs = receipt
+            .logs
+            .iter()
+            .filter(|log| ethers_address_to_alloy(log.address) == orderbook)
+            .map(|log| Log {
+                address: orderbook,
+                data: LogData::new_unchecked(
+                    log.topics.iter().map(|topic| B256::from(topic.0)).collect(),
+                    Bytes::from(log.data.to_vec()),
+                ),
+            })
+            .collect();
+        Self {
+            transaction_hash: B256::from(receipt.transaction_hash.0),
+            block_number: receipt.block_number.map(|v| v.as_u64()),
+            logs,
+        }
+    }
+
+    /// Decodes the orderbook logs, logs that are not orderbook events are skipped
+    pub fn events(&self) -> Vec<IOrderBookV4Events> {
+        self.logs
+            .iter()
+            .filter_map(|log| {
+                IOrderBookV4Events::decode_raw_log(log.topics(), &log.data.data, true).ok()
+            })
+            .collect()
+    }
+
+    /// Decodes the first orderbook log that is the given event
+    pub fn find_event<E: SolEvent>(&self) -> Option<E> {
+        self.logs
+            .iter()
+            .find_map(|log| E::decode_log_data(&log.data, true).ok())
+    }
+
+    /// `AddOrderV2` event of an add order transaction, none if the order
+    /// already existed
+    pub fn added_order(&self) -> Option<AddOrderV2> {
+        self.find_event()
+    }
+
+    /// `RemoveOrderV2` event of a remove order transaction, none if the order
+    /// was not live
+    pub fn removed_order(&self) -> Option<RemoveOrderV2> {
+        self.find_event()
+    }
+
+    /// `Deposit` event of a deposit transaction
+    pub fn deposit(&self) -> Option<Deposit> {
+        self.find_event()
+    }
+
+    /// `Withdraw` event of a withdraw transaction, its amount is the actually
+    /// withdrawn amount which may be less than the requested one
+    pub fn withdrawal(&self) -> Option<Withdraw> {
+        self.find_event()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+    use rain_orderbook_bindings::IOrderBookV4::OrderV3;
+
+    fn ethers_log(address: Address, data: LogData) -> ethers::types::Log {
+        ethers::types::Log {
+            address: ethers::types::H160::from(address.0 .0),
+            topics: data
+                .topics()
+                .iter()
+                .map(|topic| ethers::types::H256::from(topic.0))
+                .collect(),
+            data: data.data.to_vec().into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_confirmed_transaction_from_receipt() {
+        let orderbook = Address::random();
+        let deposit = Deposit {
+            sender: Address::random(),
+            token: Address::random(),
+            vaultId: U256::from(1),
+            amount: U256::from(10),
+        };
+        let add_order = AddOrderV2 {
+            sender: Address::random(),
+            orderHash: B256::random(),
+            order: OrderV3::default(),
+        };
+        let receipt = TransactionReceipt {
+            transaction_hash: ethers::types::H256::repeat_byte(1),
+            block_number: Some(5.into()),
+            logs: vec![
+                ethers_log(Address::random(), deposit.encode_log_data()),
+                ethers_log(orderbook, add_order.encode_log_data()),
+                ethers_log(orderbook, deposit.encode_log_data()),
+            ],
+            ..Default::default()
+        };
+
+        let result = ConfirmedTransaction::from_receipt(&receipt, orderbook);
+        assert_eq!(result.transaction_hash, B256::repeat_byte(1));
+        assert_eq!(result.block_number, Some(5));
+        assert_eq!(result.logs.len(), 2);
+        assert_eq!(
+            result.events(),
+            vec![
+                IOrderBookV4Events::AddOrderV2(add_order.clone()),
+                IOrderBookV4Events::Deposit(deposit.clone()),
+            ]
+        );
+        assert_eq!(result.find_event::<AddOrderV2>(), Some(add_order.clone()));
+        assert_eq!(result.find_event::<Deposit>(), Some(deposit.clone()));
+        assert_eq!(result.added_order(), Some(add_order));
+        assert_eq!(result.deposit(), Some(deposit));
+        assert_eq!(result.removed_order(), None);
+        assert_eq!(result.withdrawal(), None);
+    }
+}
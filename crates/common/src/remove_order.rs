@@ -1,11 +1,17 @@
+use crate::transaction::{TransactionArgsError, WritableTransactionExecuteError};
 #[cfg(not(target_family = "wasm"))]
-use crate::transaction::TransactionArgs;
-use crate::transaction::TransactionArgsError;
+use crate::{
+    receipt::ConfirmedTransaction,
+    simulate::{SimulationError, WriteSimulation, WriteSimulator},
+    transaction::{SignerClient, TransactionArgs},
+};
 use alloy::primitives::hex::FromHexError;
+#[cfg(not(target_family = "wasm"))]
+use alloy::primitives::Address;
 use alloy::sol_types::SolCall;
 use alloy_ethers_typecast::transaction::WritableClientError;
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::transaction::{WriteTransaction, WriteTransactionStatus};
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use rain_orderbook_bindings::IOrderBookV4::removeOrder2Call;
 use rain_orderbook_subgraph_client::types::{
     order_detail::Order, order_detail_traits::OrderDetailError,
@@ -23,6 +29,11 @@ pub enum RemoveOrderArgsError {
     FromHexError(#[from] FromHexError),
     #[error(transparent)]
     OrderDetailError(#[from] OrderDetailError),
+    #[error(transparent)]
+    WritableTransactionExecuteError(#[from] WritableTransactionExecuteError),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    SimulationError(#[from] SimulationError),
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -48,14 +59,15 @@ impl TryInto<removeOrder2Call> for RemoveOrderArgs {
 }
 
 impl RemoveOrderArgs {
+    /// Execute the remove order call, the confirmed transaction has the
+    /// `RemoveOrderV2` event of the removed order
     #[cfg(not(target_family = "wasm"))]
     pub async fn execute<S: Fn(WriteTransactionStatus<removeOrder2Call>)>(
         self,
         transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
         transaction_status_changed: S,
-    ) -> Result<(), RemoveOrderArgsError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
+    ) -> Result<ConfirmedTransaction, RemoveOrderArgsError> {
         let remove_order_call: removeOrder2Call = self.try_into()?;
         let params = transaction_args
             .try_into_write_contract_parameters(
@@ -64,11 +76,31 @@ impl RemoveOrderArgs {
             )
             .await?;
 
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        let receipt = signer_client
+            .write(params, transaction_status_changed)
             .await?;
 
-        Ok(())
+        Ok(ConfirmedTransaction::from_receipt(
+            &receipt,
+            transaction_args.orderbook_address,
+        ))
+    }
+
+    /// Simulates the remove order call from the signer on a fork of the
+    /// latest block without signing
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn simulate(
+        self,
+        transaction_args: TransactionArgs,
+        from: Address,
+    ) -> Result<WriteSimulation, RemoveOrderArgsError> {
+        let orderbook = transaction_args.orderbook_address;
+        let mut simulator = WriteSimulator::new(&transaction_args.rpc_url, from, None).await?;
+
+        let remove_order_call: removeOrder2Call = self.try_into()?;
+        Ok(simulator
+            .simulate(orderbook, remove_order_call, orderbook, &[])
+            .await?)
     }
 
     pub async fn get_rm_order_calldata(self) -> Result<Vec<u8>, RemoveOrderArgsError> {
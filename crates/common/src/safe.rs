@@ -0,0 +1,171 @@
+use crate::{
+    batch::{BatchArgs, BatchError},
+    deposit::DepositArgs,
+};
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol_types::SolCall;
+use rain_orderbook_app_settings::network::Network;
+use rain_orderbook_bindings::{
+    IOrderBookV4::{deposit2Call, withdraw2Call},
+    IERC20::approveCall,
+};
+use serde::{Deserialize, Serialize};
+
+pub static SAFE_TX_BUILDER_BATCH_VERSION: &str = "1.0";
+pub static SAFE_TX_BUILDER_VERSION: &str = "1.16.5";
+
+/// A single call of a Safe Transaction Builder batch
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SafeTransaction {
+    pub to: Address,
+    /// Native token value sent with the call, in wei as a decimal string
+    pub value: String,
+    pub data: Bytes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeTransactionBatchMeta {
+    pub name: String,
+    pub description: String,
+    pub tx_builder_version: String,
+}
+
+/// Calls exported as a Safe Transaction Builder JSON batch file, the batch
+/// is imported into the Safe app and executed by the Safe in a single
+/// transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeTransactionBatch {
+    pub version: String,
+    pub chain_id: String,
+    /// Unix timestamp in milliseconds
+    pub created_at: u64,
+    pub meta: SafeTransactionBatchMeta,
+    pub transactions: Vec<SafeTransaction>,
+}
+
+impl SafeTransactionBatch {
+    pub fn new(chain_id: u64, name: String) -> Self {
+        Self {
+            version: SAFE_TX_BUILDER_BATCH_VERSION.to_string(),
+            chain_id: chain_id.to_string(),
+            created_at: chrono::Utc::now().timestamp_millis() as u64,
+            meta: SafeTransactionBatchMeta {
+                name,
+                description: String::new(),
+                tx_builder_version: SAFE_TX_BUILDER_VERSION.to_string(),
+            },
+            transactions: vec![],
+        }
+    }
+
+    /// create a new instance for the chain of the given Network
+    pub fn new_from_network(network: &Network, name: String) -> Self {
+        Self::new(network.chain_id, name)
+    }
+
+    /// Adds a call to the given target, ie the orderbook or a token
+    pub fn add_call<T: SolCall>(&mut self, to: Address, value: U256, call: &T) -> &mut Self {
+        self.transactions.push(SafeTransaction {
+            to,
+            value: value.to_string(),
+            data: Bytes::from(call.abi_encode()),
+        });
+        self
+    }
+
+    /// Adds the approval of the deposit amount followed by the deposit, the
+    /// current allowance of the Safe is unknown so the full amount is approved
+    pub fn add_deposit(&mut self, orderbook: Address, args: &DepositArgs) -> &mut Self {
+        let deposit_call: deposit2Call = args.clone().into();
+        self.add_call(
+            args.token,
+            U256::ZERO,
+            &approveCall {
+                spender: orderbook,
+                amount: args.amount,
+            },
+        )
+        .add_call(orderbook, U256::ZERO, &deposit_call)
+    }
+
+    pub fn add_withdraw(&mut self, orderbook: Address, call: &withdraw2Call) -> &mut Self {
+        self.add_call(orderbook, U256::ZERO, call)
+    }
+
+    /// Adds an approval of the total deposit amount of each deposited token
+    /// followed by the multicall of the batch
+    pub fn add_batch(
+        &mut self,
+        orderbook: Address,
+        batch: &BatchArgs,
+    ) -> Result<&mut Self, BatchError> {
+        let multicall = batch.get_multicall()?;
+        for (token, total) in batch.get_deposit_totals() {
+            self.add_call(
+                token,
+                U256::ZERO,
+                &approveCall {
+                    spender: orderbook,
+                    amount: total,
+                },
+            );
+        }
+        Ok(self.add_call(orderbook, U256::ZERO, &multicall))
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_transaction_batch_deposit() {
+        let orderbook = Address::random();
+        let token = Address::random();
+        let args = DepositArgs {
+            token,
+            vault_id: U256::from(1),
+            amount: U256::from(1000),
+            tasks: vec![],
+        };
+
+        let mut batch = SafeTransactionBatch::new(137, "Deposit".to_string());
+        batch.add_deposit(orderbook, &args);
+
+        assert_eq!(batch.chain_id, "137");
+        assert_eq!(batch.transactions.len(), 2);
+        assert_eq!(batch.transactions[0].to, token);
+        assert_eq!(
+            batch.transactions[0].data,
+            Bytes::from(
+                approveCall {
+                    spender: orderbook,
+                    amount: U256::from(1000),
+                }
+                .abi_encode()
+            )
+        );
+        assert_eq!(batch.transactions[1].to, orderbook);
+        assert_eq!(batch.transactions[1].value, "0");
+        assert_eq!(
+            batch.transactions[1].data,
+            Bytes::from(deposit2Call::from(args).abi_encode())
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&batch.to_json().unwrap()).unwrap();
+        assert_eq!(json["version"], "1.0");
+        assert_eq!(json["chainId"], "137");
+        assert_eq!(json["meta"]["txBuilderVersion"], SAFE_TX_BUILDER_VERSION);
+        assert_eq!(json["transactions"][1]["value"], "0");
+        assert_eq!(
+            json["transactions"][1]["to"],
+            serde_json::to_value(orderbook).unwrap()
+        );
+    }
+}
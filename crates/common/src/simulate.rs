@@ -0,0 +1,326 @@
+use alloy::primitives::{Address, Bytes, Log, U256};
+use alloy::sol_types::{SolCall, SolEventInterface};
+use alloy_ethers_typecast::transaction::{ReadableClientError, ReadableClientHttp};
+use rain_interpreter_eval::{
+    error::ForkCallError,
+    fork::{Forker, NewForkedEvm},
+};
+use rain_orderbook_bindings::{
+    IOrderBookV4::{vaultBalanceCall, IOrderBookV4Events},
+    IERC20::IERC20Events,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SimulationError {
+    #[error(transparent)]
+    ForkCallError(#[from] ForkCallError),
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+}
+
+/// A vault whose balance change is reported by a simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedVault {
+    pub owner: Address,
+    pub token: Address,
+    pub vault_id: U256,
+}
+
+/// Balance of a watched vault before and after a simulated call, in token decimals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultBalanceDelta {
+    pub vault: WatchedVault,
+    pub before: U256,
+    pub after: U256,
+}
+
+/// An event emitted by a simulated call, decoded against the orderbook and
+/// ERC20 abis if possible
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedEvent {
+    pub address: Address,
+    pub description: String,
+}
+
+/// Outcome of a write call simulated on a fork from the sender
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteSimulation {
+    pub block_number: u64,
+    pub from: Address,
+    pub to: Address,
+    pub calldata: Bytes,
+    pub gas_used: u64,
+    /// Decoded revert of the call, if it reverted
+    pub error: Option<String>,
+    pub events: Vec<SimulatedEvent>,
+    pub vault_deltas: Vec<VaultBalanceDelta>,
+}
+
+impl WriteSimulation {
+    pub fn reverted(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Simulates write calls of a sender on a fork, calls are committed so that
+/// a sequence of calls, eg an approve followed by a deposit, is simulated
+/// the same way it would be executed on chain
+pub struct WriteSimulator {
+    forker: Forker,
+    block_number: u64,
+    from: Address,
+}
+
+impl WriteSimulator {
+    /// Forks the given rpc at the given block, latest block if not specified
+    pub async fn new(
+        rpc_url: &str,
+        from: Address,
+        block_number: Option<u64>,
+    ) -> Result<Self, SimulationError> {
+        let block_number = match block_number {
+            Some(v) => v,
+            None => {
+                ReadableClientHttp::new_from_url(rpc_url.to_string())?
+                    .get_block_number()
+                    .await?
+            }
+        };
+        let mut forker = Forker::new();
+        forker
+            .add_or_select(
+                NewForkedEvm {
+                    fork_url: rpc_url.to_string(),
+                    fork_block_number: Some(block_number),
+                },
+                None,
+            )
+            .await?;
+
+        Ok(Self {
+            forker,
+            block_number,
+            from,
+        })
+    }
+
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    pub fn from(&self) -> Address {
+        self.from
+    }
+
+    /// Reads the given call on the current state of the fork
+    pub async fn read<T: SolCall>(
+        &self,
+        to: Address,
+        call: T,
+    ) -> Result<T::Return, SimulationError> {
+        Ok(self
+            .forker
+            .alloy_call(self.from, to, call, true)
+            .await?
+            .typed_return)
+    }
+
+    /// Simulates the given call from the sender and reports its gas usage,
+    /// revert, emitted events and the balance changes of the watched vaults
+    /// of the given orderbook. A reverted call is reported rather than
+    /// returned as an error
+    pub async fn simulate<T: SolCall>(
+        &mut self,
+        to: Address,
+        call: T,
+        orderbook: Address,
+        watched_vaults: &[WatchedVault],
+    ) -> Result<WriteSimulation, SimulationError> {
+        let mut before = vec![];
+        for vault in watched_vaults {
+            before.push(self.vault_balance(orderbook, vault).await?);
+        }
+
+        let calldata = Bytes::from(call.abi_encode());
+        let (gas_used, events, error) =
+            match self
+                .forker
+                .alloy_call_committing(self.from, to, call, U256::ZERO, true)
+            {
+                Ok(res) => (
+                    res.raw.gas_used,
+                    res.raw.logs.iter().map(decode_log).collect(),
+                    None,
+                ),
+                // reverts are decoded by the forker
+                Err(e) => (0, vec![], Some(e.to_string())),
+            };
+
+        let mut vault_deltas = vec![];
+        for (vault, before) in watched_vaults.iter().zip(before) {
+            vault_deltas.push(VaultBalanceDelta {
+                vault: *vault,
+                before,
+                after: self.vault_balance(orderbook, vault).await?,
+            });
+        }
+
+        Ok(WriteSimulation {
+            block_number: self.block_number,
+            from: self.from,
+            to,
+            calldata,
+            gas_used,
+            error,
+            events,
+            vault_deltas,
+        })
+    }
+
+    async fn vault_balance(
+        &self,
+        orderbook: Address,
+        vault: &WatchedVault,
+    ) -> Result<U256, SimulationError> {
+        Ok(self
+            .read(
+                orderbook,
+                vaultBalanceCall {
+                    owner: vault.owner,
+                    token: vault.token,
+                    vaultId: vault.vault_id,
+                },
+            )
+            .await?
+            ._0)
+    }
+}
+
+fn decode_log(log: &Log) -> SimulatedEvent {
+    let topics = log.topics();
+    let data = &log.data.data;
+    let description = if let Ok(event) = IOrderBookV4Events::decode_raw_log(topics, data, true) {
+        format!("{:?}", event)
+    } else if let Ok(event) = IERC20Events::decode_raw_log(topics, data, true) {
+        format!("{:?}", event)
+    } else {
+        match topics.first() {
+            Some(topic) => format!("Unknown event {}", topic),
+            None => "Anonymous event".to_string(),
+        }
+    };
+    SimulatedEvent {
+        address: log.address,
+        description,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::LogData;
+    use alloy::sol_types::SolEvent;
+    use rain_orderbook_bindings::IERC20::{approveCall, transferCall, Transfer};
+    use rain_orderbook_env::CI_DEPLOY_POLYGON_RPC_URL;
+
+    #[test]
+    fn test_decode_log() {
+        let token = Address::random();
+        let event = Transfer {
+            from: Address::random(),
+            to: Address::random(),
+            value: U256::from(10),
+        };
+        let log = Log {
+            address: token,
+            data: event.encode_log_data(),
+        };
+
+        let result = decode_log(&log);
+        assert_eq!(result.address, token);
+        assert_eq!(
+            result.description,
+            format!("{:?}", IERC20Events::Transfer(event))
+        );
+
+        let topic = alloy::primitives::B256::random();
+        let log = Log {
+            address: token,
+            data: LogData::new_unchecked(vec![topic], Bytes::new()),
+        };
+        assert_eq!(
+            decode_log(&log).description,
+            format!("Unknown event {}", topic)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_simulate_reverted_call() {
+        // DAI and the orderbook on polygon
+        let token = "0x8f3Cf7ad23Cd3CaDbD9735AFf958023239c6A063"
+            .parse::<Address>()
+            .unwrap();
+        let orderbook = "0x2f209e5b67A33B8fE96E28f24628dF6Da301c8eB"
+            .parse::<Address>()
+            .unwrap();
+        let from = Address::random();
+        let vault = WatchedVault {
+            owner: from,
+            token,
+            vault_id: U256::from(1),
+        };
+        let mut simulator = WriteSimulator::new(CI_DEPLOY_POLYGON_RPC_URL, from, None)
+            .await
+            .unwrap();
+
+        let result = simulator
+            .simulate(
+                token,
+                approveCall {
+                    spender: orderbook,
+                    amount: U256::from(10),
+                },
+                orderbook,
+                &[vault],
+            )
+            .await
+            .unwrap();
+        assert!(!result.reverted());
+        assert!(result.gas_used > 0);
+        assert_eq!(result.events.len(), 1);
+        assert_eq!(result.events[0].address, token);
+
+        // the sender holds no DAI so the transfer reverts
+        let result = simulator
+            .simulate(
+                token,
+                transferCall {
+                    to: Address::random(),
+                    amount: U256::from(10),
+                },
+                orderbook,
+                &[vault],
+            )
+            .await
+            .unwrap();
+        assert!(result.reverted());
+        assert_eq!(result.from, from);
+        assert_eq!(result.to, token);
+        assert_eq!(result.gas_used, 0);
+        assert!(result.events.is_empty());
+        assert_eq!(
+            result.vault_deltas,
+            vec![VaultBalanceDelta {
+                vault,
+                before: U256::ZERO,
+                after: U256::ZERO,
+            }]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::rainlang::compose_to_rainlang;
+use alloy::primitives::Address;
+use alloy_ethers_typecast::transaction::{ReadableClientError, ReadableClientHttp};
+use dotrain::error::ComposeError;
+use rain_interpreter_dispair::{DISPair, DISPairError};
+use rain_interpreter_parser::{Parser2, ParserError, ParserV2};
+use rain_orderbook_app_settings::deployment::Deployment;
+use rain_orderbook_bindings::IOrderBookV4::{EvaluableV3, TaskV1};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+pub static ORDERBOOK_DEPOSIT_POST_TASK_ENTRYPOINTS: [&str; 1] = ["post-deposit"];
+pub static ORDERBOOK_WITHDRAW_POST_TASK_ENTRYPOINTS: [&str; 1] = ["post-withdraw"];
+
+#[derive(Error, Debug)]
+pub enum PostTaskArgsError {
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+    #[error(transparent)]
+    DISPairError(#[from] DISPairError),
+    #[error(transparent)]
+    ParserError(#[from] ParserError),
+    #[error(transparent)]
+    ComposeError(#[from] ComposeError),
+}
+
+/// Dotrain whose entrypoints are composed into tasks that the orderbook
+/// evaluates after a deposit, withdraw or add order
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PostTaskArgs {
+    pub dotrain: String,
+    pub deployer: Address,
+    pub bindings: HashMap<String, String>,
+}
+
+impl PostTaskArgs {
+    /// create a new instance from the deployer and bindings of a Deployment
+    pub fn new_from_deployment(dotrain: String, deployment: &Deployment) -> Self {
+        Self {
+            dotrain,
+            deployer: deployment.scenario.deployer.address,
+            bindings: deployment.scenario.bindings.to_owned(),
+        }
+    }
+
+    /// Compose the given entrypoints to rainlang string
+    pub fn compose_to_rainlang(&self, entrypoints: &[&str]) -> Result<String, PostTaskArgsError> {
+        Ok(compose_to_rainlang(
+            self.dotrain.clone(),
+            self.bindings.clone(),
+            entrypoints,
+        )?)
+    }
+
+    /// Compose the given entrypoints and parse them with the deployer's
+    /// parser into a task evaluated by the deployer's interpreter and store
+    pub async fn try_into_task(
+        &self,
+        rpc_url: String,
+        entrypoints: &[&str],
+    ) -> Result<TaskV1, PostTaskArgsError> {
+        let rainlang = self.compose_to_rainlang(entrypoints)?;
+
+        let client = ReadableClientHttp::new_from_url(rpc_url)?;
+        let dispair = DISPair::from_deployer(self.deployer, client.clone()).await?;
+        let parser: ParserV2 = dispair.clone().into();
+        let rainlang_parsed = parser.parse_text(rainlang.as_str(), client).await?;
+
+        Ok(TaskV1 {
+            evaluable: EvaluableV3 {
+                interpreter: dispair.interpreter,
+                store: dispair.store,
+                bytecode: rainlang_parsed.bytecode.into(),
+            },
+            signedContext: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compose_vault_post_tasks() {
+        let dotrain = r#"
+some front matter
+---
+#calculate-io
+_ _: 0 0;
+#handle-io
+:;
+#post-deposit
+_: 1;
+#post-withdraw
+_: 2;
+"#;
+        let args = PostTaskArgs {
+            dotrain: dotrain.to_string(),
+            deployer: Address::random(),
+            bindings: HashMap::new(),
+        };
+
+        assert_eq!(
+            args.compose_to_rainlang(&ORDERBOOK_DEPOSIT_POST_TASK_ENTRYPOINTS)
+                .unwrap(),
+            "/* 0. post-deposit */ \n_: 1;"
+        );
+        assert_eq!(
+            args.compose_to_rainlang(&ORDERBOOK_WITHDRAW_POST_TASK_ENTRYPOINTS)
+                .unwrap(),
+            "/* 0. post-withdraw */ \n_: 2;"
+        );
+    }
+}
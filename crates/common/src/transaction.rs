@@ -1,7 +1,17 @@
-use alloy::primitives::{ruint::FromUintError, Address, U256};
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    journal::{Journal, JournalEntry, JournalStatus},
+    simulate::SimulationError,
+};
+use alloy::primitives::{ruint::FromUintError, Address, Bytes, U256};
 use alloy::sol_types::SolCall;
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::client::{LedgerClient, LedgerClientError};
+use alloy_ethers_typecast::{
+    client::{LedgerClient, LedgerClientError},
+    ethers_address_to_alloy,
+    gas_fee_middleware::GasFeeMiddleware,
+    transaction::{WriteTransaction, WriteTransactionStatus},
+};
 use alloy_ethers_typecast::{
     gas_fee_middleware::GasFeeSpeed,
     transaction::{
@@ -9,8 +19,24 @@ use alloy_ethers_typecast::{
         WriteContractParametersBuilder, WriteContractParametersBuilderError,
     },
 };
+#[cfg(not(target_family = "wasm"))]
+use ethers::{
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer, WalletError},
+    types::{BlockNumber, TransactionReceipt},
+};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+#[cfg(not(target_family = "wasm"))]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
 use thiserror::Error;
+#[cfg(not(target_family = "wasm"))]
+use tracing::warn;
 
 #[derive(Error, Debug)]
 pub enum WritableTransactionExecuteError {
@@ -21,8 +47,13 @@ pub enum WritableTransactionExecuteError {
     #[cfg(not(target_family = "wasm"))]
     #[error(transparent)]
     LedgerClient(#[from] LedgerClientError),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    Simulation(#[from] SimulationError),
     #[error("Invalid input args: {0}")]
     InvalidArgs(String),
+    #[error("Transaction was confirmed without a receipt")]
+    MissingReceipt,
 }
 
 #[derive(Error, Debug)]
@@ -38,11 +69,198 @@ pub enum TransactionArgsError {
     #[cfg(not(target_family = "wasm"))]
     #[error(transparent)]
     LedgerClient(#[from] LedgerClientError),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+    #[error("Ledger signer has no local wallet")]
+    NotLocalSigner,
+    #[error("Environment variable {0} holding the private key is not set")]
+    PrivateKeyEnvMissing(String),
+    #[error("Keystore {0} has no address")]
+    KeystoreAddressMissing(PathBuf),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+}
+
+/// Signer of the transactions, defaults to a Ledger wallet
+#[derive(Clone, Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransactionSigner {
+    /// Ledger wallet at the derivation index of the transaction args
+    #[default]
+    Ledger,
+    /// Hex encoded raw private key read from the given environment variable
+    PrivateKeyEnv { env_var: String },
+    /// Encrypted JSON keystore file and its password, the password is never
+    /// serialized
+    Keystore {
+        path: PathBuf,
+        #[serde(skip)]
+        password: SecretString,
+    },
+}
+
+/// String that is redacted from debug output, eg a keystore password
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Client that signs and sends transactions with a [TransactionSigner]
+#[cfg(not(target_family = "wasm"))]
+pub enum SignerClient {
+    Ledger(LedgerClient),
+    Local(SignerMiddleware<GasFeeMiddleware<Provider<Http>>, LocalWallet>),
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl SignerClient {
+    /// Address of the signer
+    pub fn address(&self) -> Address {
+        match self {
+            SignerClient::Ledger(ledger_client) => {
+                ethers_address_to_alloy(ledger_client.client.address())
+            }
+            SignerClient::Local(client) => ethers_address_to_alloy(client.address()),
+        }
+    }
+
+    /// Signs and sends the given write call, waits for its confirmation and
+    /// returns its receipt. The transaction and its status changes are
+    /// recorded in the journal of the user data directory. The client is only
+    /// borrowed so that all the transactions of a command are signed within a
+    /// single signer session
+    pub async fn write<T: SolCall + Clone, S: Fn(WriteTransactionStatus<T>)>(
+        &self,
+        params: WriteContractParameters<T>,
+        transaction_status_changed: S,
+    ) -> Result<TransactionReceipt, WritableTransactionExecuteError> {
+        let journal = Journal::open_default()
+            .map_err(|e| warn!("transaction will not be journaled: {}", e))
+            .ok();
+        let entry = match self {
+            SignerClient::Ledger(ledger_client) => {
+                new_journal_entry(&ledger_client.client, self.address(), &params).await
+            }
+            SignerClient::Local(client) => new_journal_entry(client, self.address(), &params).await,
+        };
+        let entry = Mutex::new(entry);
+        let record = |update: &dyn Fn(&mut JournalEntry)| {
+            let (Some(journal), Ok(mut entry)) = (&journal, entry.lock()) else {
+                return;
+            };
+            update(&mut entry);
+            if let Err(e) = journal.record(&entry) {
+                warn!("failed to journal transaction: {}", e);
+            }
+        };
+        record(&|_| {});
+
+        let receipt = Mutex::new(None);
+        let sent = AtomicBool::new(false);
+        let transaction_status_changed = |status: WriteTransactionStatus<T>| {
+            match &status {
+                WriteTransactionStatus::PendingPrepare(_) => {
+                    record(&|v| v.set_status(JournalStatus::PendingPrepare))
+                }
+                WriteTransactionStatus::PendingSign(_) => {
+                    record(&|v| v.set_status(JournalStatus::PendingSign))
+                }
+                WriteTransactionStatus::PendingSend(_) => {
+                    sent.store(true, Ordering::SeqCst);
+                    record(&|v| v.set_status(JournalStatus::PendingSend))
+                }
+                WriteTransactionStatus::Confirmed(v) => {
+                    record(&|entry| entry.set_confirmed(v));
+                    if let Ok(mut receipt) = receipt.lock() {
+                        *receipt = Some(v.clone());
+                    }
+                }
+            }
+            transaction_status_changed(status);
+        };
+        let result = match self {
+            SignerClient::Ledger(ledger_client) => {
+                WriteTransaction::new(&ledger_client.client, params, 4, transaction_status_changed)
+                    .execute()
+                    .await
+            }
+            SignerClient::Local(client) => {
+                WriteTransaction::new(client, params, 4, transaction_status_changed)
+                    .execute()
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            // a transaction that may have been sent stays pending so that it
+            // can still be waited for
+            if !sent.load(Ordering::SeqCst) {
+                record(&|v| v.set_status(JournalStatus::Failed(e.to_string())));
+            }
+            return Err(e.into());
+        }
+        receipt
+            .into_inner()
+            .ok()
+            .flatten()
+            .ok_or(WritableTransactionExecuteError::MissingReceipt)
+    }
+}
+
+/// Journal entry of a write call about to be sent by the given client, the
+/// chain, pending nonce and latest block are read on a best effort basis
+#[cfg(not(target_family = "wasm"))]
+async fn new_journal_entry<M: Middleware, T: SolCall>(
+    client: &M,
+    from: Address,
+    params: &WriteContractParameters<T>,
+) -> JournalEntry {
+    let mut entry = JournalEntry::new(
+        client.get_chainid().await.ok().map(|v| v.as_u64()),
+        from,
+        params.address,
+        T::SIGNATURE.to_string(),
+        Bytes::from(params.call.abi_encode()),
+    );
+    entry.max_priority_fee_per_gas = params.max_priority_fee_per_gas;
+    entry.max_fee_per_gas = params.max_fee_per_gas;
+    entry.nonce = client
+        .get_transaction_count(
+            ethers::types::H160::from(from.0 .0),
+            Some(BlockNumber::Pending.into()),
+        )
+        .await
+        .ok()
+        .map(|v| v.as_u64());
+    entry.sent_at_block = client.get_block_number().await.ok().map(|v| v.as_u64());
+    entry
 }
 
 #[derive(Clone, Serialize, Deserialize, Default)]
 pub struct TransactionArgs {
     pub orderbook_address: Address,
+    #[serde(default)]
+    pub signer: TransactionSigner,
     pub derivation_index: Option<usize>,
     pub chain_id: Option<u64>,
     pub rpc_url: String,
@@ -80,6 +298,64 @@ impl TransactionArgs {
         Ok(())
     }
 
+    /// Builds the local wallet of a private key or keystore signer, with the
+    /// chain id of the args
+    #[cfg(not(target_family = "wasm"))]
+    pub fn try_into_local_wallet(&self) -> Result<LocalWallet, TransactionArgsError> {
+        let chain_id = self.chain_id.ok_or(TransactionArgsError::ChainIdNone)?;
+        let wallet = match &self.signer {
+            TransactionSigner::PrivateKeyEnv { env_var } => std::env::var(env_var)
+                .map_err(|_| TransactionArgsError::PrivateKeyEnvMissing(env_var.clone()))?
+                .trim()
+                .trim_start_matches("0x")
+                .parse::<LocalWallet>()?,
+            TransactionSigner::Keystore { path, password } => {
+                LocalWallet::decrypt_keystore(path, password.expose())?
+            }
+            TransactionSigner::Ledger => return Err(TransactionArgsError::NotLocalSigner),
+        };
+        Ok(wallet.with_chain_id(chain_id))
+    }
+
+    /// Reads the address of a private key or keystore signer, the keystore is
+    /// not decrypted so its address is read from its `address` field
+    #[cfg(not(target_family = "wasm"))]
+    pub fn try_into_local_address(&self) -> Result<Address, TransactionArgsError> {
+        match &self.signer {
+            TransactionSigner::Keystore { path, .. } => {
+                let keystore: serde_json::Value =
+                    serde_json::from_str(&std::fs::read_to_string(path)?)?;
+                keystore
+                    .get("address")
+                    .and_then(serde_json::Value::as_str)
+                    .and_then(|v| v.parse::<Address>().ok())
+                    .ok_or_else(|| TransactionArgsError::KeystoreAddressMissing(path.clone()))
+            }
+            _ => Ok(ethers_address_to_alloy(
+                self.try_into_local_wallet()?.address(),
+            )),
+        }
+    }
+
+    /// Builds the client of the configured signer
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn try_into_signer_client(self) -> Result<SignerClient, TransactionArgsError> {
+        match &self.signer {
+            TransactionSigner::Ledger => {
+                Ok(SignerClient::Ledger(self.try_into_ledger_client().await?))
+            }
+            _ => {
+                let wallet = self.try_into_local_wallet()?;
+                let provider = Provider::<Http>::try_from(self.rpc_url.as_str())?;
+                let provider = GasFeeMiddleware::new(
+                    provider,
+                    self.gas_fee_speed.unwrap_or(GasFeeSpeed::Medium),
+                );
+                Ok(SignerClient::Local(SignerMiddleware::new(provider, wallet)))
+            }
+        }
+    }
+
     #[cfg(not(target_family = "wasm"))]
     pub async fn try_into_ledger_client(self) -> Result<LedgerClient, TransactionArgsError> {
         match self.chain_id {
@@ -99,3 +375,149 @@ impl TransactionArgs {
         }
     }
 }
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+    use ethers::core::rand::thread_rng;
+
+    fn transaction_args(signer: TransactionSigner) -> TransactionArgs {
+        TransactionArgs {
+            signer,
+            chain_id: Some(137),
+            rpc_url: "https://example.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_keystore_password_is_not_leaked() {
+        let signer = TransactionSigner::Keystore {
+            path: PathBuf::from("keystore.json"),
+            password: "hunter2".to_string().into(),
+        };
+        assert!(!format!("{:?}", signer).contains("hunter2"));
+
+        let serialized = serde_json::to_string(&transaction_args(signer)).unwrap();
+        assert!(!serialized.contains("hunter2"));
+        let deserialized: TransactionArgs = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(
+            deserialized.signer,
+            TransactionSigner::Keystore {
+                path: PathBuf::from("keystore.json"),
+                password: SecretString::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_keystore_local_wallet() {
+        let dir = std::env::temp_dir().join(format!("keystore-{}", Address::random()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (wallet, name) =
+            LocalWallet::new_keystore(&dir, &mut thread_rng(), "password", None).unwrap();
+
+        let result = transaction_args(TransactionSigner::Keystore {
+            path: dir.join(&name),
+            password: "password".to_string().into(),
+        })
+        .try_into_local_wallet()
+        .unwrap();
+        assert_eq!(result.address(), wallet.address());
+        assert_eq!(result.chain_id(), 137);
+
+        let result = transaction_args(TransactionSigner::Keystore {
+            path: dir.join(&name),
+            password: "wrong".to_string().into(),
+        })
+        .try_into_local_wallet();
+        assert!(matches!(result, Err(TransactionArgsError::Wallet(_))));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_private_key_env_local_wallet() {
+        let env_var = "TEST_PRIVATE_KEY_ENV_LOCAL_WALLET".to_string();
+        let wallet = LocalWallet::new(&mut thread_rng());
+        std::env::set_var(
+            &env_var,
+            format!(
+                "0x{}",
+                alloy::primitives::hex::encode(wallet.signer().to_bytes())
+            ),
+        );
+
+        let result = transaction_args(TransactionSigner::PrivateKeyEnv {
+            env_var: env_var.clone(),
+        })
+        .try_into_local_wallet()
+        .unwrap();
+        assert_eq!(result.address(), wallet.address());
+
+        std::env::remove_var(&env_var);
+        let result =
+            transaction_args(TransactionSigner::PrivateKeyEnv { env_var }).try_into_local_wallet();
+        assert!(matches!(
+            result,
+            Err(TransactionArgsError::PrivateKeyEnvMissing(_))
+        ));
+    }
+
+    #[test]
+    fn test_local_address() {
+        let dir = std::env::temp_dir().join(format!("keystore-{}", Address::random()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (wallet, name) =
+            LocalWallet::new_keystore(&dir, &mut thread_rng(), "password", None).unwrap();
+        let path = dir.join(&name);
+        let args = transaction_args(TransactionSigner::Keystore {
+            path: path.clone(),
+            password: SecretString::default(),
+        });
+        assert!(matches!(
+            args.try_into_local_address(),
+            Err(TransactionArgsError::KeystoreAddressMissing(_))
+        ));
+
+        // keystores of geth and foundry carry the address without a 0x prefix
+        let mut keystore: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        keystore["address"] = alloy::primitives::hex::encode(wallet.address()).into();
+        std::fs::write(&path, keystore.to_string()).unwrap();
+        assert_eq!(
+            args.try_into_local_address().unwrap(),
+            ethers_address_to_alloy(wallet.address())
+        );
+
+        let env_var = "TEST_PRIVATE_KEY_ENV_LOCAL_ADDRESS".to_string();
+        std::env::set_var(
+            &env_var,
+            alloy::primitives::hex::encode(wallet.signer().to_bytes()),
+        );
+        let result = transaction_args(TransactionSigner::PrivateKeyEnv {
+            env_var: env_var.clone(),
+        })
+        .try_into_local_address()
+        .unwrap();
+        assert_eq!(result, ethers_address_to_alloy(wallet.address()));
+        std::env::remove_var(&env_var);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_local_wallet_errors() {
+        let result = transaction_args(TransactionSigner::Ledger).try_into_local_wallet();
+        assert!(matches!(result, Err(TransactionArgsError::NotLocalSigner)));
+
+        let mut args = transaction_args(TransactionSigner::PrivateKeyEnv {
+            env_var: "UNUSED".to_string(),
+        });
+        args.chain_id = None;
+        assert!(matches!(
+            args.try_into_local_wallet(),
+            Err(TransactionArgsError::ChainIdNone)
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    add_order::{AddOrderArgs, AddOrderArgsError},
+    batch::{BatchArgs, BatchError},
+    meta::{TryDecodeRainlangSource, TryDecodeRainlangSourceError},
+    remove_order::RemoveOrderArgs,
+};
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    receipt::ConfirmedTransaction,
+    simulate::WriteSimulation,
+    transaction::{SignerClient, TransactionArgs},
+};
+use alloy::primitives::Address;
+#[cfg(not(target_family = "wasm"))]
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
+use rain_orderbook_bindings::{
+    IOrderBookV4::{removeOrder2Call, OrderV3, IO},
+    OrderBook::multicallCall,
+};
+use rain_orderbook_subgraph_client::types::{
+    order_detail::Order, order_detail_traits::OrderDetailError,
+};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum UpdateOrderArgsError {
+    #[error(transparent)]
+    AddOrderArgsError(#[from] AddOrderArgsError),
+    #[error(transparent)]
+    OrderDetailError(#[from] OrderDetailError),
+    #[error(transparent)]
+    TryDecodeRainlangSourceError(#[from] TryDecodeRainlangSourceError),
+    #[error(transparent)]
+    BatchError(#[from] BatchError),
+    #[error("Order has no meta to decode its rainlang from")]
+    MissingMeta,
+    #[error("Order is in orderbook {0}, not in orderbook {1}")]
+    OrderbookMismatch(String, Address),
+}
+
+/// Replaces an existing order with a new one, the old order is removed and
+/// the new one is added in a single multicall transaction
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UpdateOrderArgs {
+    /// Order to replace
+    pub order: Order,
+    /// Order to add in place of the replaced one
+    pub add_order_args: AddOrderArgs,
+}
+
+/// Rainlang of the replaced and the new order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RainlangDiff {
+    pub old: String,
+    pub new: String,
+    /// Unified diff of the old and new rainlang
+    pub diff: String,
+}
+
+impl UpdateOrderArgs {
+    /// Builds the update of the given order, if `reuse_vault_ids` is set the
+    /// new order's IOs use the vault ids of the old order's IOs of the same
+    /// token, so the new order keeps trading from and into the same vaults
+    pub fn new(
+        order: Order,
+        mut add_order_args: AddOrderArgs,
+        reuse_vault_ids: bool,
+    ) -> Result<Self, UpdateOrderArgsError> {
+        if reuse_vault_ids {
+            let old_order: OrderV3 = order.clone().try_into()?;
+            reuse_ios_vault_ids(&old_order.validInputs, &mut add_order_args.inputs);
+            reuse_ios_vault_ids(&old_order.validOutputs, &mut add_order_args.outputs);
+        }
+        Ok(Self {
+            order,
+            add_order_args,
+        })
+    }
+
+    /// Diffs the rainlang of the old order, decoded from its meta, against
+    /// the composed rainlang of the new order
+    pub fn get_rainlang_diff(&self) -> Result<RainlangDiff, UpdateOrderArgsError> {
+        let old = self
+            .order
+            .meta
+            .as_ref()
+            .ok_or(UpdateOrderArgsError::MissingMeta)?
+            .try_decode_rainlangsource()?;
+        let new = self.add_order_args.compose_to_rainlang()?;
+        Ok(RainlangDiff {
+            diff: rainlang_diff(&old, &new),
+            old,
+            new,
+        })
+    }
+
+    /// Builds the batch that removes the old order and then adds the new one
+    /// to the given orderbook, which must be the orderbook of the old order
+    pub async fn try_into_batch(
+        &self,
+        rpc_url: String,
+        orderbook: Address,
+    ) -> Result<BatchArgs, UpdateOrderArgsError> {
+        if self.order.orderbook.id.0.parse::<Address>().ok() != Some(orderbook) {
+            return Err(UpdateOrderArgsError::OrderbookMismatch(
+                self.order.orderbook.id.0.clone(),
+                orderbook,
+            ));
+        }
+        let remove_order_call: removeOrder2Call =
+            RemoveOrderArgs::from(self.order.clone()).try_into()?;
+        let add_order_call = self.add_order_args.try_into_call(rpc_url).await?;
+
+        let mut batch = BatchArgs::default();
+        batch
+            .remove_order(remove_order_call)
+            .add_order(add_order_call);
+        Ok(batch)
+    }
+
+    /// Execute the update in a single multicall transaction, the confirmed
+    /// transaction has the `RemoveOrderV2` and `AddOrderV2` events
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn execute<S: Fn(WriteTransactionStatus<multicallCall>)>(
+        &self,
+        transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
+        transaction_status_changed: S,
+    ) -> Result<ConfirmedTransaction, UpdateOrderArgsError> {
+        let batch = self
+            .try_into_batch(
+                transaction_args.rpc_url.clone(),
+                transaction_args.orderbook_address,
+            )
+            .await?;
+        Ok(batch
+            .execute(transaction_args, signer_client, transaction_status_changed)
+            .await?)
+    }
+
+    /// Simulates the update from the signer on a fork of the latest block
+    /// without signing
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn simulate(
+        &self,
+        transaction_args: TransactionArgs,
+        from: Address,
+    ) -> Result<Vec<WriteSimulation>, UpdateOrderArgsError> {
+        let batch = self
+            .try_into_batch(
+                transaction_args.rpc_url.clone(),
+                transaction_args.orderbook_address,
+            )
+            .await?;
+        Ok(batch.simulate(transaction_args, from).await?)
+    }
+
+    pub async fn get_update_order_calldata(
+        &self,
+        rpc_url: String,
+        orderbook: Address,
+    ) -> Result<Vec<u8>, UpdateOrderArgsError> {
+        Ok(self
+            .try_into_batch(rpc_url, orderbook)
+            .await?
+            .get_multicall_calldata()?)
+    }
+}
+
+/// Sets the vault id of each new IO to the vault id of the first old IO of
+/// the same token, IOs of tokens the old order doesnt have are left as is
+fn reuse_ios_vault_ids(old_ios: &[IO], new_ios: &mut [IO]) {
+    for io in new_ios.iter_mut() {
+        if let Some(old_io) = old_ios.iter().find(|v| v.token == io.token) {
+            io.vaultId = old_io.vaultId;
+        }
+    }
+}
+
+fn rainlang_diff(old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header("old", "new")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+
+    #[test]
+    fn test_reuse_ios_vault_ids() {
+        let token_a = Address::random();
+        let token_b = Address::random();
+        let old_ios = vec![
+            IO {
+                token: token_a,
+                vaultId: U256::from(1),
+                decimals: 18,
+            },
+            IO {
+                token: token_a,
+                vaultId: U256::from(2),
+                decimals: 18,
+            },
+        ];
+        let mut new_ios = vec![
+            IO {
+                token: token_b,
+                vaultId: U256::from(3),
+                decimals: 6,
+            },
+            IO {
+                token: token_a,
+                vaultId: U256::from(4),
+                decimals: 18,
+            },
+        ];
+
+        reuse_ios_vault_ids(&old_ios, &mut new_ios);
+
+        assert_eq!(new_ios[0].vaultId, U256::from(3));
+        assert_eq!(new_ios[1].vaultId, U256::from(1));
+    }
+
+    #[test]
+    fn test_rainlang_diff() {
+        let old = "#calculate-io\n_ _: 100e18 2e18;\n#handle-io\n:;\n";
+        let new = "#calculate-io\n_ _: 100e18 3e18;\n#handle-io\n:;\n";
+
+        let result = rainlang_diff(old, new);
+
+        assert!(result.starts_with("--- old\n+++ new\n"));
+        assert!(result.contains("-_ _: 100e18 2e18;\n+_ _: 100e18 3e18;\n"));
+        assert!(result.contains(" #handle-io\n"));
+        assert_eq!(rainlang_diff(old, old), "");
+    }
+
+    #[tokio::test]
+    async fn test_try_into_batch_orderbook_mismatch() {
+        let orderbook = Address::random();
+        let order: Order = serde_json::from_value(serde_json::json!({
+            "id": "0x01",
+            "orderBytes": "0x",
+            "orderHash": "0x01",
+            "owner": Address::random().to_string(),
+            "outputs": [],
+            "inputs": [],
+            "active": true,
+            "addEvents": [],
+            "meta": null,
+            "timestampAdded": "0",
+            "orderbook": { "id": orderbook.to_string() },
+        }))
+        .unwrap();
+        let update_order_args = UpdateOrderArgs {
+            order,
+            add_order_args: AddOrderArgs {
+                dotrain: "".into(),
+                inputs: vec![],
+                outputs: vec![],
+                bindings: Default::default(),
+                deployer: Address::default(),
+                nonce: None,
+                secret: None,
+                salt: None,
+            },
+        };
+
+        let other_orderbook = Address::random();
+        let err = update_order_args
+            .try_into_batch("http://localhost:8545".into(), other_orderbook)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            UpdateOrderArgsError::OrderbookMismatch(ref found, expected)
+                if found == &orderbook.to_string() && expected == other_orderbook
+        ));
+    }
+}
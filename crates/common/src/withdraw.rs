@@ -1,11 +1,18 @@
 #[cfg(not(target_family = "wasm"))]
-use crate::transaction::TransactionArgs;
-use crate::transaction::WritableTransactionExecuteError;
+use crate::{
+    receipt::ConfirmedTransaction,
+    simulate::{WatchedVault, WriteSimulation, WriteSimulator},
+    transaction::{SignerClient, TransactionArgs},
+};
+use crate::{
+    task::{PostTaskArgs, PostTaskArgsError, ORDERBOOK_WITHDRAW_POST_TASK_ENTRYPOINTS},
+    transaction::WritableTransactionExecuteError,
+};
 use alloy::primitives::{Address, U256};
 use alloy::sol_types::SolCall;
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::transaction::{WriteTransaction, WriteTransactionStatus};
-use rain_orderbook_bindings::IOrderBookV4::withdraw2Call;
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
+use rain_orderbook_bindings::IOrderBookV4::{withdraw2Call, TaskV1};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -13,6 +20,10 @@ pub struct WithdrawArgs {
     pub token: Address,
     pub vault_id: U256,
     pub target_amount: U256,
+    /// Tasks the orderbook evaluates after the withdraw, eg the composed
+    /// `post-withdraw` entrypoint of a dotrain
+    #[serde(default)]
+    pub tasks: Vec<TaskV1>,
 }
 
 impl From<WithdrawArgs> for withdraw2Call {
@@ -21,31 +32,70 @@ impl From<WithdrawArgs> for withdraw2Call {
             token: val.token,
             vaultId: val.vault_id,
             targetAmount: val.target_amount,
-            tasks: vec![],
+            tasks: val.tasks,
         }
     }
 }
 
 impl WithdrawArgs {
-    /// Execute OrderbookV3 withdraw call
+    /// Compose the `post-withdraw` entrypoint of the post task dotrain and add it
+    /// to the tasks of the withdraw
+    pub async fn try_add_post_task(
+        &mut self,
+        post_task: &PostTaskArgs,
+        rpc_url: String,
+    ) -> Result<(), PostTaskArgsError> {
+        let task = post_task
+            .try_into_task(rpc_url, &ORDERBOOK_WITHDRAW_POST_TASK_ENTRYPOINTS)
+            .await?;
+        self.tasks.push(task);
+        Ok(())
+    }
+
+    /// Execute OrderbookV3 withdraw call, the confirmed transaction has the
+    /// `Withdraw` event with the actually withdrawn amount
     #[cfg(not(target_family = "wasm"))]
     pub async fn execute<S: Fn(WriteTransactionStatus<withdraw2Call>)>(
         &self,
         transaction_args: TransactionArgs,
+        signer_client: &SignerClient,
         transaction_status_changed: S,
-    ) -> Result<(), WritableTransactionExecuteError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
+    ) -> Result<ConfirmedTransaction, WritableTransactionExecuteError> {
         let withdraw_call: withdraw2Call = self.clone().into();
         let params = transaction_args
             .try_into_write_contract_parameters(withdraw_call, transaction_args.orderbook_address)
             .await?;
 
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        let receipt = signer_client
+            .write(params, transaction_status_changed)
             .await?;
 
-        Ok(())
+        Ok(ConfirmedTransaction::from_receipt(
+            &receipt,
+            transaction_args.orderbook_address,
+        ))
+    }
+
+    /// Simulates the withdraw call from the signer on a fork of the latest
+    /// block without signing
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn simulate(
+        &self,
+        transaction_args: TransactionArgs,
+        from: Address,
+    ) -> Result<WriteSimulation, WritableTransactionExecuteError> {
+        let orderbook = transaction_args.orderbook_address;
+        let mut simulator = WriteSimulator::new(&transaction_args.rpc_url, from, None).await?;
+
+        let withdraw_call: withdraw2Call = self.clone().into();
+        let vault = WatchedVault {
+            owner: simulator.from(),
+            token: self.token,
+            vault_id: self.vault_id,
+        };
+        Ok(simulator
+            .simulate(orderbook, withdraw_call, orderbook, &[vault])
+            .await?)
     }
 
     pub async fn get_withdraw_calldata(&self) -> Result<Vec<u8>, WritableTransactionExecuteError> {
@@ -66,6 +116,7 @@ mod tests {
                 .unwrap(),
             vault_id: U256::from(42),
             target_amount: U256::from(100),
+            tasks: vec![TaskV1::default()],
         };
 
         let withdraw_call: withdraw2Call = args.into();
@@ -77,5 +128,6 @@ mod tests {
         );
         assert_eq!(withdraw_call.vaultId, U256::from(42));
         assert_eq!(withdraw_call.targetAmount, U256::from(100));
+        assert_eq!(withdraw_call.tasks, vec![TaskV1::default()]);
     }
 }
@@ -5,8 +5,9 @@ use alloy::primitives::{
 };
 use alloy::sol_types::SolType;
 use clap::Args;
-use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote};
-use std::str::FromStr;
+use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote, SignedContextV1};
+use serde::Deserialize;
+use std::{fs::read_to_string, path::Path, path::PathBuf, str::FromStr};
 
 /// Group of valid input formats
 /// Only one of them can be passed at a time in cli
@@ -50,6 +51,15 @@ pub struct Input {
         ],
     )]
     pub spec: Option<Vec<String>>,
+
+    /// Path to a JSON or CSV file of quote specs or quote targets, which
+    /// unlike other inputs can include signed context for each entry.
+    /// JSON is the serialized form of a batch quote spec or batch quote target,
+    /// CSV has `orderbook`, `input_io_index`, `output_io_index`, either
+    /// `order_hash` or `order_bytes` and an optional `signed_context` column
+    /// that holds a JSON array of signed contexts
+    #[arg(long, env, value_name = "PATH")]
+    pub input_file: Option<PathBuf>,
 }
 
 /// Determines the variants of parsed json input
@@ -74,6 +84,9 @@ impl Input {
         if self.spec.is_some() {
             inputs_count += 1;
         }
+        if self.input_file.is_some() {
+            inputs_count += 1;
+        }
         if inputs_count > 1 {
             Err(anyhow::anyhow!("conflicting inputs"))
         } else if let Some(v) = &self.input {
@@ -82,6 +95,8 @@ impl Input {
             Ok(InputContentType::Target(targets.try_into()?))
         } else if let Some(specs) = &self.spec {
             Ok(InputContentType::Spec(specs.try_into()?))
+        } else if let Some(path) = &self.input_file {
+            read_input_file(path)
         } else {
             Err(anyhow::anyhow!("expected at least one input"))
         }
@@ -123,6 +138,106 @@ pub fn parse_input(value: &str) -> anyhow::Result<BatchQuoteSpec> {
     Ok(batch_quote_sepcs)
 }
 
+/// Reads and parses the given input file, files with `.csv` extension are
+/// parsed as CSV and anything else as JSON
+pub fn read_input_file(path: &Path) -> anyhow::Result<InputContentType> {
+    let content = read_to_string(path)?;
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    if is_csv {
+        parse_csv_input(&content)
+    } else {
+        parse_json_input(&content)
+    }
+}
+
+/// Parses the given JSON string, which is either a [BatchQuoteTarget] or
+/// a [BatchQuoteSpec]
+pub fn parse_json_input(value: &str) -> anyhow::Result<InputContentType> {
+    if let Ok(targets) = serde_json::from_str::<BatchQuoteTarget>(value) {
+        if targets.0.is_empty() {
+            return Err(anyhow::anyhow!("empty input file"));
+        }
+        return Ok(InputContentType::Target(targets));
+    }
+    match serde_json::from_str::<BatchQuoteSpec>(value) {
+        Ok(specs) if specs.0.is_empty() => Err(anyhow::anyhow!("empty input file")),
+        Ok(specs) => Ok(InputContentType::Spec(specs)),
+        Err(e) => Err(anyhow::anyhow!(
+            "expected quote specs or quote targets: {}",
+            e
+        )),
+    }
+}
+
+/// A row of a CSV input file
+#[derive(Debug, Clone, Deserialize)]
+struct InputFileRow {
+    orderbook: String,
+    input_io_index: u8,
+    output_io_index: u8,
+    order_hash: Option<String>,
+    order_bytes: Option<String>,
+    signed_context: Option<String>,
+}
+
+impl InputFileRow {
+    fn parse_signed_context(&self) -> anyhow::Result<Vec<SignedContextV1>> {
+        match self.signed_context.as_deref().map(str::trim) {
+            None | Some("") => Ok(vec![]),
+            Some(v) => Ok(serde_json::from_str(v)?),
+        }
+    }
+}
+
+/// Parses the given CSV string, whose rows are either all quote specs (have
+/// `order_hash`) or all quote targets (have `order_bytes`)
+pub fn parse_csv_input(value: &str) -> anyhow::Result<InputContentType> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(value.as_bytes());
+    let mut specs = BatchQuoteSpec::default();
+    let mut targets = BatchQuoteTarget::default();
+    for (i, row) in reader.deserialize::<InputFileRow>().enumerate() {
+        let row = row?;
+        let orderbook = Address::from_hex(&row.orderbook)?;
+        let signed_context = row.parse_signed_context()?;
+        match (&row.order_hash, &row.order_bytes) {
+            (Some(order_hash), None) => specs.0.push(QuoteSpec {
+                orderbook,
+                signed_context,
+                order_hash: U256::from_str(order_hash)?,
+                input_io_index: row.input_io_index,
+                output_io_index: row.output_io_index,
+            }),
+            (None, Some(order_bytes)) => targets.0.push(QuoteTarget {
+                orderbook,
+                quote_config: Quote {
+                    signedContext: signed_context,
+                    inputIOIndex: U256::from(row.input_io_index),
+                    outputIOIndex: U256::from(row.output_io_index),
+                    order: OrderV3::abi_decode(&decode(order_bytes)?, true)?,
+                },
+            }),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "row {} must have exactly one of 'order_hash' or 'order_bytes'",
+                    i + 1
+                ))
+            }
+        }
+    }
+    match (specs.0.is_empty(), targets.0.is_empty()) {
+        (false, true) => Ok(InputContentType::Spec(specs)),
+        (true, false) => Ok(InputContentType::Target(targets)),
+        (true, true) => Err(anyhow::anyhow!("empty input file")),
+        (false, false) => Err(anyhow::anyhow!(
+            "input file cannot mix quote specs and quote targets"
+        )),
+    }
+}
+
 // tries to map an array of strings into a BatchQuoteTarget
 impl TryFrom<&Vec<String>> for BatchQuoteTarget {
     type Error = anyhow::Error;
@@ -412,6 +527,7 @@ mod tests {
             input: Some(specs.clone()),
             target: None,
             spec: None,
+            input_file: None,
         };
         matches!(input.read_content().unwrap(), InputContentType::Spec(_));
 
@@ -425,6 +541,7 @@ mod tests {
             input: None,
             target: Some(targets_str.clone()),
             spec: None,
+            input_file: None,
         };
         matches!(input.read_content().unwrap(), InputContentType::Target(_));
 
@@ -438,6 +555,7 @@ mod tests {
             input: None,
             spec: Some(specs_str.clone()),
             target: None,
+            input_file: None,
         };
         matches!(input.read_content().unwrap(), InputContentType::Spec(_));
 
@@ -445,6 +563,7 @@ mod tests {
             input: None,
             target: None,
             spec: None,
+            input_file: None,
         };
         assert_eq!(
             input
@@ -458,6 +577,7 @@ mod tests {
             input: Some(specs),
             target: Some(targets_str),
             spec: None,
+            input_file: None,
         };
         assert_eq!(
             input
@@ -467,4 +587,139 @@ mod tests {
             "conflicting inputs"
         );
     }
+
+    #[test]
+    fn test_parse_json_input() {
+        let signed_context = vec![SignedContextV1 {
+            signer: Address::random(),
+            context: vec![U256::from(1), U256::from(2)],
+            signature: vec![1, 2, 3].into(),
+        }];
+        let specs = BatchQuoteSpec(vec![QuoteSpec {
+            order_hash: U256::from(1),
+            input_io_index: 1,
+            output_io_index: 2,
+            signed_context: signed_context.clone(),
+            orderbook: Address::random(),
+        }]);
+        let result = parse_json_input(&serde_json::to_string(&specs).unwrap()).unwrap();
+        assert_eq!(result, InputContentType::Spec(specs));
+
+        let targets = BatchQuoteTarget(vec![QuoteTarget {
+            orderbook: Address::random(),
+            quote_config: Quote {
+                signedContext: signed_context,
+                ..Default::default()
+            },
+        }]);
+        let result = parse_json_input(&serde_json::to_string(&targets).unwrap()).unwrap();
+        assert_eq!(result, InputContentType::Target(targets));
+
+        assert_eq!(
+            parse_json_input("[]")
+                .expect_err("expected error")
+                .to_string(),
+            "empty input file"
+        );
+        assert!(parse_json_input(r#"[{"some":"value"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_input() {
+        let orderbook = Address::random();
+        let signer = Address::random();
+        let csv = format!(
+            r#"orderbook,input_io_index,output_io_index,order_hash,signed_context
+{orderbook},1,2,0x01,"[{{""signer"":""{signer}"",""context"":[""0x5""],""signature"":""0x0102""}}]"
+{orderbook},0,0,0x02,
+"#
+        );
+        let result = parse_csv_input(&csv).unwrap();
+        let expected = InputContentType::Spec(BatchQuoteSpec(vec![
+            QuoteSpec {
+                order_hash: U256::from(1),
+                input_io_index: 1,
+                output_io_index: 2,
+                signed_context: vec![SignedContextV1 {
+                    signer,
+                    context: vec![U256::from(5)],
+                    signature: vec![1, 2].into(),
+                }],
+                orderbook,
+            },
+            QuoteSpec {
+                order_hash: U256::from(2),
+                input_io_index: 0,
+                output_io_index: 0,
+                signed_context: vec![],
+                orderbook,
+            },
+        ]));
+        assert_eq!(result, expected);
+
+        let order_bytes = encode_prefixed(OrderV3::default().abi_encode());
+        let csv = format!(
+            "orderbook,input_io_index,output_io_index,order_bytes\n{orderbook},3,4,{order_bytes}\n"
+        );
+        let result = parse_csv_input(&csv).unwrap();
+        let expected = InputContentType::Target(BatchQuoteTarget(vec![QuoteTarget {
+            orderbook,
+            quote_config: Quote {
+                signedContext: vec![],
+                inputIOIndex: U256::from(3),
+                outputIOIndex: U256::from(4),
+                order: OrderV3::default(),
+            },
+        }]));
+        assert_eq!(result, expected);
+
+        let csv = format!(
+            "orderbook,input_io_index,output_io_index,order_hash,order_bytes\n{orderbook},0,0,0x01,\n{orderbook},0,0,,{order_bytes}\n"
+        );
+        assert_eq!(
+            parse_csv_input(&csv)
+                .expect_err("expected error")
+                .to_string(),
+            "input file cannot mix quote specs and quote targets"
+        );
+
+        let csv = format!(
+            "orderbook,input_io_index,output_io_index,order_hash,order_bytes\n{orderbook},0,0,0x01,{order_bytes}\n"
+        );
+        assert_eq!(
+            parse_csv_input(&csv)
+                .expect_err("expected error")
+                .to_string(),
+            "row 1 must have exactly one of 'order_hash' or 'order_bytes'"
+        );
+    }
+
+    #[test]
+    fn test_read_input_file() {
+        let test_path = std::env::current_dir().unwrap().join("test-input-file.csv");
+        let orderbook = Address::random();
+        std::fs::write(
+            &test_path,
+            format!("orderbook,input_io_index,output_io_index,order_hash\n{orderbook},0,0,0x01\n"),
+        )
+        .unwrap();
+        let input = Input {
+            input: None,
+            target: None,
+            spec: None,
+            input_file: Some(test_path.clone()),
+        };
+        let result = input.read_content();
+        std::fs::remove_file(test_path).unwrap();
+        assert_eq!(
+            result.unwrap(),
+            InputContentType::Spec(BatchQuoteSpec(vec![QuoteSpec {
+                order_hash: U256::from(1),
+                input_io_index: 0,
+                output_io_index: 0,
+                signed_context: vec![],
+                orderbook,
+            }]))
+        );
+    }
 }
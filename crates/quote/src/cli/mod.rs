@@ -1,12 +1,29 @@
-use crate::{OrderQuoteValue, QuoteResult};
+use crate::{
+    revert::register_abi_files,
+    rpc::{get_block_number, get_block_timestamp},
+    OrderQuoteValue, QuoteResult, QuoteSeriesPoint,
+};
 use alloy::primitives::Address;
 use clap::{command, ArgAction, Parser};
+use rain_orderbook_app_settings::blocks::{Block, BlockRange, Blocks};
 use serde::{Deserialize, Serialize};
-use std::{fs::write, io::Write, path::PathBuf};
+use std::{
+    fs::{write, File},
+    io::Write,
+    path::PathBuf,
+    time::Duration,
+};
 use url::Url;
 
 mod input;
+mod series;
+mod watch;
 pub use input::*;
+pub use series::*;
+pub use watch::*;
+
+/// Seconds between polls for a new block in watch mode
+const BLOCK_POLL_INTERVAL_SECS: u64 = 2;
 
 /// Rain orderbook Quoter CLI app entrypoint sruct
 #[derive(Parser, Debug, Clone, PartialEq)]
@@ -36,6 +53,27 @@ pub struct Quoter {
     #[arg(short, long, env, value_name = "INTEGER")]
     pub block_number: Option<u64>,
 
+    /// Optional block range to quote over, in form of `start..end` where each
+    /// end can be a block number or `latest`/`latest-N` and the end defaults to
+    /// latest, results in a time series
+    #[arg(long, env, value_name = "RANGE", conflicts_with = "block_number")]
+    pub block_range: Option<BlockRange>,
+
+    /// Optional interval between the quoted blocks of the block range, quotes
+    /// every block of the range if not specified
+    #[arg(
+        long,
+        env,
+        value_name = "INTEGER",
+        requires = "block_range",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub block_interval: Option<u32>,
+
+    /// Output format of a block range time series
+    #[arg(long, env, value_enum, default_value_t, requires = "block_range")]
+    pub series_format: SeriesFormat,
+
     /// Optional multicall3 address to use when quoting
     #[arg(short, long, env, value_name = "ADDRESS")]
     pub multicall_address: Option<Address>,
@@ -51,6 +89,34 @@ pub struct Quoter {
     /// Pretty format the result
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub pretty: bool,
+
+    /// Keep re-quoting on every new block, or on every interval if specified,
+    /// and output the changed quotes as JSON lines
+    #[arg(
+        short,
+        long,
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["block_number", "block_range"]
+    )]
+    pub watch: bool,
+
+    /// Optional interval in seconds to re-quote at in watch mode
+    #[arg(long, env, value_name = "SECONDS", requires = "watch")]
+    pub interval: Option<u64>,
+
+    /// Minimum relative change of ratio or max output in basis points for
+    /// a quote to be reported in watch mode, status flips are always reported
+    #[arg(long, env, value_name = "BPS", default_value_t = 0, requires = "watch")]
+    pub threshold: u64,
+
+    /// Optional condition to end the watch mode on
+    #[arg(long, env, value_enum, value_name = "CONDITION", requires = "watch")]
+    pub exit_on: Option<ExitOn>,
+
+    /// Optional abi json files whose errors are used to decode reverted quotes,
+    /// in addition to the orderbook, interpreter and ERC20 errors
+    #[arg(long, value_name = "PATH")]
+    pub abi: Vec<PathBuf>,
 }
 
 /// A serializable/deserializable struct that bridges [QuoteResult] for cli
@@ -88,21 +154,55 @@ impl From<Vec<QuoteResult>> for QuoterResult {
 impl Quoter {
     /// Executes the CLI call based on the given options of self
     pub async fn run(&self) -> anyhow::Result<QuoterResult> {
-        let result: QuoterResult = match self.input.read_content()? {
-            InputContentType::Target(v) => v
-                .do_quote(self.rpc.as_str(), self.block_number, self.multicall_address)
-                .await?
-                .into(),
+        register_abi_files(&self.abi)?;
+        let result: QuoterResult = self.quote_at(self.block_number).await?.into();
+
+        if !self.no_stdout || self.output.is_some() {
+            let stringified_result = if self.pretty {
+                serde_json::to_string_pretty::<QuoterResult>(&result)?
+            } else {
+                serde_json::to_string::<QuoterResult>(&result)?
+            };
+            self.write_output(stringified_result)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Executes the CLI call over the given block range of self and returns
+    /// the flattened time series rows
+    pub async fn run_series(&self) -> anyhow::Result<Vec<QuoteSeriesRow>> {
+        let Some(range) = &self.block_range else {
+            return Err(anyhow::anyhow!("requires '--block-range' to quote over"));
+        };
+        // an open start would quote every block since genesis
+        if *range.start() == Block::Genesis {
+            return Err(anyhow::anyhow!(
+                "'--block-range' requires a start block, eg 'latest-7200..'"
+            ));
+        }
+        register_abi_files(&self.abi)?;
+        let blocks = match self.block_interval {
+            Some(interval) => Blocks::RangeWithInterval {
+                range: range.clone(),
+                interval,
+            },
+            None => Blocks::SimpleRange(range.clone()),
+        };
+        let series: Vec<QuoteSeriesPoint> = match self.input.read_content()? {
+            InputContentType::Target(v) => {
+                v.do_quote_series(self.rpc.as_str(), &blocks, self.multicall_address)
+                    .await?
+            }
             InputContentType::Spec(v) => {
                 if let Some(sg) = &self.subgraph {
-                    v.do_quote(
+                    v.do_quote_series(
                         sg.as_str(),
                         self.rpc.as_str(),
-                        self.block_number,
+                        &blocks,
                         self.multicall_address,
                     )
                     .await?
-                    .into()
                 } else {
                     return Err(anyhow::anyhow!(
                         "requires '--subgraph' url to read orders details from"
@@ -110,23 +210,117 @@ impl Quoter {
                 }
             }
         };
+        let rows = QuoteSeriesRow::from_series(series);
 
         if !self.no_stdout || self.output.is_some() {
-            let stringified_result = if self.pretty {
-                serde_json::to_string_pretty::<QuoterResult>(&result)?
-            } else {
-                serde_json::to_string::<QuoterResult>(&result)?
-            };
-            if !self.no_stdout {
-                let mut stdout = std::io::stdout().lock();
-                stdout.write_all(stringified_result.as_bytes())?;
-            }
-            if let Some(v) = &self.output {
-                write(v, stringified_result)?;
+            let stringified_result = self.series_format.stringify(&rows, self.pretty)?;
+            self.write_output(stringified_result)?;
+        }
+
+        Ok(rows)
+    }
+
+    /// Executes the CLI call in watch mode, re-quoting on every new block or
+    /// interval and outputting the changed quotes as JSON lines.
+    /// Only returns once a quote row satisfies the exit condition of self,
+    /// failed re-quotes are logged and retried on the next tick
+    pub async fn run_watch(&self) -> anyhow::Result<QuoteWatchRow> {
+        register_abi_files(&self.abi)?;
+        let mut watcher = QuoteWatcher::new(self.threshold);
+        let mut file = self.output.as_ref().map(File::create).transpose()?;
+        let mut last_block = None;
+        loop {
+            match self.watch_tick(&mut watcher, &mut last_block).await {
+                Ok(rows) => {
+                    for row in &rows {
+                        let line = serde_json::to_string(row)? + "\n";
+                        if !self.no_stdout {
+                            let mut stdout = std::io::stdout().lock();
+                            stdout.write_all(line.as_bytes())?;
+                            stdout.flush()?;
+                        }
+                        if let Some(f) = &mut file {
+                            f.write_all(line.as_bytes())?;
+                        }
+                    }
+                    if let Some(exit_on) = self.exit_on {
+                        if let Some(row) = rows.into_iter().find(|v| v.matches(exit_on)) {
+                            return Ok(row);
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("failed to re-quote: {}", e),
             }
+            tokio::time::sleep(Duration::from_secs(
+                self.interval.unwrap_or(BLOCK_POLL_INTERVAL_SECS),
+            ))
+            .await;
         }
+    }
 
-        Ok(result)
+    /// Quotes at the latest block and returns the changed rows, skips quoting
+    /// if no interval is set and there is no new block since the last tick
+    async fn watch_tick(
+        &self,
+        watcher: &mut QuoteWatcher,
+        last_block: &mut Option<u64>,
+    ) -> anyhow::Result<Vec<QuoteWatchRow>> {
+        let block_number = get_block_number(self.rpc.as_str()).await?;
+        if self.interval.is_none() && *last_block == Some(block_number) {
+            return Ok(vec![]);
+        }
+        let timestamp = get_block_timestamp(self.rpc.as_str(), block_number).await?;
+        let results = self.quote_at(Some(block_number)).await?;
+        *last_block = Some(block_number);
+        Ok(watcher.update(block_number, timestamp, results))
+    }
+
+    /// Executes the CLI call that the options of self describe
+    pub async fn run_cli(&self) -> anyhow::Result<()> {
+        if self.watch {
+            self.run_watch().await.map(|_| ())
+        } else if self.block_range.is_some() {
+            self.run_series().await.map(|_| ())
+        } else {
+            self.run().await.map(|_| ())
+        }
+    }
+
+    /// Quotes the input of self at the given block number
+    async fn quote_at(&self, block_number: Option<u64>) -> anyhow::Result<Vec<QuoteResult>> {
+        Ok(match self.input.read_content()? {
+            InputContentType::Target(v) => {
+                v.do_quote(self.rpc.as_str(), block_number, self.multicall_address)
+                    .await?
+            }
+            InputContentType::Spec(v) => {
+                if let Some(sg) = &self.subgraph {
+                    v.do_quote(
+                        sg.as_str(),
+                        self.rpc.as_str(),
+                        block_number,
+                        self.multicall_address,
+                    )
+                    .await?
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "requires '--subgraph' url to read orders details from"
+                    ));
+                }
+            }
+        })
+    }
+
+    /// Writes the given stringified result to stdout and/or output file
+    fn write_output(&self, stringified_result: String) -> anyhow::Result<()> {
+        if !self.no_stdout {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(stringified_result.as_bytes())?;
+        }
+        if let Some(v) = &self.output {
+            write(v, stringified_result)?;
+        }
+        Ok(())
     }
 }
 
@@ -134,7 +328,7 @@ impl Quoter {
 pub async fn main() -> anyhow::Result<()> {
     tracing::subscriber::set_global_default(tracing_subscriber::fmt::Subscriber::new())?;
     let cli = Quoter::parse();
-    cli.run().await.map(|_| ())
+    cli.run_cli().await
 }
 
 #[cfg(test)]
@@ -257,9 +451,17 @@ mod tests {
             rpc: Url::parse("http://a.com").unwrap(),
             subgraph: None,
             block_number: None,
+            block_range: None,
+            block_interval: None,
+            series_format: SeriesFormat::Json,
             multicall_address: None,
             no_stdout: true,
             pretty: true,
+            watch: false,
+            interval: None,
+            threshold: 0,
+            exit_on: None,
+            abi: vec![],
             input: Input {
                 target: None,
                 spec: None,
@@ -267,6 +469,7 @@ mod tests {
                     QuoteSpec::default(),
                     QuoteSpec::default(),
                 ])),
+                input_file: None,
             },
         };
         let result = cli.run().await.expect_err("expected error").to_string();
@@ -359,6 +562,7 @@ mod tests {
                     }],
                     "meta": null,
                     "timestampAdded": "0",
+                    "orderbook": { "id": encode_prefixed(Address::random().0.0) },
                 }]
             }
         });
@@ -383,13 +587,22 @@ mod tests {
             rpc: Url::parse(&rpc_url).unwrap(),
             subgraph: Some(Url::parse(&sg_url).unwrap()),
             block_number: None,
+            block_range: None,
+            block_interval: None,
+            series_format: SeriesFormat::Json,
             multicall_address: None,
             no_stdout: true,
             pretty: false,
+            watch: false,
+            interval: None,
+            threshold: 0,
+            exit_on: None,
+            abi: vec![],
             input: Input {
                 target: None,
                 spec: None,
                 input: Some(batch_quote_specs),
+                input_file: None,
             },
         };
 
@@ -417,13 +630,22 @@ mod tests {
             rpc: Url::parse(&rpc_url).unwrap(),
             subgraph: Some(Url::parse(&sg_url).unwrap()),
             block_number: None,
+            block_range: None,
+            block_interval: None,
+            series_format: SeriesFormat::Json,
             multicall_address: None,
             no_stdout: true,
             pretty: false,
+            watch: false,
+            interval: None,
+            threshold: 0,
+            exit_on: None,
+            abi: vec![],
             input: Input {
                 target: None,
                 input: None,
                 spec: Some(specs_str),
+                input_file: None,
             },
         };
 
@@ -457,13 +679,22 @@ mod tests {
             rpc: Url::parse(&rpc_url).unwrap(),
             subgraph: None,
             block_number: None,
+            block_range: None,
+            block_interval: None,
+            series_format: SeriesFormat::Json,
             multicall_address: None,
             no_stdout: false,
             pretty: false,
+            watch: false,
+            interval: None,
+            threshold: 0,
+            exit_on: None,
+            abi: vec![],
             input: Input {
                 input: None,
                 spec: None,
                 target: Some(targets_str),
+                input_file: None,
             },
         };
 
@@ -510,4 +741,168 @@ mod tests {
         // rmeove the output test file
         std::fs::remove_file(test_path).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_run_series_target_args() {
+        let rpc_server = MockServer::start_async().await;
+        let rpc_url = rpc_server.url("/rpc");
+
+        let targets_str = vec![
+            encode_prefixed(Address::random().0),
+            0.to_string(),
+            0.to_string(),
+            encode_prefixed(OrderV3::default().abi_encode()),
+        ];
+        let cli = Quoter::parse_from([
+            "cmd",
+            "--rpc",
+            &rpc_url,
+            "--block-range",
+            "98..100",
+            "--series-format",
+            "csv",
+            "--no-stdout",
+            "--target",
+            &targets_str[0],
+            &targets_str[1],
+            &targets_str[2],
+            &targets_str[3],
+        ]);
+        assert_eq!(cli.series_format, SeriesFormat::Csv);
+
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_blockNumber"}"#);
+            then.json_body_obj(
+                &serde_json::from_str::<serde_json::Value>(
+                    &Response::new_success(1, "0x64").to_json_string().unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_getBlockByNumber"}"#);
+            then.json_body_obj(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "timestamp": "0x3e8" }
+            }));
+        });
+        let rpc_response_data = vec![MulticallResult {
+            success: true,
+            returnData: quoteCall::abi_encode_returns(&(true, U256::from(1), U256::from(2))).into(),
+        }]
+        .abi_encode();
+        rpc_server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(
+                &serde_json::from_str::<serde_json::Value>(
+                    &Response::new_success(1, encode_prefixed(rpc_response_data).as_str())
+                        .to_json_string()
+                        .unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+
+        let result = cli.run_series().await.unwrap();
+        let expected = (98..=100)
+            .map(|block_number| QuoteSeriesRow {
+                block_number,
+                timestamp: 1000,
+                target_index: 0,
+                ratio: Some("2".to_string()),
+                max_output: Some("1".to_string()),
+                error: None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[tokio::test]
+    async fn test_run_watch_exit_on() {
+        let rpc_server = MockServer::start_async().await;
+        let rpc_url = rpc_server.url("/rpc");
+
+        let targets_str = vec![
+            encode_prefixed(Address::random().0),
+            0.to_string(),
+            0.to_string(),
+            encode_prefixed(OrderV3::default().abi_encode()),
+        ];
+        let cli = Quoter::parse_from([
+            "cmd",
+            "--rpc",
+            &rpc_url,
+            "--watch",
+            "--threshold",
+            "50",
+            "--exit-on",
+            "error",
+            "--no-stdout",
+            "--target",
+            &targets_str[0],
+            &targets_str[1],
+            &targets_str[2],
+            &targets_str[3],
+        ]);
+        assert!(cli.watch);
+        assert_eq!(cli.threshold, 50);
+        assert_eq!(cli.exit_on, Some(ExitOn::Error));
+
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_blockNumber"}"#);
+            then.json_body_obj(
+                &serde_json::from_str::<serde_json::Value>(
+                    &Response::new_success(1, "0x64").to_json_string().unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_getBlockByNumber"}"#);
+            then.json_body_obj(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "timestamp": "0x3e8" }
+            }));
+        });
+        let rpc_response_data = vec![MulticallResult {
+            success: true,
+            returnData: quoteCall::abi_encode_returns(&(false, U256::ZERO, U256::ZERO)).into(),
+        }]
+        .abi_encode();
+        rpc_server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(
+                &serde_json::from_str::<serde_json::Value>(
+                    &Response::new_success(1, encode_prefixed(rpc_response_data).as_str())
+                        .to_json_string()
+                        .unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+
+        let result = cli.run_watch().await.unwrap();
+        let expected = QuoteWatchRow {
+            change: QuoteChange::Initial,
+            row: QuoteSeriesRow {
+                block_number: 100,
+                timestamp: 1000,
+                target_index: 0,
+                ratio: None,
+                max_output: None,
+                error: Some(FailedQuote::NonExistent.to_string()),
+            },
+        };
+        assert_eq!(result, expected);
+    }
 }
@@ -0,0 +1,136 @@
+use crate::QuoteSeriesPoint;
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+
+/// Supported output formats of a quote time series
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeriesFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// A flattened row of a quote time series, ie the quote result of a single
+/// target at a specific block.
+/// Values are decimal strings so they can be directly loaded into charting tools
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteSeriesRow {
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub target_index: usize,
+    pub ratio: Option<String>,
+    pub max_output: Option<String>,
+    pub error: Option<String>,
+}
+
+impl QuoteSeriesRow {
+    /// Flattens the given quote series into rows
+    pub fn from_series(series: Vec<QuoteSeriesPoint>) -> Vec<Self> {
+        let mut rows = vec![];
+        for point in series {
+            for (target_index, result) in point.results.into_iter().enumerate() {
+                let (ratio, max_output, error) = match result {
+                    Ok(v) => (
+                        Some(v.ratio.to_string()),
+                        Some(v.max_output.to_string()),
+                        None,
+                    ),
+                    Err(e) => (None, None, Some(e.to_string())),
+                };
+                rows.push(Self {
+                    block_number: point.block_number,
+                    timestamp: point.timestamp,
+                    target_index,
+                    ratio,
+                    max_output,
+                    error,
+                });
+            }
+        }
+        rows
+    }
+}
+
+impl SeriesFormat {
+    /// Stringifies the given rows in self's format
+    pub fn stringify(&self, rows: &[QuoteSeriesRow], pretty: bool) -> anyhow::Result<String> {
+        match self {
+            SeriesFormat::Json => {
+                if pretty {
+                    Ok(serde_json::to_string_pretty(rows)?)
+                } else {
+                    Ok(serde_json::to_string(rows)?)
+                }
+            }
+            SeriesFormat::Csv => {
+                let mut csv_writer = Writer::from_writer(vec![]);
+                for row in rows {
+                    csv_writer.serialize(row)?;
+                }
+                Ok(String::from_utf8(csv_writer.into_inner()?)?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::FailedQuote, OrderQuoteValue};
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_series_rows() {
+        let series = vec![
+            QuoteSeriesPoint {
+                block_number: 10,
+                timestamp: 100,
+                results: vec![
+                    Ok(OrderQuoteValue {
+                        max_output: U256::from(3),
+                        ratio: U256::from(2),
+                    }),
+                    Err(FailedQuote::NonExistent),
+                ],
+            },
+            QuoteSeriesPoint {
+                block_number: 11,
+                timestamp: 112,
+                results: vec![
+                    Ok(OrderQuoteValue {
+                        max_output: U256::from(5),
+                        ratio: U256::from(1),
+                    }),
+                    Err(FailedQuote::NonExistent),
+                ],
+            },
+        ];
+        let rows = QuoteSeriesRow::from_series(series);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[2],
+            QuoteSeriesRow {
+                block_number: 11,
+                timestamp: 112,
+                target_index: 0,
+                ratio: Some("1".to_string()),
+                max_output: Some("5".to_string()),
+                error: None,
+            }
+        );
+
+        let result = SeriesFormat::Csv.stringify(&rows, false).unwrap();
+        let expected = "blockNumber,timestamp,targetIndex,ratio,maxOutput,error
+10,100,0,2,3,
+10,100,1,,,Order does not exist
+11,112,0,1,5,
+11,112,1,,,Order does not exist
+";
+        assert_eq!(result, expected);
+
+        let result = SeriesFormat::Json.stringify(&rows[..1], false).unwrap();
+        let expected = r#"[{"blockNumber":10,"timestamp":100,"targetIndex":0,"ratio":"2","maxOutput":"3","error":null}]"#;
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,208 @@
+use super::QuoteSeriesRow;
+use crate::{OrderQuoteValue, QuoteResult};
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+
+/// Conditions that end a watch session, useful for scripting alerts
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitOn {
+    /// Exit on the first change of any target after the initial quote
+    Change,
+    /// Exit as soon as any target's quote is an error
+    Error,
+    /// Exit as soon as any target's quote is ok
+    Ok,
+}
+
+/// Describes why a watched quote row was emitted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteChange {
+    /// First quote of the target in the watch session
+    Initial,
+    /// Ratio, max output or error message changed beyond the threshold
+    Changed,
+    /// Status flipped from error to ok
+    ToOk,
+    /// Status flipped from ok to error, eg vault emptied or order removed
+    ToError,
+}
+
+/// A single JSON line of the watch mode output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteWatchRow {
+    pub change: QuoteChange,
+    #[serde(flatten)]
+    pub row: QuoteSeriesRow,
+}
+
+impl QuoteWatchRow {
+    /// Determines if this row satisfies the given exit condition
+    pub fn matches(&self, exit_on: ExitOn) -> bool {
+        match exit_on {
+            ExitOn::Change => self.change != QuoteChange::Initial,
+            ExitOn::Error => self.row.error.is_some() && self.change != QuoteChange::Changed,
+            ExitOn::Ok => self.row.error.is_none() && self.change != QuoteChange::Changed,
+        }
+    }
+}
+
+/// Keeps the last emitted quote of each target and filters the new quotes
+/// down to the ones that changed
+#[derive(Debug, Clone, Default)]
+pub struct QuoteWatcher {
+    /// Minimum relative change of ratio or max output, in basis points
+    pub threshold_bps: u64,
+    previous: Vec<Option<Result<OrderQuoteValue, String>>>,
+}
+
+impl QuoteWatcher {
+    pub fn new(threshold_bps: u64) -> Self {
+        Self {
+            threshold_bps,
+            previous: vec![],
+        }
+    }
+
+    /// Compares the given quote results against the last emitted ones and
+    /// returns the rows that should be emitted, the emitted results become
+    /// the new baseline of their targets
+    pub fn update(
+        &mut self,
+        block_number: u64,
+        timestamp: u64,
+        results: Vec<QuoteResult>,
+    ) -> Vec<QuoteWatchRow> {
+        if self.previous.len() < results.len() {
+            self.previous.resize(results.len(), None);
+        }
+        let mut rows = vec![];
+        for (target_index, result) in results.into_iter().enumerate() {
+            let current = result.map_err(|e| e.to_string());
+            let change = match &self.previous[target_index] {
+                None => Some(QuoteChange::Initial),
+                Some(Ok(_)) if current.is_err() => Some(QuoteChange::ToError),
+                Some(Err(_)) if current.is_ok() => Some(QuoteChange::ToOk),
+                Some(Ok(prev)) => {
+                    let current = current.as_ref().expect("checked above");
+                    (self.exceeds_threshold(prev.ratio, current.ratio)
+                        || self.exceeds_threshold(prev.max_output, current.max_output))
+                    .then_some(QuoteChange::Changed)
+                }
+                Some(Err(prev)) => {
+                    (Some(prev) != current.as_ref().err()).then_some(QuoteChange::Changed)
+                }
+            };
+            if let Some(change) = change {
+                let (ratio, max_output, error) = match &current {
+                    Ok(v) => (
+                        Some(v.ratio.to_string()),
+                        Some(v.max_output.to_string()),
+                        None,
+                    ),
+                    Err(e) => (None, None, Some(e.clone())),
+                };
+                rows.push(QuoteWatchRow {
+                    change,
+                    row: QuoteSeriesRow {
+                        block_number,
+                        timestamp,
+                        target_index,
+                        ratio,
+                        max_output,
+                        error,
+                    },
+                });
+                self.previous[target_index] = Some(current);
+            }
+        }
+        rows
+    }
+
+    /// Checks if the change from prev to current exceeds the threshold
+    fn exceeds_threshold(&self, prev: U256, current: U256) -> bool {
+        let diff = if current > prev {
+            current - prev
+        } else {
+            prev - current
+        };
+        diff.saturating_mul(U256::from(10_000))
+            > prev.saturating_mul(U256::from(self.threshold_bps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FailedQuote;
+
+    fn ok(ratio: u64, max_output: u64) -> QuoteResult {
+        Ok(OrderQuoteValue {
+            ratio: U256::from(ratio),
+            max_output: U256::from(max_output),
+        })
+    }
+
+    #[test]
+    fn test_quote_watcher() {
+        // 1% threshold
+        let mut watcher = QuoteWatcher::new(100);
+
+        let rows = watcher.update(1, 10, vec![ok(1000, 5), Err(FailedQuote::NonExistent)]);
+        assert_eq!(
+            rows.iter().map(|v| v.change).collect::<Vec<_>>(),
+            vec![QuoteChange::Initial, QuoteChange::Initial]
+        );
+        assert!(rows[0].matches(ExitOn::Ok));
+        assert!(rows[1].matches(ExitOn::Error));
+        assert!(!rows[0].matches(ExitOn::Change));
+
+        // below threshold and same error, nothing is emitted
+        let rows = watcher.update(2, 12, vec![ok(1005, 5), Err(FailedQuote::NonExistent)]);
+        assert!(rows.is_empty());
+
+        // accumulated change against the last emitted quote exceeds threshold
+        let rows = watcher.update(3, 14, vec![ok(1011, 5), Err(FailedQuote::NonExistent)]);
+        assert_eq!(
+            rows,
+            vec![QuoteWatchRow {
+                change: QuoteChange::Changed,
+                row: QuoteSeriesRow {
+                    block_number: 3,
+                    timestamp: 14,
+                    target_index: 0,
+                    ratio: Some("1011".to_string()),
+                    max_output: Some("5".to_string()),
+                    error: None,
+                },
+            }]
+        );
+        assert!(rows[0].matches(ExitOn::Change));
+        assert!(!rows[0].matches(ExitOn::Ok));
+
+        // status flips
+        let rows = watcher.update(4, 16, vec![Err(FailedQuote::NonExistent), ok(1, 1)]);
+        assert_eq!(
+            rows.iter().map(|v| v.change).collect::<Vec<_>>(),
+            vec![QuoteChange::ToError, QuoteChange::ToOk]
+        );
+        assert!(rows[0].matches(ExitOn::Error));
+        assert!(rows[1].matches(ExitOn::Ok));
+
+        let result = serde_json::to_string(&rows[0]).unwrap();
+        let expected = r#"{"change":"toError","blockNumber":4,"timestamp":16,"targetIndex":0,"ratio":null,"maxOutput":null,"error":"Order does not exist"}"#;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_quote_watcher_zero_threshold() {
+        let mut watcher = QuoteWatcher::new(0);
+        watcher.update(1, 10, vec![ok(0, 0)]);
+        assert!(watcher.update(2, 12, vec![ok(0, 0)]).is_empty());
+        assert_eq!(
+            watcher.update(3, 14, vec![ok(0, 1)])[0].change,
+            QuoteChange::Changed
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{error::Error, rpc::batch_quote, QuoteResult, QuoteTarget};
+use alloy::primitives::{
+    hex::{decode, encode_prefixed},
+    Address, B256, U256,
+};
+use alloy::sol_types::SolValue;
+use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote};
+use rain_orderbook_subgraph_client::{types::order_detail, OrderbookSubgraphClient};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use url::Url;
+
+/// A level of a depth ladder, ie a quoted IO pair of a single order.
+/// Amounts are in their token decimals and the ratio is 18 decimals
+/// fixed point of input token per output token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthLevel {
+    pub order_hash: B256,
+    pub owner: Address,
+    pub input_io_index: u8,
+    pub output_io_index: u8,
+    pub ratio: U256,
+    pub max_output: U256,
+    pub max_input: U256,
+    pub cumulative_output: U256,
+    pub cumulative_input: U256,
+}
+
+/// Liquidity offered by the active orders of an orderbook for a token pair,
+/// sorted by ratio so that the best priced levels for a taker come first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthLadder {
+    pub orderbook: Address,
+    pub input_token: Address,
+    pub output_token: Address,
+    pub input_decimals: u8,
+    pub output_decimals: u8,
+    pub levels: Vec<DepthLevel>,
+}
+
+impl DepthLadder {
+    /// Builds a ladder from the given quote targets and their quote results,
+    /// targets that failed to quote or have no output are left out
+    pub fn from_quotes(
+        orderbook: Address,
+        input_token: Address,
+        output_token: Address,
+        quote_targets: &[QuoteTarget],
+        quote_results: Vec<QuoteResult>,
+    ) -> Self {
+        let mut input_decimals = None;
+        let mut output_decimals = None;
+        let mut levels = vec![];
+        for (target, result) in quote_targets.iter().zip(quote_results) {
+            let Ok(quote) = result else {
+                continue;
+            };
+            let config = &target.quote_config;
+            let (Some(input), Some(output)) = (
+                config
+                    .order
+                    .validInputs
+                    .get(config.inputIOIndex.to::<usize>()),
+                config
+                    .order
+                    .validOutputs
+                    .get(config.outputIOIndex.to::<usize>()),
+            ) else {
+                continue;
+            };
+            input_decimals.get_or_insert(input.decimals);
+            output_decimals.get_or_insert(output.decimals);
+            if quote.max_output.is_zero() {
+                continue;
+            }
+
+            // orderbook rounds the input up in favor of the order
+            let input18 = div_up(quote.max_output.saturating_mul(quote.ratio), one18());
+            levels.push(DepthLevel {
+                order_hash: target.get_order_hash(),
+                owner: config.order.owner,
+                input_io_index: config.inputIOIndex.to::<u8>(),
+                output_io_index: config.outputIOIndex.to::<u8>(),
+                ratio: quote.ratio,
+                max_output: scale18_to(quote.max_output, output.decimals, false),
+                max_input: scale18_to(input18, input.decimals, true),
+                cumulative_output: U256::ZERO,
+                cumulative_input: U256::ZERO,
+            });
+        }
+
+        levels.sort_by(|a, b| a.ratio.cmp(&b.ratio));
+        let mut cumulative_output = U256::ZERO;
+        let mut cumulative_input = U256::ZERO;
+        for level in levels.iter_mut() {
+            cumulative_output = cumulative_output.saturating_add(level.max_output);
+            cumulative_input = cumulative_input.saturating_add(level.max_input);
+            level.cumulative_output = cumulative_output;
+            level.cumulative_input = cumulative_input;
+        }
+
+        Self {
+            orderbook,
+            input_token,
+            output_token,
+            input_decimals: input_decimals.unwrap_or(18),
+            output_decimals: output_decimals.unwrap_or(18),
+            levels,
+        }
+    }
+}
+
+/// Builds quote targets for every IO pair of the given orders that trades
+/// the given input and output tokens
+pub fn get_depth_quote_targets(
+    orders: &[order_detail::Order],
+    orderbook: Address,
+    input_token: Address,
+    output_token: Address,
+) -> Result<Vec<QuoteTarget>, Error> {
+    let mut quote_targets = vec![];
+    for order_detail in orders {
+        let order = OrderV3::abi_decode(
+            decode(order_detail.order_bytes.0.as_str())?.as_slice(),
+            true,
+        )?;
+        for (input_io_index, input) in order.validInputs.iter().enumerate() {
+            if input.token != input_token {
+                continue;
+            }
+            for (output_io_index, output) in order.validOutputs.iter().enumerate() {
+                if output.token != output_token {
+                    continue;
+                }
+                quote_targets.push(QuoteTarget {
+                    orderbook,
+                    quote_config: Quote {
+                        order: order.clone(),
+                        inputIOIndex: U256::from(input_io_index),
+                        outputIOIndex: U256::from(output_io_index),
+                        signedContext: vec![],
+                    },
+                });
+            }
+        }
+    }
+    Ok(quote_targets)
+}
+
+/// Fetches the active orders of the given orderbook subgraph that have the
+/// given input and output tokens and quotes their matching IO pairs in batch
+pub async fn quote_token_pair(
+    subgraph_url: &str,
+    rpc_url: &str,
+    orderbook: Address,
+    input_token: Address,
+    output_token: Address,
+    block_number: Option<u64>,
+    multicall_address: Option<Address>,
+) -> Result<(Vec<QuoteTarget>, Vec<QuoteResult>), Error> {
+    let url = Url::from_str(subgraph_url)?;
+    let sg_client = OrderbookSubgraphClient::new(url);
+    // orderbook and erc20 entities of the subgraph are identified by their
+    // lowercase address
+    let orders = sg_client
+        .orders_by_token_pair_all(
+            encode_prefixed(orderbook),
+            encode_prefixed(input_token),
+            encode_prefixed(output_token),
+        )
+        .await?;
+
+    let quote_targets = get_depth_quote_targets(&orders, orderbook, input_token, output_token)?;
+    let quote_results = if quote_targets.is_empty() {
+        vec![]
+    } else {
+        batch_quote(&quote_targets, rpc_url, block_number, multicall_address).await?
+    };
+    Ok((quote_targets, quote_results))
+}
+
+/// Fetches the active orders of the given orderbook subgraph that have the
+/// given input and output tokens, quotes their matching IO pairs in batch
+/// and aggregates the results into a [DepthLadder]
+pub async fn get_depth(
+    subgraph_url: &str,
+    rpc_url: &str,
+    orderbook: Address,
+    input_token: Address,
+    output_token: Address,
+    block_number: Option<u64>,
+    multicall_address: Option<Address>,
+) -> Result<DepthLadder, Error> {
+    let (quote_targets, quote_results) = quote_token_pair(
+        subgraph_url,
+        rpc_url,
+        orderbook,
+        input_token,
+        output_token,
+        block_number,
+        multicall_address,
+    )
+    .await?;
+
+    Ok(DepthLadder::from_quotes(
+        orderbook,
+        input_token,
+        output_token,
+        &quote_targets,
+        quote_results,
+    ))
+}
+
+pub(crate) fn one18() -> U256 {
+    U256::from(10).pow(U256::from(18))
+}
+
+pub(crate) fn div_up(value: U256, denominator: U256) -> U256 {
+    let result = value / denominator;
+    if (value % denominator).is_zero() {
+        result
+    } else {
+        result.saturating_add(U256::from(1))
+    }
+}
+
+/// Scales an 18 decimals amount to the given decimals
+pub(crate) fn scale18_to(value: U256, decimals: u8, round_up: bool) -> U256 {
+    if decimals > 18 {
+        value.saturating_mul(U256::from(10).pow(U256::from(decimals - 18)))
+    } else if round_up {
+        div_up(value, U256::from(10).pow(U256::from(18 - decimals)))
+    } else {
+        value / U256::from(10).pow(U256::from(18 - decimals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::FailedQuote, OrderQuoteValue};
+    use rain_orderbook_bindings::IOrderBookV4::IO;
+
+    fn target(
+        orderbook: Address,
+        input_token: Address,
+        output_token: Address,
+        nonce: u8,
+    ) -> QuoteTarget {
+        QuoteTarget {
+            orderbook,
+            quote_config: Quote {
+                order: OrderV3 {
+                    validInputs: vec![IO {
+                        token: input_token,
+                        decimals: 6,
+                        vaultId: U256::from(1),
+                    }],
+                    validOutputs: vec![IO {
+                        token: output_token,
+                        decimals: 18,
+                        vaultId: U256::from(1),
+                    }],
+                    nonce: B256::from(U256::from(nonce)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_depth_ladder_from_quotes() {
+        let orderbook = Address::random();
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let targets = vec![
+            target(orderbook, input_token, output_token, 1),
+            target(orderbook, input_token, output_token, 2),
+            target(orderbook, input_token, output_token, 3),
+            target(orderbook, input_token, output_token, 4),
+        ];
+        let one = one18();
+        let results = vec![
+            Ok(OrderQuoteValue {
+                max_output: one * U256::from(2),
+                ratio: U256::from(3000) * one,
+            }),
+            Ok(OrderQuoteValue {
+                max_output: one,
+                ratio: U256::from(2900) * one,
+            }),
+            Err(FailedQuote::NonExistent),
+            Ok(OrderQuoteValue {
+                max_output: U256::ZERO,
+                ratio: U256::from(1000) * one,
+            }),
+        ];
+
+        let result =
+            DepthLadder::from_quotes(orderbook, input_token, output_token, &targets, results);
+
+        assert_eq!(result.input_decimals, 6);
+        assert_eq!(result.output_decimals, 18);
+        assert_eq!(result.levels.len(), 2);
+
+        let best = &result.levels[0];
+        assert_eq!(best.order_hash, targets[1].get_order_hash());
+        assert_eq!(best.ratio, U256::from(2900) * one);
+        assert_eq!(best.max_output, one);
+        assert_eq!(best.max_input, U256::from(2_900_000_000u64));
+        assert_eq!(best.cumulative_output, one);
+        assert_eq!(best.cumulative_input, U256::from(2_900_000_000u64));
+
+        let next = &result.levels[1];
+        assert_eq!(next.order_hash, targets[0].get_order_hash());
+        assert_eq!(next.max_output, one * U256::from(2));
+        assert_eq!(next.max_input, U256::from(6_000_000_000u64));
+        assert_eq!(next.cumulative_output, one * U256::from(3));
+        assert_eq!(next.cumulative_input, U256::from(8_900_000_000u64));
+    }
+
+    #[test]
+    fn test_get_depth_quote_targets() {
+        let orderbook = Address::random();
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let order = OrderV3 {
+            validInputs: vec![
+                IO {
+                    token: output_token,
+                    ..Default::default()
+                },
+                IO {
+                    token: input_token,
+                    ..Default::default()
+                },
+            ],
+            validOutputs: vec![
+                IO {
+                    token: output_token,
+                    ..Default::default()
+                },
+                IO {
+                    token: input_token,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let order_detail: order_detail::Order = serde_json::from_value(serde_json::json!({
+            "id": encode_prefixed(B256::random()),
+            "orderBytes": encode_prefixed(order.abi_encode()),
+            "orderHash": encode_prefixed(B256::random()),
+            "owner": encode_prefixed(order.owner),
+            "outputs": [],
+            "inputs": [],
+            "active": true,
+            "addEvents": [],
+            "meta": null,
+            "timestampAdded": "0",
+            "orderbook": { "id": encode_prefixed(orderbook) },
+        }))
+        .unwrap();
+
+        let result =
+            get_depth_quote_targets(&[order_detail], orderbook, input_token, output_token).unwrap();
+
+        assert_eq!(
+            result,
+            vec![QuoteTarget {
+                orderbook,
+                quote_config: Quote {
+                    order,
+                    inputIOIndex: U256::from(1),
+                    outputIOIndex: U256::from(0),
+                    signedContext: vec![],
+                },
+            }]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use alloy::primitives::hex::FromHexError;
 use alloy_ethers_typecast::transaction::ReadableClientError;
 use rain_error_decoding::{AbiDecodeFailedErrors, AbiDecodedErrorType};
+use rain_orderbook_app_settings::blocks::BlockError;
 use rain_orderbook_subgraph_client::OrderbookSubgraphClientError;
 use thiserror::Error;
 use url::ParseError;
@@ -18,6 +19,8 @@ pub enum FailedQuote {
     CorruptReturnData(String),
     #[error(transparent)]
     RevertErrorDecodeFailed(#[from] AbiDecodeFailedErrors),
+    #[error("Rpc call failed: {0}")]
+    RpcCallFailed(String),
     #[cfg(target_family = "wasm")]
     #[error(transparent)]
     SerdeWasmBindgenError(#[from] serde_wasm_bindgen::Error),
@@ -35,6 +38,14 @@ pub enum Error {
     FromHexError(#[from] FromHexError),
     #[error(transparent)]
     AlloySolTypesError(#[from] alloy::sol_types::Error),
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    #[error("Rpc error: {0}")]
+    RpcResponseError(String),
+    #[error(transparent)]
+    BlockError(#[from] BlockError),
+    #[error("No orders can be taken within the given price limit")]
+    NoTakeOrdersRoute,
     #[cfg(target_family = "wasm")]
     #[error(transparent)]
     SerdeWasmBindgenError(#[from] serde_wasm_bindgen::Error),
@@ -1,5 +1,6 @@
 use super::*;
 use crate::QuoteTarget as MainQuoteTarget;
+use crate::TakeOrdersRouteArgs as MainTakeOrdersRouteArgs;
 use crate::{OrderQuoteValue as MainOrderQuoteValue, QuoteSpec as MainQuoteSpec};
 use alloy::primitives::{
     hex::{encode_prefixed, FromHex},
@@ -106,11 +107,47 @@ impl From<crate::QuoteResult> for super::QuoteResult {
     }
 }
 
+impl From<TakeOrdersRouteArgs> for MainTakeOrdersRouteArgs {
+    fn from(value: TakeOrdersRouteArgs) -> Self {
+        let mut orderbook_error = "orderbook address, ".to_string();
+        let mut sell_token_error = "sell token address, ".to_string();
+        let mut buy_token_error = "buy token address, ".to_string();
+        let mut buy_amount_error = "buy amount, ".to_string();
+        let mut max_average_ratio_error = "max average ratio, ".to_string();
+        let mut multicall_address_error = "multicall address, ".to_string();
+        MainTakeOrdersRouteArgs {
+            orderbook: Address::from_hex(&value.orderbook)
+                .inspect_err(|e| orderbook_error.push_str(&e.to_string()))
+                .expect_throw(&orderbook_error),
+            sell_token: Address::from_hex(&value.sell_token)
+                .inspect_err(|e| sell_token_error.push_str(&e.to_string()))
+                .expect_throw(&sell_token_error),
+            buy_token: Address::from_hex(&value.buy_token)
+                .inspect_err(|e| buy_token_error.push_str(&e.to_string()))
+                .expect_throw(&buy_token_error),
+            buy_amount: U256::from_str(&value.buy_amount)
+                .inspect_err(|e| buy_amount_error.push_str(&e.to_string()))
+                .expect_throw(&buy_amount_error),
+            max_average_ratio: U256::from_str(&value.max_average_ratio)
+                .inspect_err(|e| max_average_ratio_error.push_str(&e.to_string()))
+                .expect_throw(&max_average_ratio_error),
+            slippage_bps: value.slippage_bps,
+            block_number: value.block_number,
+            multicall_address: value.multicall_address.map(|v| {
+                Address::from_hex(v)
+                    .inspect_err(|e| multicall_address_error.push_str(&e.to_string()))
+                    .expect_throw(&multicall_address_error)
+            }),
+        }
+    }
+}
+
 impl_wasm_traits!(QuoteSpec);
 impl_wasm_traits!(QuoteTarget);
 impl_wasm_traits!(QuoteResult);
 impl_wasm_traits!(BatchQuoteSpec);
 impl_wasm_traits!(BatchQuoteTarget);
+impl_wasm_traits!(TakeOrdersRouteArgs);
 
 #[cfg(test)]
 mod tests {
@@ -1,4 +1,5 @@
 use crate::{error::Error, BatchQuoteSpec as MainBatchQuoteSpec, QuoteSpec as MainQuoteSpec};
+use crate::{get_take_orders_route, TakeOrdersRouteArgs as MainTakeOrdersRouteArgs};
 use crate::{BatchQuoteTarget as MainBatchQuoteTarget, QuoteTarget as MainQuoteTarget};
 use alloy::primitives::{
     hex::{encode_prefixed, FromHex},
@@ -60,6 +61,23 @@ pub struct QuoteSpec {
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct BatchQuoteSpec(pub Vec<QuoteSpec>);
 
+/// Specifies what a taker wants to buy from an orderbook and at what price,
+/// amount and ratio are 18 decimals fixed point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct TakeOrdersRouteArgs {
+    pub orderbook: String,
+    pub sell_token: String,
+    pub buy_token: String,
+    pub buy_amount: String,
+    pub max_average_ratio: String,
+    #[serde(default)]
+    pub slippage_bps: u64,
+    pub block_number: Option<u64>,
+    pub multicall_address: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 #[serde(untagged)]
@@ -175,3 +193,18 @@ pub async fn get_batch_quote_target_from_subgraph(
         )?),
     }
 }
+
+/// Given a subgraph url, will fetch the orders that buy the sell token for
+/// the buy token, quotes them using the given rpc url and chooses the orders
+/// that fill the buy amount within the max average ratio.
+/// Resolves with the chosen orders, the takeOrders2 config and its calldata
+#[wasm_bindgen(js_name = "getTakeOrdersRoute")]
+pub async fn get_take_orders_route_js(
+    args: &TakeOrdersRouteArgs,
+    subgraph_url: &str,
+    rpc_url: &str,
+) -> Result<JsValue, Error> {
+    let args = MainTakeOrdersRouteArgs::from(args.clone());
+    let route = get_take_orders_route(subgraph_url, rpc_url, &args).await?;
+    Ok(to_value(&route)?)
+}
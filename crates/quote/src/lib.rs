@@ -1,10 +1,17 @@
 #[cfg(not(target_family = "wasm"))]
 pub mod cli;
+mod depth;
 pub mod error;
 mod quote;
+pub mod revert;
 pub mod rpc;
+mod series;
+mod take_orders;
 
 #[cfg(target_family = "wasm")]
 pub mod js_api;
 
+pub use depth::*;
 pub use quote::*;
+pub use series::*;
+pub use take_orders::*;
@@ -314,6 +314,7 @@ mod tests {
             }],
             "meta": null,
             "timestampAdded": "0",
+            "orderbook": { "id": encode_prefixed(Address::random().0.0) },
         });
         let retrun_sg_data = if batch {
             json!({
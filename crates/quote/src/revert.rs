@@ -0,0 +1,315 @@
+use alloy::dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy::json_abi::{Error as AbiError, JsonAbi};
+use alloy::primitives::hex::encode_prefixed;
+use once_cell::sync::Lazy;
+use rain_error_decoding::{AbiDecodeFailedErrors, AbiDecodedErrorType};
+use rain_orderbook_bindings::{
+    IOrderBookV4, OrderBook, RainterpreterNPE2, RainterpreterStoreNPE2, ERC20, IERC20,
+};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[cfg(not(target_family = "wasm"))]
+use std::path::{Path, PathBuf};
+
+/// Solidity builtin revert errors that are not part of any contract abi
+const BUILTIN_ERRORS: [&str; 2] = ["Error(string)", "Panic(uint256)"];
+
+/// The process wide local error registry, initially populated with the errors
+/// of the contracts compiled into the bindings and the errors cached on disk
+pub static LOCAL_ERROR_REGISTRY: Lazy<RwLock<ErrorRegistry>> = Lazy::new(|| {
+    let mut registry = ErrorRegistry::from_bindings();
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(path) = cache_file_path() {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            if let Ok(sigs) = serde_json::from_str::<Vec<String>>(&content) {
+                for sig in sigs {
+                    registry.register_signature(&sig);
+                }
+            }
+        }
+    }
+    RwLock::new(registry)
+});
+
+/// A registry of known solidity custom errors keyed by their selectors, used
+/// for decoding revert data without a remote selector registry
+#[derive(Debug, Clone, Default)]
+pub struct ErrorRegistry {
+    errors: HashMap<[u8; 4], Vec<AbiError>>,
+}
+
+impl ErrorRegistry {
+    /// Builds a registry from the orderbook, interpreter and ERC20 abis
+    /// compiled into the bindings and solidity builtin errors
+    pub fn from_bindings() -> Self {
+        let mut registry = Self::default();
+        for abi in [
+            IOrderBookV4::abi::contract(),
+            OrderBook::abi::contract(),
+            RainterpreterNPE2::abi::contract(),
+            RainterpreterStoreNPE2::abi::contract(),
+            IERC20::abi::contract(),
+            ERC20::abi::contract(),
+        ] {
+            registry.register_abi(&abi);
+        }
+        for sig in BUILTIN_ERRORS {
+            registry.register_signature(sig);
+        }
+        registry
+    }
+
+    /// Adds the errors of the given abi to the registry
+    pub fn register_abi(&mut self, abi: &JsonAbi) {
+        for error in abi.errors() {
+            self.register_error(error.clone());
+        }
+    }
+
+    /// Adds the given error to the registry, returns false if the error
+    /// was already registered
+    pub fn register_error(&mut self, error: AbiError) -> bool {
+        let errors = self.errors.entry(error.selector().0).or_default();
+        if errors.iter().any(|v| v.signature() == error.signature()) {
+            false
+        } else {
+            errors.push(error);
+            true
+        }
+    }
+
+    /// Parses and adds the given error signature, eg `Error(string)`, to the
+    /// registry, returns false if the signature is invalid or already registered
+    pub fn register_signature(&mut self, sig: &str) -> bool {
+        AbiError::parse(sig)
+            .map(|error| self.register_error(error))
+            .unwrap_or(false)
+    }
+
+    /// Reads an abi json file, either a plain abi array or a compiled
+    /// contract artifact with an `abi` field, and adds its errors
+    #[cfg(not(target_family = "wasm"))]
+    pub fn register_abi_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        let value: serde_json::Value = serde_json::from_str(&content)?;
+        let abi: JsonAbi = match value.get("abi") {
+            Some(abi) => serde_json::from_value(abi.clone())?,
+            None => serde_json::from_value(value)?,
+        };
+        self.register_abi(&abi);
+        Ok(())
+    }
+
+    /// Tries to decode the given revert data with the registered errors
+    pub fn decode(&self, data: &[u8]) -> Option<AbiDecodedErrorType> {
+        let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+        self.errors.get(&selector)?.iter().find_map(|error| {
+            error
+                .abi_decode_input(&data[4..], false)
+                .ok()
+                .map(|values| AbiDecodedErrorType::Known {
+                    name: error.name.clone(),
+                    args: values.iter().map(format_value).collect(),
+                    sig: error.signature(),
+                    data: data.to_vec(),
+                })
+        })
+    }
+}
+
+/// Adds the errors of the given abi json files to the local error registry
+#[cfg(not(target_family = "wasm"))]
+pub fn register_abi_files(paths: &[PathBuf]) -> anyhow::Result<()> {
+    let mut registry = LOCAL_ERROR_REGISTRY
+        .write()
+        .map_err(|_| anyhow::anyhow!("poisoned error registry"))?;
+    for path in paths {
+        registry.register_abi_file(path)?;
+    }
+    Ok(())
+}
+
+/// Decodes the given revert data, first against the local error registry and
+/// only if that fails against the remote selector registry, errors resolved
+/// by the remote registry are added to the local one and cached on disk
+pub async fn decode_revert(data: &[u8]) -> Result<AbiDecodedErrorType, AbiDecodeFailedErrors> {
+    if let Some(decoded) = LOCAL_ERROR_REGISTRY
+        .read()
+        .ok()
+        .and_then(|registry| registry.decode(data))
+    {
+        return Ok(decoded);
+    }
+    let decoded = AbiDecodedErrorType::selector_registry_abi_decode(data).await?;
+    if let AbiDecodedErrorType::Known { sig, .. } = &decoded {
+        remember_signature(sig);
+    }
+    Ok(decoded)
+}
+
+/// Adds the given error signature to the local error registry and caches it
+/// on disk if it was not known before
+fn remember_signature(sig: &str) {
+    let is_new = LOCAL_ERROR_REGISTRY
+        .write()
+        .map(|mut registry| registry.register_signature(sig))
+        .unwrap_or(false);
+    #[cfg(not(target_family = "wasm"))]
+    if is_new {
+        if let Err(e) = cache_signature(sig) {
+            tracing::warn!("failed to cache error signature: {}", e);
+        }
+    }
+    #[cfg(target_family = "wasm")]
+    let _ = is_new;
+}
+
+/// Path of the on disk cache of error signatures resolved by the remote registry
+#[cfg(not(target_family = "wasm"))]
+fn cache_file_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|v| v.join("rain-orderbook").join("error-signatures.json"))
+}
+
+/// Appends the given error signature to the on disk cache
+#[cfg(not(target_family = "wasm"))]
+fn cache_signature(sig: &str) -> anyhow::Result<()> {
+    let path = cache_file_path().ok_or_else(|| anyhow::anyhow!("no cache directory"))?;
+    let mut sigs: Vec<String> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+    if !sigs.iter().any(|v| v == sig) {
+        sigs.push(sig.to_string());
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(&sigs)?)?;
+    }
+    Ok(())
+}
+
+/// Formats a decoded error argument into a human readable string
+fn format_value(value: &DynSolValue) -> String {
+    if let Some(v) = value.as_str() {
+        v.to_string()
+    } else if let Some(v) = value.as_address() {
+        v.to_string()
+    } else if let Some((v, _)) = value.as_uint() {
+        v.to_string()
+    } else if let Some((v, _)) = value.as_int() {
+        v.to_string()
+    } else if let Some(v) = value.as_bool() {
+        v.to_string()
+    } else if let Some((v, size)) = value.as_fixed_bytes() {
+        encode_prefixed(&v[..size])
+    } else if let Some(v) = value.as_bytes() {
+        encode_prefixed(v)
+    } else if let Some(v) = value.as_tuple() {
+        format!(
+            "({})",
+            v.iter().map(format_value).collect::<Vec<_>>().join(", ")
+        )
+    } else if let Some(v) = value.as_array().or(value.as_fixed_array()) {
+        format!(
+            "[{}]",
+            v.iter().map(format_value).collect::<Vec<_>>().join(", ")
+        )
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+    use alloy::sol_types::{SolError, SolValue};
+    use rain_orderbook_bindings::OrderBook::SameOwner;
+
+    #[test]
+    fn test_decode_local_error() {
+        let registry = ErrorRegistry::from_bindings();
+
+        let owner = Address::random();
+        let data = SameOwner { owner }.abi_encode();
+        let result = registry.decode(&data).unwrap();
+        assert!(matches!(
+            result,
+            AbiDecodedErrorType::Known { name, args, sig, .. }
+                if name == "SameOwner" && args == vec![owner.to_string()] && sig == "SameOwner(address)"
+        ));
+
+        // builtin revert string
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend("some reason".to_string().abi_encode_params());
+        let result = registry.decode(&data).unwrap();
+        assert!(matches!(
+            result,
+            AbiDecodedErrorType::Known { name, args, .. }
+                if name == "Error" && args == vec!["some reason".to_string()]
+        ));
+
+        // unknown selector
+        assert!(registry.decode(&[1, 2, 3, 4]).is_none());
+        assert!(registry.decode(&[1, 2]).is_none());
+    }
+
+    #[test]
+    fn test_register_signature() {
+        let mut registry = ErrorRegistry::default();
+        assert!(registry.register_signature("SomeError(address,uint256[])"));
+        assert!(!registry.register_signature("SomeError(address,uint256[])"));
+        assert!(!registry.register_signature("not a signature"));
+
+        let address = Address::random();
+        let error = AbiError::parse("SomeError(address,uint256[])").unwrap();
+        let mut data = error.selector().to_vec();
+        data.extend((address, vec![U256::from(1), U256::from(2)]).abi_encode_params());
+        let result = registry.decode(&data).unwrap();
+        assert!(matches!(
+            result,
+            AbiDecodedErrorType::Known { name, args, .. }
+                if name == "SomeError" && args == vec![address.to_string(), "[1, 2]".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_register_abi_file() {
+        let test_path = std::env::current_dir().unwrap().join("test-error-abi.json");
+        std::fs::write(
+            &test_path,
+            r#"{"abi":[{"type":"error","name":"CustomError","inputs":[{"name":"a","type":"uint256"}]}]}"#,
+        )
+        .unwrap();
+        let mut registry = ErrorRegistry::default();
+        let result = registry.register_abi_file(&test_path);
+        std::fs::remove_file(test_path).unwrap();
+        result.unwrap();
+
+        let error = AbiError::parse("CustomError(uint256)").unwrap();
+        let mut data = error.selector().to_vec();
+        data.extend(U256::from(7).abi_encode());
+        let result = registry.decode(&data).unwrap();
+        assert!(matches!(
+            result,
+            AbiDecodedErrorType::Known { name, args, .. }
+                if name == "CustomError" && args == vec!["7".to_string()]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decode_revert_local_first() {
+        // resolves without touching the remote registry
+        let data = SameOwner {
+            owner: Address::random(),
+        }
+        .abi_encode();
+        assert_eq!(SameOwner::SELECTOR.to_vec(), data[..4].to_vec());
+        let result = decode_revert(&data).await.unwrap();
+        assert!(matches!(
+            result,
+            AbiDecodedErrorType::Known { name, .. } if name == "SameOwner"
+        ));
+    }
+}
@@ -1,8 +1,12 @@
 use crate::{
     error::{Error, FailedQuote},
     quote::{QuoteResult, QuoteTarget},
+    revert::decode_revert,
+};
+use alloy::primitives::{
+    hex::{decode, encode_prefixed, FromHex},
+    Address, U64,
 };
-use alloy::primitives::{hex::FromHex, Address, U64};
 use alloy::sol_types::SolCall;
 use alloy_ethers_typecast::{
     multicall::{
@@ -11,19 +15,68 @@ use alloy_ethers_typecast::{
     },
     transaction::{ReadContractParameters, ReadableClient},
 };
-use rain_error_decoding::AbiDecodedErrorType;
+use futures::future::join_all;
 use rain_orderbook_bindings::IOrderBookV4::quoteCall;
+use serde_json::{json, Value};
+
+/// Determines how a batch of quote targets is sent to the rpc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteTransport {
+    /// All quotes are aggregated into a single `aggregate3` call
+    /// to the multicall3 contract at the given address
+    Multicall(Address),
+    /// Each quote is sent as its own `eth_call` directly to the
+    /// target's orderbook, all in parallel
+    Direct,
+}
+
+impl QuoteTransport {
+    /// Picks the transport for the given rpc by checking if the multicall
+    /// contract has code deployed at the given block, falls back to
+    /// [QuoteTransport::Direct] if it doesnt
+    pub async fn detect(
+        rpc: &str,
+        block_number: Option<u64>,
+        multicall_address: Option<Address>,
+    ) -> Result<Self, Error> {
+        let multicall_address =
+            multicall_address.unwrap_or(Address::from_hex(MULTICALL3_ADDRESS).unwrap());
+        let code = get_code(rpc, multicall_address, block_number).await?;
+        if code.is_empty() {
+            Ok(Self::Direct)
+        } else {
+            Ok(Self::Multicall(multicall_address))
+        }
+    }
+}
 
 /// Quotes array of given quote targets using the given rpc url
+/// Quotes are aggregated through multicall3 if it is deployed on the
+/// chain, otherwise they are sent as individual eth_calls
 pub async fn batch_quote(
     quote_targets: &[QuoteTarget],
     rpc: &str,
     block_number: Option<u64>,
     multicall_address: Option<Address>,
+) -> Result<Vec<QuoteResult>, Error> {
+    match QuoteTransport::detect(rpc, block_number, multicall_address).await? {
+        QuoteTransport::Multicall(multicall_address) => {
+            multicall_batch_quote(quote_targets, rpc, block_number, multicall_address).await
+        }
+        QuoteTransport::Direct => direct_batch_quote(quote_targets, rpc, block_number).await,
+    }
+}
+
+/// Quotes array of given quote targets using multicall3 `aggregate3`
+pub async fn multicall_batch_quote(
+    quote_targets: &[QuoteTarget],
+    rpc: &str,
+    block_number: Option<u64>,
+    multicall_address: Address,
 ) -> Result<Vec<QuoteResult>, Error> {
     let client = ReadableClient::new_from_url(rpc.to_string())?;
     let parameters = ReadContractParameters {
-        address: multicall_address.unwrap_or(Address::from_hex(MULTICALL3_ADDRESS).unwrap()),
+        address: multicall_address,
         block_number: block_number.map(U64::from),
         call: aggregate3Call {
             calls: quote_targets
@@ -44,27 +97,196 @@ pub async fn batch_quote(
 
     let mut result: Vec<QuoteResult> = vec![];
     for res in multicall_result.returnData {
-        if res.success {
-            match quoteCall::abi_decode_returns(&res.returnData, true) {
-                Ok(v) => {
-                    if v.exists {
-                        result.push(Ok(v.into()));
-                    } else {
-                        result.push(Err(FailedQuote::NonExistent));
-                    }
-                }
-                Err(e) => result.push(Err(FailedQuote::CorruptReturnData(e.to_string()))),
-            }
-        } else {
-            match AbiDecodedErrorType::selector_registry_abi_decode(&res.returnData).await {
-                Ok(e) => result.push(Err(FailedQuote::RevertError(e))),
-                Err(e) => result.push(Err(FailedQuote::RevertErrorDecodeFailed(e))),
+        result.push(decode_quote_return(res.success, &res.returnData).await);
+    }
+    Ok(result)
+}
+
+/// Quotes array of given quote targets by sending a direct `quote` eth_call
+/// for each of them to their orderbook in parallel, this is used for chains
+/// that dont have multicall3 deployed, a failed call only fails its own quote
+/// just like a failed call in a multicall `aggregate3` would
+pub async fn direct_batch_quote(
+    quote_targets: &[QuoteTarget],
+    rpc: &str,
+    block_number: Option<u64>,
+) -> Result<Vec<QuoteResult>, Error> {
+    // one client for all calls so they share its connection pool
+    let client = reqwest::Client::new();
+    let calls = quote_targets.iter().map(|quote_target| {
+        eth_call(
+            &client,
+            rpc,
+            quote_target.orderbook,
+            quoteCall {
+                quoteConfig: quote_target.quote_config.clone(),
             }
+            .abi_encode(),
+            block_number,
+        )
+    });
+
+    let mut result: Vec<QuoteResult> = vec![];
+    for res in join_all(calls).await {
+        match res {
+            Ok(Ok(data)) => result.push(decode_quote_return(true, &data).await),
+            Ok(Err(revert_data)) => result.push(decode_quote_return(false, &revert_data).await),
+            Err(e) => result.push(Err(FailedQuote::RpcCallFailed(e.to_string()))),
         }
     }
     Ok(result)
 }
 
+/// Decodes the return data of a quote call into a [QuoteResult]
+async fn decode_quote_return(success: bool, data: &[u8]) -> QuoteResult {
+    if success {
+        match quoteCall::abi_decode_returns(data, true) {
+            Ok(v) => {
+                if v.exists {
+                    Ok(v.into())
+                } else {
+                    Err(FailedQuote::NonExistent)
+                }
+            }
+            Err(e) => Err(FailedQuote::CorruptReturnData(e.to_string())),
+        }
+    } else {
+        match decode_revert(data).await {
+            Ok(e) => Err(FailedQuote::RevertError(e)),
+            Err(e) => Err(FailedQuote::RevertErrorDecodeFailed(e)),
+        }
+    }
+}
+
+/// Sends a json-rpc request and returns its `result` field, or the
+/// `error` object as the inner Err if the node responded with an error
+async fn json_rpc_request(
+    client: &reqwest::Client,
+    rpc: &str,
+    method: &str,
+    params: Value,
+) -> Result<Result<Value, Value>, Error> {
+    let response = client
+        .post(rpc)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+
+    if let Some(error) = response.get("error") {
+        return Ok(Err(error.clone()));
+    }
+    response
+        .get("result")
+        .cloned()
+        .map(Ok)
+        .ok_or_else(|| Error::RpcResponseError(format!("missing result for {}", method)))
+}
+
+/// Builds the json-rpc block tag param for the given block number
+fn block_tag(block_number: Option<u64>) -> String {
+    block_number
+        .map(|v| format!("0x{:x}", v))
+        .unwrap_or_else(|| "latest".to_string())
+}
+
+/// Parses a json-rpc hex encoded quantity into u64
+fn parse_quantity(value: &Value) -> Result<u64, Error> {
+    value
+        .as_str()
+        .and_then(|v| u64::from_str_radix(v.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| Error::RpcResponseError(format!("invalid quantity: {}", value)))
+}
+
+/// Reads the latest block number with `eth_blockNumber`
+pub async fn get_block_number(rpc: &str) -> Result<u64, Error> {
+    match json_rpc_request(&reqwest::Client::new(), rpc, "eth_blockNumber", json!([])).await? {
+        Ok(v) => parse_quantity(&v),
+        Err(e) => Err(Error::RpcResponseError(e.to_string())),
+    }
+}
+
+/// Reads the timestamp of the given block with `eth_getBlockByNumber`
+pub async fn get_block_timestamp(rpc: &str, block_number: u64) -> Result<u64, Error> {
+    match json_rpc_request(
+        &reqwest::Client::new(),
+        rpc,
+        "eth_getBlockByNumber",
+        json!([block_tag(Some(block_number)), false]),
+    )
+    .await?
+    {
+        Ok(block) => parse_quantity(block.get("timestamp").unwrap_or(&Value::Null)),
+        Err(e) => Err(Error::RpcResponseError(e.to_string())),
+    }
+}
+
+/// Reads the code deployed at the given address with `eth_getCode`
+pub async fn get_code(
+    rpc: &str,
+    address: Address,
+    block_number: Option<u64>,
+) -> Result<Vec<u8>, Error> {
+    match json_rpc_request(
+        &reqwest::Client::new(),
+        rpc,
+        "eth_getCode",
+        json!([encode_prefixed(address), block_tag(block_number)]),
+    )
+    .await?
+    {
+        Ok(Value::String(code)) => Ok(decode(code)?),
+        Ok(v) => Err(Error::RpcResponseError(format!(
+            "unexpected eth_getCode result: {}",
+            v
+        ))),
+        Err(e) => Err(Error::RpcResponseError(e.to_string())),
+    }
+}
+
+/// Sends an `eth_call` with the given calldata to the given address,
+/// resolves with the inner Ok for the call's return data and the inner
+/// Err for the revert data if the call reverted
+async fn eth_call(
+    client: &reqwest::Client,
+    rpc: &str,
+    to: Address,
+    calldata: Vec<u8>,
+    block_number: Option<u64>,
+) -> Result<Result<Vec<u8>, Vec<u8>>, Error> {
+    match json_rpc_request(
+        client,
+        rpc,
+        "eth_call",
+        json!([
+            {
+                "to": encode_prefixed(to),
+                "data": encode_prefixed(calldata),
+            },
+            block_tag(block_number)
+        ]),
+    )
+    .await?
+    {
+        Ok(Value::String(data)) => Ok(Ok(decode(data)?)),
+        Ok(v) => Err(Error::RpcResponseError(format!(
+            "unexpected eth_call result: {}",
+            v
+        ))),
+        // reverted calls carry the revert data in the error object's "data" field
+        Err(e) => match e.get("data").and_then(Value::as_str) {
+            Some(revert_data) => Ok(Err(decode(revert_data)?)),
+            None => Err(Error::RpcResponseError(e.to_string())),
+        },
+    }
+}
+
 #[cfg(not(target_family = "wasm"))]
 #[cfg(test)]
 mod tests {
@@ -125,6 +347,17 @@ mod tests {
         ]
         .abi_encode();
 
+        // mock multicall code so multicall transport is picked
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x6080").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+
         // mock rpc with call data and response data
         rpc_server.mock(|when, then| {
             when.method(POST).path("/").json_body_partial(
@@ -173,4 +406,174 @@ mod tests {
         );
         assert!(iter_result.next().is_none());
     }
+
+    #[tokio::test]
+    async fn test_quote_transport_detect() {
+        let rpc_server = MockServer::start_async().await;
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/with-code")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x6080").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/no-code")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+
+        let multicall = Address::random();
+        let result =
+            QuoteTransport::detect(rpc_server.url("/with-code").as_str(), None, Some(multicall))
+                .await
+                .unwrap();
+        assert_eq!(result, QuoteTransport::Multicall(multicall));
+
+        let result = QuoteTransport::detect(rpc_server.url("/with-code").as_str(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            QuoteTransport::Multicall(Address::from_hex(MULTICALL3_ADDRESS).unwrap())
+        );
+
+        let result = QuoteTransport::detect(rpc_server.url("/no-code").as_str(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(result, QuoteTransport::Direct);
+    }
+
+    #[tokio::test]
+    async fn test_batch_quote_direct_fallback() {
+        let rpc_server = MockServer::start_async().await;
+
+        // no multicall code on chain
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/ok")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/ok")
+                .json_body_partial(r#"{"method":"eth_call"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(
+                    &Response::new_success(
+                        1,
+                        encode_prefixed(quoteCall::abi_encode_returns(&(
+                            true,
+                            U256::from(1),
+                            U256::from(2),
+                        )))
+                        .as_str(),
+                    )
+                    .to_json_string()
+                    .unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+
+        let quote_targets = vec![QuoteTarget::default(), QuoteTarget::default()];
+        let result = batch_quote(&quote_targets, rpc_server.url("/ok").as_str(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        for res in result {
+            assert_eq!(
+                res.unwrap(),
+                OrderQuoteValue {
+                    max_output: U256::from(1),
+                    ratio: U256::from(2),
+                }
+            );
+        }
+
+        // reverted quote calls
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/revert")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/revert")
+                .json_body_partial(r#"{"method":"eth_call"}"#);
+            then.json_body_obj(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": {
+                    "code": 3,
+                    "message": "execution reverted",
+                    "data": "0x"
+                }
+            }));
+        });
+
+        let result = batch_quote(
+            &[QuoteTarget::default()],
+            rpc_server.url("/revert").as_str(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            result[0],
+            Err(FailedQuote::RevertErrorDecodeFailed(_))
+        ));
+
+        // rpc errors without revert data only fail their own quote
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc-error")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc-error")
+                .json_body_partial(r#"{"method":"eth_call"}"#);
+            then.json_body_obj(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": {
+                    "code": -32000,
+                    "message": "header not found"
+                }
+            }));
+        });
+
+        let result = batch_quote(
+            &[QuoteTarget::default(), QuoteTarget::default()],
+            rpc_server.url("/rpc-error").as_str(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.len(), 2);
+        for res in result {
+            assert!(matches!(res, Err(FailedQuote::RpcCallFailed(_))));
+        }
+    }
 }
@@ -0,0 +1,196 @@
+use crate::{
+    error::{Error, FailedQuote},
+    rpc::{batch_quote, get_block_number, get_block_timestamp},
+    BatchQuoteSpec, BatchQuoteTarget, QuoteResult, QuoteTarget,
+};
+use alloy::primitives::Address;
+use rain_orderbook_app_settings::blocks::Blocks;
+use std::collections::VecDeque;
+
+/// Holds the quote results of a batch of targets at a specific block
+#[derive(Debug)]
+pub struct QuoteSeriesPoint {
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub results: Vec<QuoteResult>,
+}
+
+/// Quotes array of given quote targets at every block of the given [Blocks]
+/// using the given rpc url, the resulting points are sorted by block number
+pub async fn quote_series(
+    quote_targets: &[QuoteTarget],
+    rpc: &str,
+    blocks: &Blocks,
+    multicall_address: Option<Address>,
+) -> Result<Vec<QuoteSeriesPoint>, Error> {
+    let latest_block = get_block_number(rpc).await?;
+    let block_numbers = blocks.expand_to_block_numbers(latest_block)?;
+
+    let mut series = vec![];
+    for block_number in block_numbers {
+        let timestamp = get_block_timestamp(rpc, block_number).await?;
+        let results =
+            batch_quote(quote_targets, rpc, Some(block_number), multicall_address).await?;
+        series.push(QuoteSeriesPoint {
+            block_number,
+            timestamp,
+            results,
+        });
+    }
+    Ok(series)
+}
+
+impl BatchQuoteTarget {
+    /// Quotes the targets in batch at every block of the given [Blocks]
+    pub async fn do_quote_series(
+        &self,
+        rpc_url: &str,
+        blocks: &Blocks,
+        multicall_address: Option<Address>,
+    ) -> Result<Vec<QuoteSeriesPoint>, Error> {
+        quote_series(&self.0, rpc_url, blocks, multicall_address).await
+    }
+}
+
+impl BatchQuoteSpec {
+    /// Given a subgraph url, will fetch the order details from the subgraph and
+    /// then quotes them at every block of the given [Blocks].
+    /// Those orders that are not found from subgraph are excluded from quoting,
+    /// and their place in each point's results is filled with an error
+    pub async fn do_quote_series(
+        &self,
+        subgraph_url: &str,
+        rpc_url: &str,
+        blocks: &Blocks,
+        multicall_address: Option<Address>,
+    ) -> Result<Vec<QuoteSeriesPoint>, Error> {
+        let opts_quote_targets = self
+            .get_batch_quote_target_from_subgraph(subgraph_url)
+            .await?;
+
+        // quote the valid quote targets
+        let quote_targets: Vec<QuoteTarget> = opts_quote_targets
+            .iter()
+            .filter_map(|v| v.clone())
+            .collect();
+        let series = quote_series(&quote_targets, rpc_url, blocks, multicall_address).await?;
+
+        // fill each point's results with quote results and invalid quote
+        // targets following their original order
+        Ok(series
+            .into_iter()
+            .map(|point| {
+                let mut quote_results = VecDeque::from(point.results);
+                QuoteSeriesPoint {
+                    block_number: point.block_number,
+                    timestamp: point.timestamp,
+                    results: opts_quote_targets
+                        .iter()
+                        .map(|v| {
+                            if v.is_some() {
+                                quote_results
+                                    .pop_front()
+                                    .unwrap_or(Err(FailedQuote::NonExistent))
+                            } else {
+                                Err(FailedQuote::NonExistent)
+                            }
+                        })
+                        .collect(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderQuoteValue;
+    use alloy::primitives::{hex::encode_prefixed, U256};
+    use alloy::sol_types::{SolCall, SolValue};
+    use alloy_ethers_typecast::{multicall::IMulticall3::Result as MulticallResult, rpc::Response};
+    use httpmock::{Method::POST, MockServer};
+    use rain_orderbook_bindings::IOrderBookV4::quoteCall;
+    use serde_json::{from_str, json, Value};
+
+    #[tokio::test]
+    async fn test_quote_series() {
+        let rpc_server = MockServer::start_async().await;
+
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_blockNumber"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x64").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_getBlockByNumber"}"#);
+            then.json_body_obj(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "number": "0x5a",
+                    "timestamp": "0x3e8"
+                }
+            }));
+        });
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_getCode"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(&Response::new_success(1, "0x6080").to_json_string().unwrap())
+                    .unwrap(),
+            );
+        });
+        let response_data = vec![MulticallResult {
+            success: true,
+            returnData: quoteCall::abi_encode_returns(&(true, U256::from(1), U256::from(2))).into(),
+        }]
+        .abi_encode();
+        rpc_server.mock(|when, then| {
+            when.method(POST)
+                .path("/rpc")
+                .json_body_partial(r#"{"method":"eth_call"}"#);
+            then.json_body_obj(
+                &from_str::<Value>(
+                    &Response::new_success(1, encode_prefixed(response_data).as_str())
+                        .to_json_string()
+                        .unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+
+        let blocks = Blocks::RangeWithInterval {
+            range: "90..100".parse().unwrap(),
+            interval: 5,
+        };
+        let result = BatchQuoteTarget(vec![QuoteTarget::default()])
+            .do_quote_series(rpc_server.url("/rpc").as_str(), &blocks, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.iter().map(|v| v.block_number).collect::<Vec<_>>(),
+            vec![90, 95, 100]
+        );
+        for point in result {
+            assert_eq!(point.timestamp, 1000);
+            assert_eq!(point.results.len(), 1);
+            assert_eq!(
+                point.results.into_iter().next().unwrap().unwrap(),
+                OrderQuoteValue {
+                    max_output: U256::from(1),
+                    ratio: U256::from(2),
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use crate::{
+    depth::{div_up, one18, quote_token_pair, scale18_to},
+    error::Error,
+    QuoteResult, QuoteTarget,
+};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::sol_types::SolCall;
+use rain_orderbook_bindings::IOrderBookV4::{
+    takeOrders2Call, TakeOrderConfigV3, TakeOrdersConfigV3,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Specifies what a taker wants to buy from an orderbook and at what price
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TakeOrdersRouteArgs {
+    pub orderbook: Address,
+    /// Token the taker sells, ie the input token of the taken orders
+    pub sell_token: Address,
+    /// Token the taker buys, ie the output token of the taken orders
+    pub buy_token: Address,
+    /// Amount of buy token to buy, 18 decimals fixed point
+    pub buy_amount: U256,
+    /// Max average price to pay as sell token per buy token, 18 decimals fixed point
+    pub max_average_ratio: U256,
+    /// How much less than the route's total output the taker accepts, in
+    /// basis points, eg as quotes move before the transaction is mined
+    pub slippage_bps: u64,
+    pub block_number: Option<u64>,
+    pub multicall_address: Option<Address>,
+}
+
+/// A single order taken by a route, amounts are 18 decimals fixed point
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TakeOrdersLeg {
+    pub order_hash: B256,
+    pub owner: Address,
+    pub input_io_index: u8,
+    pub output_io_index: u8,
+    pub ratio: U256,
+    pub max_output: U256,
+    /// Amount of buy token expected from this order
+    pub output: U256,
+    /// Amount of sell token expected to be paid to this order
+    pub input: U256,
+}
+
+/// Orders chosen to fill a buy within a price limit along with the
+/// `takeOrders2` call that takes them, amounts are 18 decimals fixed point
+/// except for those of the config which are in the buy token's decimals, as
+/// orderbook reads them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TakeOrdersRoute {
+    pub legs: Vec<TakeOrdersLeg>,
+    pub total_output: U256,
+    pub total_input: U256,
+    pub average_ratio: U256,
+    /// If the route buys less than the requested amount
+    pub partial: bool,
+    pub config: TakeOrdersConfigV3,
+    pub calldata: Bytes,
+}
+
+impl TakeOrdersRoute {
+    /// Chooses the orders and IO pairs to take from the given quoted targets.
+    /// Quotes are taken cheapest first, each order at most once with its best
+    /// priced IO pair, until the buy amount is filled or taking more would
+    /// push the average price above the limit, in which case the last order
+    /// is partially taken. The taken total can fall short of the route's by
+    /// up to the slippage
+    pub fn from_quotes(
+        quote_targets: &[QuoteTarget],
+        quote_results: Vec<QuoteResult>,
+        buy_amount: U256,
+        max_average_ratio: U256,
+        slippage_bps: u64,
+    ) -> Result<Self, Error> {
+        let mut candidates: Vec<_> = quote_targets
+            .iter()
+            .zip(quote_results)
+            .filter_map(|(target, result)| match result {
+                Ok(quote) if !quote.max_output.is_zero() => Some((target, quote)),
+                _ => None,
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.1.ratio.cmp(&b.1.ratio));
+
+        let one = one18();
+        let mut seen = HashSet::new();
+        let mut legs = vec![];
+        let mut total_output = U256::ZERO;
+        let mut total_input = U256::ZERO;
+        for (target, quote) in candidates {
+            let remaining = buy_amount.saturating_sub(total_output);
+            if remaining.is_zero() {
+                break;
+            }
+            // IO pairs of the same order can draw from the same vaults so
+            // only the best priced pair of each order is taken
+            let order_hash = target.get_order_hash();
+            if !seen.insert(order_hash) {
+                continue;
+            }
+
+            let mut output = remaining.min(quote.max_output);
+            let mut input = div_up(output.saturating_mul(quote.ratio), one);
+            let within_limit = |total_input: U256, total_output: U256| {
+                total_input.saturating_mul(one) <= max_average_ratio.saturating_mul(total_output)
+            };
+            let mut exhausted = false;
+            if !within_limit(total_input + input, total_output + output) {
+                // the ratio is above the limit, take as much as keeps the
+                // average at the limit
+                let headroom = max_average_ratio
+                    .saturating_mul(total_output)
+                    .saturating_sub(total_input.saturating_mul(one));
+                output = output.min(
+                    headroom
+                        / quote
+                            .ratio
+                            .saturating_sub(max_average_ratio)
+                            .max(U256::from(1)),
+                );
+                input = div_up(output.saturating_mul(quote.ratio), one);
+                // rounding the input up can still tip the average over the limit
+                if !within_limit(total_input + input, total_output + output) {
+                    output = U256::ZERO;
+                }
+                exhausted = true;
+            }
+            if output.is_zero() {
+                break;
+            }
+
+            let config = &target.quote_config;
+            legs.push((
+                target,
+                TakeOrdersLeg {
+                    order_hash,
+                    owner: config.order.owner,
+                    input_io_index: config.inputIOIndex.to::<u8>(),
+                    output_io_index: config.outputIOIndex.to::<u8>(),
+                    ratio: quote.ratio,
+                    max_output: quote.max_output,
+                    output,
+                    input,
+                },
+            ));
+            total_output += output;
+            total_input += input;
+            if exhausted {
+                break;
+            }
+        }
+
+        if legs.is_empty() {
+            return Err(Error::NoTakeOrdersRoute);
+        }
+
+        // the taker's input is the orders' output, which orderbook reads in
+        // the buy token's decimals
+        let buy_decimals = legs
+            .first()
+            .and_then(|(target, _)| {
+                let config = &target.quote_config;
+                config
+                    .order
+                    .validOutputs
+                    .get(config.outputIOIndex.to::<usize>())
+            })
+            .map_or(18, |v| v.decimals);
+        let maximum_input = scale18_to(total_output, buy_decimals, false);
+        let minimum_input = maximum_input
+            .saturating_mul(U256::from(10_000u64.saturating_sub(slippage_bps)))
+            / U256::from(10_000);
+
+        let config = TakeOrdersConfigV3 {
+            minimumInput: minimum_input,
+            maximumInput: maximum_input,
+            maximumIORatio: legs
+                .iter()
+                .map(|(_, leg)| leg.ratio)
+                .max()
+                .unwrap_or_default(),
+            orders: legs
+                .iter()
+                .map(|(target, _)| TakeOrderConfigV3 {
+                    order: target.quote_config.order.clone(),
+                    inputIOIndex: target.quote_config.inputIOIndex,
+                    outputIOIndex: target.quote_config.outputIOIndex,
+                    signedContext: target.quote_config.signedContext.clone(),
+                })
+                .collect(),
+            data: Bytes::new(),
+        };
+        let calldata = takeOrders2Call {
+            config: config.clone(),
+        }
+        .abi_encode()
+        .into();
+
+        Ok(Self {
+            legs: legs.into_iter().map(|(_, leg)| leg).collect(),
+            average_ratio: div_up(total_input.saturating_mul(one), total_output),
+            partial: total_output < buy_amount,
+            total_output,
+            total_input,
+            config,
+            calldata,
+        })
+    }
+}
+
+/// Fetches the active orders of the given orderbook subgraph that buy the
+/// sell token for the buy token, quotes them and builds a [TakeOrdersRoute]
+/// that fills the buy amount within the max average price
+pub async fn get_take_orders_route(
+    subgraph_url: &str,
+    rpc_url: &str,
+    args: &TakeOrdersRouteArgs,
+) -> Result<TakeOrdersRoute, Error> {
+    let (quote_targets, quote_results) = quote_token_pair(
+        subgraph_url,
+        rpc_url,
+        args.orderbook,
+        args.sell_token,
+        args.buy_token,
+        args.block_number,
+        args.multicall_address,
+    )
+    .await?;
+
+    TakeOrdersRoute::from_quotes(
+        &quote_targets,
+        quote_results,
+        args.buy_amount,
+        args.max_average_ratio,
+        args.slippage_bps,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::FailedQuote, OrderQuoteValue};
+    use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote, IO};
+
+    fn target(nonce: u8) -> QuoteTarget {
+        QuoteTarget {
+            orderbook: Address::ZERO,
+            quote_config: Quote {
+                order: OrderV3 {
+                    validInputs: vec![IO {
+                        decimals: 18,
+                        ..Default::default()
+                    }],
+                    // a 6 decimals buy token, eg USDC
+                    validOutputs: vec![IO {
+                        decimals: 6,
+                        ..Default::default()
+                    }],
+                    nonce: B256::from(U256::from(nonce)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+
+    fn quote(max_output: u64, ratio: u64) -> QuoteResult {
+        Ok(OrderQuoteValue {
+            max_output: U256::from(max_output) * one18(),
+            ratio: U256::from(ratio) * one18(),
+        })
+    }
+
+    #[test]
+    fn test_take_orders_route_full_fill() {
+        let targets = vec![target(1), target(2), target(3)];
+        let results = vec![quote(5, 3), quote(2, 2), Err(FailedQuote::NonExistent)];
+
+        let result = TakeOrdersRoute::from_quotes(
+            &targets,
+            results,
+            U256::from(4) * one18(),
+            U256::from(3) * one18(),
+            100,
+        )
+        .unwrap();
+
+        assert!(!result.partial);
+        assert_eq!(result.legs.len(), 2);
+        assert_eq!(result.legs[0].order_hash, targets[1].get_order_hash());
+        assert_eq!(result.legs[0].output, U256::from(2) * one18());
+        assert_eq!(result.legs[0].input, U256::from(4) * one18());
+        assert_eq!(result.legs[1].order_hash, targets[0].get_order_hash());
+        assert_eq!(result.legs[1].output, U256::from(2) * one18());
+        assert_eq!(result.legs[1].input, U256::from(6) * one18());
+        assert_eq!(result.total_output, U256::from(4) * one18());
+        assert_eq!(result.total_input, U256::from(10) * one18());
+        assert_eq!(
+            result.average_ratio,
+            U256::from(25) * one18() / U256::from(10)
+        );
+
+        // 4 of the buy token in its 6 decimals, less 1% slippage
+        assert_eq!(result.config.maximumInput, U256::from(4_000_000));
+        assert_eq!(result.config.minimumInput, U256::from(3_960_000));
+        assert_eq!(result.config.maximumIORatio, U256::from(3) * one18());
+        assert_eq!(result.config.orders.len(), 2);
+        assert_eq!(result.config.orders[0].order, targets[1].quote_config.order);
+        assert_eq!(
+            result.calldata,
+            Bytes::from(
+                takeOrders2Call {
+                    config: result.config.clone()
+                }
+                .abi_encode()
+            )
+        );
+    }
+
+    #[test]
+    fn test_take_orders_route_price_limit() {
+        let targets = vec![target(1), target(2)];
+        let results = vec![quote(2, 2), quote(10, 4)];
+
+        // average of 3 allows buying 2 more at 4 on top of 2 at 2
+        let result = TakeOrdersRoute::from_quotes(
+            &targets,
+            results,
+            U256::from(10) * one18(),
+            U256::from(3) * one18(),
+            0,
+        )
+        .unwrap();
+
+        assert!(result.partial);
+        assert_eq!(result.legs.len(), 2);
+        assert_eq!(result.legs[1].output, U256::from(2) * one18());
+        assert_eq!(result.total_output, U256::from(4) * one18());
+        assert_eq!(result.total_input, U256::from(12) * one18());
+        assert_eq!(result.average_ratio, U256::from(3) * one18());
+        assert_eq!(result.config.minimumInput, U256::from(4_000_000));
+        assert_eq!(result.config.maximumInput, U256::from(4_000_000));
+    }
+
+    #[test]
+    fn test_take_orders_route_no_route() {
+        let targets = vec![target(1)];
+        let results = vec![quote(2, 5)];
+
+        let result = TakeOrdersRoute::from_quotes(
+            &targets,
+            results,
+            U256::from(1) * one18(),
+            U256::from(3) * one18(),
+            0,
+        );
+
+        assert!(matches!(result, Err(Error::NoTakeOrdersRoute)));
+    }
+}
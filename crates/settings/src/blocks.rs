@@ -3,7 +3,7 @@ use serde::{
     de::{self, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt;
+use std::{fmt, str::FromStr};
 use thiserror::Error;
 use typeshare::typeshare;
 
@@ -14,6 +14,9 @@ pub enum Block {
     Number(BlockNumber),
     Genesis,
     Latest,
+    /// The given number of blocks before the latest one
+    #[typeshare(skip)]
+    LatestMinus(BlockNumber),
 }
 
 impl Block {
@@ -22,6 +25,7 @@ impl Block {
             Block::Number(n) => *n,
             Block::Genesis => 0,
             Block::Latest => latest_block,
+            Block::LatestMinus(n) => latest_block.saturating_sub(*n),
         }
     }
 }
@@ -34,6 +38,14 @@ pub struct BlockRange {
 }
 
 impl BlockRange {
+    pub fn start(&self) -> &Block {
+        &self.start
+    }
+
+    pub fn end(&self) -> &Block {
+        &self.end
+    }
+
     pub fn validate(&self, latest_block: BlockNumber) -> Result<(), BlockError> {
         let start = self.start.to_block_number(latest_block);
         let end = self.end.to_block_number(latest_block);
@@ -54,7 +66,9 @@ impl Serialize for BlockRange {
 
         match &self.start {
             Block::Genesis => range_string.push_str(""),
-            Block::Latest => range_string.push_str(""),
+            // an empty start is read back as genesis
+            Block::Latest => range_string.push_str("latest"),
+            Block::LatestMinus(n) => range_string.push_str(&format!("latest-{}", n)),
             Block::Number(n) => range_string.push_str(&n.to_string()),
         }
 
@@ -63,6 +77,7 @@ impl Serialize for BlockRange {
         match &self.end {
             Block::Genesis => range_string.push_str(""),
             Block::Latest => range_string.push_str(""),
+            Block::LatestMinus(n) => range_string.push_str(&format!("latest-{}", n)),
             Block::Number(n) => range_string.push_str(&n.to_string()),
         }
 
@@ -110,22 +125,47 @@ impl<'de> Visitor<'de> for BlockRangeVisitor {
     }
 }
 
+impl FromStr for BlockRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_range(s)
+    }
+}
+
 fn parse_range(s: &str) -> Result<BlockRange, String> {
     let parts: Vec<&str> = s.split("..").collect();
     if parts.len() == 2 {
         let start = match parts[0] {
             "" => Block::Genesis,
-            s => Block::Number(s.parse().map_err(|_| "Invalid block number")?),
+            s => parse_block(s)?,
         };
         let end = match parts[1] {
             "" => Block::Latest,
-            s => Block::Number(s.parse().map_err(|_| "Invalid block number")?),
+            s => parse_block(s)?,
         };
         return Ok(BlockRange { start, end });
     }
     Err(format!("Invalid range syntax: {}", s))
 }
 
+/// Parses a block number, `latest` or `latest-N`
+fn parse_block(s: &str) -> Result<Block, String> {
+    if s == "latest" {
+        return Ok(Block::Latest);
+    }
+    if let Some(n) = s.strip_prefix("latest-") {
+        return Ok(Block::LatestMinus(
+            n.parse()
+                .map_err(|_| format!("Invalid block number: {}", s))?,
+        ));
+    }
+    Ok(Block::Number(
+        s.parse()
+            .map_err(|_| format!("Invalid block number: {}", s))?,
+    ))
+}
+
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 pub enum Blocks {
@@ -369,4 +409,79 @@ interval: 5
         let expected = vec![0, 1, 2, 3, 4, 5];
         assert_eq!(blocks.expand_to_block_numbers(100).unwrap(), expected);
     }
+
+    #[test]
+    fn test_block_range_from_str() {
+        let result: BlockRange = "10..20".parse().unwrap();
+        assert_eq!(
+            result,
+            BlockRange {
+                start: Block::Number(10),
+                end: Block::Number(20),
+            }
+        );
+
+        let result: BlockRange = "10..".parse().unwrap();
+        assert_eq!(
+            result,
+            BlockRange {
+                start: Block::Number(10),
+                end: Block::Latest,
+            }
+        );
+
+        assert!("10-20".parse::<BlockRange>().is_err());
+        assert!("a..20".parse::<BlockRange>().is_err());
+        assert!("latest-a..".parse::<BlockRange>().is_err());
+    }
+
+    #[test]
+    fn test_block_range_relative_to_latest() {
+        let result: BlockRange = "latest-7200..".parse().unwrap();
+        assert_eq!(
+            result,
+            BlockRange {
+                start: Block::LatestMinus(7200),
+                end: Block::Latest,
+            }
+        );
+        let expanded_blocks = Blocks::RangeWithInterval {
+            range: result.clone(),
+            interval: 3600,
+        }
+        .expand_to_block_numbers(10000)
+        .unwrap();
+        assert_eq!(expanded_blocks, vec![2800, 6400, 10000]);
+
+        let serialized = serde_yaml::to_string(&result).unwrap();
+        let deserialized: BlockRange = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, result);
+
+        let result: BlockRange = "latest-10..latest-5".parse().unwrap();
+        assert_eq!(
+            result,
+            BlockRange {
+                start: Block::LatestMinus(10),
+                end: Block::LatestMinus(5),
+            }
+        );
+        assert_eq!(Block::LatestMinus(200).to_block_number(100), 0);
+    }
+
+    #[test]
+    fn test_block_range_latest_start_round_trip() {
+        let result: BlockRange = "latest..100".parse().unwrap();
+        assert_eq!(
+            result,
+            BlockRange {
+                start: Block::Latest,
+                end: Block::Number(100),
+            }
+        );
+
+        let serialized = serde_yaml::to_string(&result).unwrap();
+        assert_eq!(serialized.trim(), "latest..100");
+        let deserialized: BlockRange = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, result);
+    }
 }
@@ -89,6 +89,10 @@ mod tests {
             bindings: HashMap::from([(String::from("key"), String::from("value"))]), // Example binding
             runs,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
             deployer: mock_deployer(),
         };
         (name.to_string(), Arc::new(scenario))
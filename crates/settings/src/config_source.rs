@@ -149,6 +149,15 @@ pub struct ScenarioConfigSource {
     pub runs: Option<u64>,
     #[typeshare(skip)]
     pub blocks: Option<Blocks>,
+    #[serde(default)]
+    pub vault_balances: HashMap<String, String>,
+    pub entrypoints: Option<Vec<String>>,
+    #[typeshare(skip)]
+    #[serde(default)]
+    pub fuzz: HashMap<String, FuzzDistribution>,
+    #[typeshare(skip)]
+    #[serde(default)]
+    pub expect: HashMap<String, FuzzExpectation>,
     pub deployer: Option<DeployerRef>,
     pub scenarios: Option<HashMap<String, ScenarioConfigSource>>,
 }
@@ -82,6 +82,10 @@ mod tests {
             deployer: mock_deployer(),
             runs: None,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
         };
         let order = Order {
             inputs: vec![],
@@ -111,6 +115,10 @@ mod tests {
             deployer: mock_deployer(),
             runs: None,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
         };
         let order = Order {
             inputs: vec![],
@@ -0,0 +1,225 @@
+use alloy::primitives::{
+    utils::{format_units, parse_units},
+    U256,
+};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, str::FromStr};
+
+/// A non negative number in 18 decimals fixed point, written as a decimal
+/// number in the config, eg `1.05`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Decimal18(pub U256);
+
+impl FromStr for Decimal18 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().starts_with('-') {
+            return Err(format!(
+                "Invalid decimal number {}: must not be negative",
+                s
+            ));
+        }
+        let value: U256 = parse_units(s.trim(), 18)
+            .map_err(|e| format!("Invalid decimal number {}: {}", s, e))?
+            .into();
+        Ok(Decimal18(value))
+    }
+}
+
+impl fmt::Display for Decimal18 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = format_units(self.0, 18).map_err(|_| fmt::Error)?;
+        // trim the padding of the fraction
+        let value = value.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(value)
+    }
+}
+
+impl Serialize for Decimal18 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal18 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(Decimal18Visitor)
+    }
+}
+
+struct Decimal18Visitor;
+
+impl<'de> Visitor<'de> for Decimal18Visitor {
+    type Value = Decimal18;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a non negative decimal number")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(de::Error::custom)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&v.to_string())
+    }
+}
+
+/// Distribution the values of an elided binding are fuzzed from, values are
+/// in 18 decimals fixed point
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum FuzzDistribution {
+    /// Uniform between min and max inclusive
+    Uniform { min: Decimal18, max: Decimal18 },
+    /// Uniform in the logarithm of the value between min and max, ie every
+    /// order of magnitude is sampled equally often
+    LogUniform { min: Decimal18, max: Decimal18 },
+    /// One of the given values
+    Set(Vec<Decimal18>),
+    /// Random walk that starts at the base and moves by up to the step in
+    /// either direction every run
+    Walk { base: Decimal18, step: Decimal18 },
+}
+
+/// Assertion that must hold for the value at a stack path in every run of
+/// a scenario. Bounds are in 18 decimals fixed point and the bounding paths
+/// are stack paths of the same evaluation
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct FuzzExpectation {
+    /// Stack path of the value, as in the columns of the scenario's runs
+    pub path: String,
+    /// Entrypoint whose evaluation the path is of, calculate-io if not
+    /// specified
+    pub entrypoint: Option<String>,
+    pub min: Option<Decimal18>,
+    pub max: Option<Decimal18>,
+    /// Stack path of a value the value must be less than or equal to
+    pub lte: Option<String>,
+    /// Stack path of a value the value must be greater than or equal to
+    pub gte: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzz_distribution() {
+        let yaml_data = r#"
+price:
+    uniform:
+        min: 0.9
+        max: "1.1"
+amount:
+    log-uniform:
+        min: 1
+        max: 1000000
+side:
+    set: [0, 1]
+drift:
+    walk:
+        base: 1
+        step: 0.01
+"#;
+        let one = U256::from(10).pow(U256::from(18));
+        let result: std::collections::HashMap<String, FuzzDistribution> =
+            serde_yaml::from_str(yaml_data).unwrap();
+        assert_eq!(
+            result["price"],
+            FuzzDistribution::Uniform {
+                min: Decimal18(one * U256::from(9) / U256::from(10)),
+                max: Decimal18(one * U256::from(11) / U256::from(10)),
+            }
+        );
+        assert_eq!(
+            result["amount"],
+            FuzzDistribution::LogUniform {
+                min: Decimal18(one),
+                max: Decimal18(one * U256::from(1_000_000)),
+            }
+        );
+        assert_eq!(
+            result["side"],
+            FuzzDistribution::Set(vec![Decimal18(U256::ZERO), Decimal18(one)])
+        );
+        assert_eq!(
+            result["drift"],
+            FuzzDistribution::Walk {
+                base: Decimal18(one),
+                step: Decimal18(one / U256::from(100)),
+            }
+        );
+
+        let serialized = serde_yaml::to_string(&result["price"]).unwrap();
+        let deserialized: FuzzDistribution = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, result["price"]);
+
+        assert!("-1".parse::<Decimal18>().is_err());
+    }
+
+    #[test]
+    fn test_fuzz_expectation() {
+        let yaml_data = r#"
+io-ratio-in-range:
+    path: 0.0
+    min: 0.9
+    max: 1.1
+max-output-capped:
+    path: 0.1
+    lte: 0.2
+    entrypoint: calculate-io
+"#;
+        let one = U256::from(10).pow(U256::from(18));
+        let result: std::collections::HashMap<String, FuzzExpectation> =
+            serde_yaml::from_str(yaml_data).unwrap();
+        assert_eq!(
+            result["io-ratio-in-range"],
+            FuzzExpectation {
+                path: "0.0".to_string(),
+                entrypoint: None,
+                min: Some(Decimal18(one * U256::from(9) / U256::from(10))),
+                max: Some(Decimal18(one * U256::from(11) / U256::from(10))),
+                lte: None,
+                gte: None,
+            }
+        );
+        assert_eq!(result["max-output-capped"].lte, Some("0.2".to_string()));
+        assert_eq!(
+            result["max-output-capped"].entrypoint,
+            Some("calculate-io".to_string())
+        );
+        assert_eq!(Decimal18(one * U256::from(2)).to_string(), "2");
+    }
+}
@@ -4,6 +4,7 @@ pub mod config;
 pub mod config_source;
 pub mod deployer;
 pub mod deployment;
+pub mod fuzz;
 pub mod merge;
 pub mod network;
 pub mod order;
@@ -17,6 +18,7 @@ pub(crate) use chart::*;
 pub(crate) use config_source::*;
 pub(crate) use deployer::*;
 pub(crate) use deployment::*;
+pub(crate) use fuzz::*;
 pub(crate) use network::*;
 pub(crate) use order::*;
 pub(crate) use orderbook::*;
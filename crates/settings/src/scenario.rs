@@ -1,4 +1,5 @@
 use crate::*;
+use alloy::primitives::U256;
 use blocks::Blocks;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, num::ParseIntError, sync::Arc};
@@ -15,6 +16,24 @@ pub struct Scenario {
     pub runs: Option<u64>,
     #[typeshare(skip)]
     pub blocks: Option<Blocks>,
+    /// Balances of the vaults of the order that is fuzzed, keyed by token
+    /// key and in token decimals. Balances that are not specified are fuzzed
+    #[typeshare(typescript(type = "Record<string, string>"))]
+    #[serde(default)]
+    pub vault_balances: HashMap<String, U256>,
+    /// Entrypoints of the order that are fuzzed, calculate-io only if not
+    /// specified
+    pub entrypoints: Option<Vec<String>>,
+    /// Distributions of the elided bindings that are fuzzed, keyed by
+    /// binding. Elided bindings without a distribution are random 32 bytes
+    #[typeshare(skip)]
+    #[serde(default)]
+    pub fuzz: HashMap<String, FuzzDistribution>,
+    /// Assertions on the runs of the scenario keyed by name, a scenario
+    /// with assertions is testable
+    #[typeshare(skip)]
+    #[serde(default)]
+    pub expect: HashMap<String, FuzzExpectation>,
     #[typeshare(typescript(type = "Deployer"))]
     pub deployer: Arc<Deployer>,
 }
@@ -31,11 +50,23 @@ pub enum ParseScenarioConfigSourceError {
     DeployerNotFound(String),
     #[error("Parent orderbook shadowed by child: {0}")]
     ParentOrderbookShadowedError(String),
+    #[error("Parent vault balance shadowed by child: {0}")]
+    ParentVaultBalanceShadowedError(String),
+    #[error("Failed to parse vault balance of {0}")]
+    VaultBalanceParseError(String),
+    #[error("Parent fuzz distribution shadowed by child: {0}")]
+    ParentFuzzShadowedError(String),
+    #[error("Parent expectation shadowed by child: {0}")]
+    ParentExpectationShadowedError(String),
 }
 
 #[derive(Default)]
 pub struct ScenarioParent {
     bindings: Option<HashMap<String, String>>,
+    vault_balances: Option<HashMap<String, U256>>,
+    entrypoints: Option<Vec<String>>,
+    fuzz: Option<HashMap<String, FuzzDistribution>>,
+    expect: Option<HashMap<String, FuzzExpectation>>,
     deployer: Option<Arc<Deployer>>,
 }
 
@@ -90,12 +121,68 @@ impl ScenarioConfigSource {
             bindings.insert(k.to_string(), v.to_string());
         }
 
+        // Merge vault balances and check for shadowing
+        let mut vault_balances = parent.vault_balances.clone().unwrap_or_default();
+        for (k, v) in &self.vault_balances {
+            let balance = v
+                .parse::<U256>()
+                .map_err(|_| ParseScenarioConfigSourceError::VaultBalanceParseError(k.clone()))?;
+            if let Some(parent_value) = parent.vault_balances.as_ref().and_then(|pb| pb.get(k)) {
+                if *parent_value != balance {
+                    return Err(
+                        ParseScenarioConfigSourceError::ParentVaultBalanceShadowedError(
+                            k.to_string(),
+                        ),
+                    );
+                }
+            }
+            vault_balances.insert(k.to_string(), balance);
+        }
+
+        // Entrypoints are inherited unless specified
+        let entrypoints = self
+            .entrypoints
+            .clone()
+            .or_else(|| parent.entrypoints.clone());
+
+        // Merge fuzz distributions and check for shadowing
+        let mut fuzz = parent.fuzz.clone().unwrap_or_default();
+        for (k, v) in &self.fuzz {
+            if let Some(parent_value) = parent.fuzz.as_ref().and_then(|pf| pf.get(k)) {
+                if parent_value != v {
+                    return Err(ParseScenarioConfigSourceError::ParentFuzzShadowedError(
+                        k.to_string(),
+                    ));
+                }
+            }
+            fuzz.insert(k.to_string(), v.clone());
+        }
+
+        // Merge expectations and check for shadowing
+        let mut expect = parent.expect.clone().unwrap_or_default();
+        for (k, v) in &self.expect {
+            if let Some(parent_value) = parent.expect.as_ref().and_then(|pe| pe.get(k)) {
+                if parent_value != v {
+                    return Err(
+                        ParseScenarioConfigSourceError::ParentExpectationShadowedError(
+                            k.to_string(),
+                        ),
+                    );
+                }
+            }
+            expect.insert(k.to_string(), v.clone());
+        }
+
         // Create and add the parent scenario for this level
         let parent_scenario = Arc::new(Scenario {
             name: name.clone(),
             bindings: bindings.clone(),
             runs: self.runs,
             blocks: self.blocks.clone(),
+            vault_balances: vault_balances.clone(),
+            entrypoints: entrypoints.clone(),
+            fuzz: fuzz.clone(),
+            expect: expect.clone(),
             deployer: deployer_ref.clone(),
         });
 
@@ -109,6 +196,10 @@ impl ScenarioConfigSource {
                     format!("{}.{}", name, child_name),
                     &ScenarioParent {
                         bindings: Some(bindings.clone()),
+                        vault_balances: Some(vault_balances.clone()),
+                        entrypoints: entrypoints.clone(),
+                        fuzz: Some(fuzz.clone()),
+                        expect: Some(expect.clone()),
                         deployer: Some(deployer_ref.clone()),
                     },
                     deployers,
@@ -168,6 +259,10 @@ mod tests {
                 bindings: HashMap::new(), // Assuming no bindings for simplification
                 runs: Some(2),
                 blocks: None,
+                vault_balances: HashMap::new(),
+                entrypoints: None,
+                fuzz: HashMap::new(),
+                expect: HashMap::new(),
                 deployer: None,
                 scenarios: None, // No further nesting
             },
@@ -180,6 +275,10 @@ mod tests {
                 bindings: HashMap::new(), // Assuming no bindings for simplification
                 runs: Some(5),
                 blocks: None,
+                vault_balances: HashMap::new(),
+                entrypoints: None,
+                fuzz: HashMap::new(),
+                expect: HashMap::new(),
                 deployer: None,
                 scenarios: Some(nested_scenario2), // Include nested_scenario2
             },
@@ -193,6 +292,10 @@ mod tests {
                 bindings: HashMap::new(), // Assuming no bindings for simplification
                 runs: Some(10),
                 blocks: None,
+                vault_balances: HashMap::from([("usdc".to_string(), "1000".to_string())]),
+                entrypoints: None,
+                fuzz: HashMap::new(),
+                expect: HashMap::new(),
                 deployer: Some("mainnet".to_string()),
                 scenarios: Some(nested_scenario1), // Include nested_scenario1
             },
@@ -245,6 +348,10 @@ mod tests {
             .get("root_scenario.nested_scenario1.nested_scenario2")
             .unwrap();
         assert_eq!(nested_scenario2.runs, Some(2));
+        assert_eq!(
+            nested_scenario2.vault_balances,
+            HashMap::from([("usdc".to_string(), U256::from(1000))])
+        );
     }
 
     #[test]
@@ -254,6 +361,10 @@ mod tests {
 
         let parent_scenario = ScenarioParent {
             bindings: Some(parent_bindings),
+            vault_balances: None,
+            entrypoints: None,
+            fuzz: None,
+            expect: None,
             deployer: Some(mock_deployer()),
         };
 
@@ -264,6 +375,10 @@ mod tests {
             bindings: child_bindings,
             runs: None,
             blocks: None,
+            vault_balances: HashMap::new(),
+            entrypoints: None,
+            fuzz: HashMap::new(),
+            expect: HashMap::new(),
             deployer: None,
             scenarios: None,
         };
@@ -5,7 +5,8 @@ use crate::types::{
     order_detail,
     order_detail::{
         BatchOrderDetailQuery, BatchOrderDetailQueryVariables, Bytes as OrderId, OrderDetailQuery,
-        OrderDetailQueryVariables, OrderIdList,
+        OrderDetailQueryVariables, OrderIdList, OrdersByTokenPairFilter, OrdersByTokenPairQuery,
+        OrdersByTokenPairQueryVariables, VaultTokenFilter,
     },
     order_take_detail,
     order_take_detail::{OrderTakeDetailQuery, OrderTakeDetailQueryVariables},
@@ -123,6 +124,71 @@ impl OrderbookSubgraphClient {
         Ok(all_pages_merged)
     }
 
+    /// Fetch active orders of the given orderbook that have the given input and
+    /// output tokens, paginated. Addresses are expected as lowercase hex, as
+    /// they are the orderbook and ERC20 entity ids in the subgraph
+    pub async fn orders_by_token_pair(
+        &self,
+        orderbook: String,
+        input_token: String,
+        output_token: String,
+        pagination_args: PaginationArgs,
+    ) -> Result<Vec<order_detail::Order>, OrderbookSubgraphClientError> {
+        let pagination_variables = Self::parse_pagination_args(pagination_args);
+        let data = self
+            .query::<OrdersByTokenPairQuery, OrdersByTokenPairQueryVariables>(
+                OrdersByTokenPairQueryVariables {
+                    filter: OrdersByTokenPairFilter {
+                        orderbook: Some(orderbook),
+                        active: Some(true),
+                        inputs: Some(VaultTokenFilter {
+                            token: Some(input_token),
+                        }),
+                        outputs: Some(VaultTokenFilter {
+                            token: Some(output_token),
+                        }),
+                    },
+                    first: pagination_variables.first,
+                    skip: pagination_variables.skip,
+                },
+            )
+            .await?;
+
+        Ok(data.orders)
+    }
+
+    /// Fetch all pages of orders_by_token_pair query
+    pub async fn orders_by_token_pair_all(
+        &self,
+        orderbook: String,
+        input_token: String,
+        output_token: String,
+    ) -> Result<Vec<order_detail::Order>, OrderbookSubgraphClientError> {
+        let mut all_pages_merged = vec![];
+        let mut page = 1;
+
+        loop {
+            let page_data = self
+                .orders_by_token_pair(
+                    orderbook.clone(),
+                    input_token.clone(),
+                    output_token.clone(),
+                    PaginationArgs {
+                        page,
+                        page_size: ALL_PAGES_QUERY_PAGE_SIZE,
+                    },
+                )
+                .await?;
+            if page_data.is_empty() {
+                break;
+            } else {
+                all_pages_merged.extend(page_data);
+                page += 1
+            }
+        }
+        Ok(all_pages_merged)
+    }
+
     /// Fetch single order take
     pub async fn order_take_detail(
         &self,
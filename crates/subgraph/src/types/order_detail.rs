@@ -39,6 +39,41 @@ pub struct BatchOrderDetailQuery {
     pub orders: Vec<Order>,
 }
 
+#[derive(cynic::QueryVariables, Debug, Clone)]
+#[typeshare]
+pub struct OrdersByTokenPairQueryVariables {
+    pub filter: OrdersByTokenPairFilter,
+    pub first: Option<i32>,
+    pub skip: Option<i32>,
+}
+
+#[derive(cynic::InputObject, Debug, Clone)]
+#[cynic(graphql_type = "Order_filter")]
+#[typeshare]
+pub struct OrdersByTokenPairFilter {
+    pub orderbook: Option<String>,
+    pub active: Option<bool>,
+    #[cynic(rename = "inputs_")]
+    pub inputs: Option<VaultTokenFilter>,
+    #[cynic(rename = "outputs_")]
+    pub outputs: Option<VaultTokenFilter>,
+}
+
+#[derive(cynic::InputObject, Debug, Clone)]
+#[cynic(graphql_type = "Vault_filter")]
+#[typeshare]
+pub struct VaultTokenFilter {
+    pub token: Option<String>,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone, Serialize)]
+#[cynic(graphql_type = "Query", variables = "OrdersByTokenPairQueryVariables")]
+#[typeshare]
+pub struct OrdersByTokenPairQuery {
+    #[arguments(where: $filter, orderDirection: "desc", skip: $skip, first: $first)]
+    pub orders: Vec<Order>,
+}
+
 #[typeshare]
 pub type RainMetaV1 = Bytes;
 
@@ -55,6 +90,13 @@ pub struct Order {
     pub add_events: Vec<AddOrder>,
     pub meta: Option<RainMetaV1>,
     pub timestamp_added: BigInt,
+    pub orderbook: Orderbook,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone, Serialize)]
+#[typeshare]
+pub struct Orderbook {
+    pub id: Bytes,
 }
 
 #[derive(cynic::QueryFragment, Debug, Clone, Serialize)]
@@ -0,0 +1,27 @@
+use insta::assert_snapshot;
+use rain_orderbook_subgraph_client::types::order_detail::{
+    OrdersByTokenPairFilter, OrdersByTokenPairQuery, OrdersByTokenPairQueryVariables,
+    VaultTokenFilter,
+};
+
+#[test]
+fn orders_by_token_pair_query_gql_output() {
+    use cynic::QueryBuilder;
+
+    let request_body = OrdersByTokenPairQuery::build(OrdersByTokenPairQueryVariables {
+        filter: OrdersByTokenPairFilter {
+            orderbook: Some("0x9abc".to_string()),
+            active: Some(true),
+            inputs: Some(VaultTokenFilter {
+                token: Some("0x1234".to_string()),
+            }),
+            outputs: Some(VaultTokenFilter {
+                token: Some("0x5678".to_string()),
+            }),
+        },
+        skip: Some(0),
+        first: Some(10),
+    });
+
+    assert_snapshot!(request_body.query);
+}
@@ -0,0 +1,26 @@
+use crate::error::CommandResult;
+use alloy::primitives::Address;
+use rain_orderbook_common::subgraph::SubgraphArgs;
+use rain_orderbook_quote::{get_depth, DepthLadder};
+
+#[tauri::command]
+pub async fn order_book_depth(
+    subgraph_args: SubgraphArgs,
+    rpc_url: String,
+    orderbook: Address,
+    input_token: Address,
+    output_token: Address,
+    block_number: Option<u64>,
+) -> CommandResult<DepthLadder> {
+    let ladder = get_depth(
+        &subgraph_args.url,
+        &rpc_url,
+        orderbook,
+        input_token,
+        output_token,
+        block_number,
+        None,
+    )
+    .await?;
+    Ok(ladder)
+}
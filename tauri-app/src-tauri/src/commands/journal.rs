@@ -0,0 +1,27 @@
+use crate::error::CommandResult;
+use rain_orderbook_common::journal::{Journal, JournalEntry};
+use std::time::Duration;
+
+#[tauri::command]
+pub async fn journal_list() -> CommandResult<Vec<JournalEntry>> {
+    Ok(Journal::open_default()?.entries()?)
+}
+
+#[tauri::command]
+pub async fn journal_wait(
+    id: String,
+    rpc_url: String,
+    poll_interval_secs: u64,
+    timeout_secs: u64,
+) -> CommandResult<JournalEntry> {
+    let journal = Journal::open_default()?;
+    let entry = journal.get(&id)?;
+    Ok(journal
+        .wait(
+            &entry,
+            &rpc_url,
+            Duration::from_secs(poll_interval_secs),
+            Duration::from_secs(timeout_secs),
+        )
+        .await?)
+}
@@ -2,8 +2,10 @@ pub mod authoring_meta;
 pub mod chain;
 pub mod charts;
 pub mod config;
+pub mod depth;
 pub mod dotrain;
 pub mod dotrain_add_order_lsp;
+pub mod journal;
 pub mod order;
 pub mod order_take;
 pub mod vault;
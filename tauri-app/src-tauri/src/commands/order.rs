@@ -67,10 +67,18 @@ pub async fn order_add(
     deployment: Deployment,
     transaction_args: TransactionArgs,
 ) -> CommandResult<()> {
+    let signer_client = transaction_args
+        .clone()
+        .try_into_signer_client()
+        .await
+        .map_err(|e| {
+            toast_error(app_handle.clone(), e.to_string());
+            e
+        })?;
     let tx_status_notice = TransactionStatusNoticeRwLock::new("Add order".into());
     let add_order_args = AddOrderArgs::new_from_deployment(dotrain, deployment).await?;
     add_order_args
-        .execute(transaction_args, |status| {
+        .execute(transaction_args, &signer_client, |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
         })
         .await
@@ -104,9 +112,17 @@ pub async fn order_remove(
         })?;
     let remove_order_args: RemoveOrderArgs = order.into();
 
+    let signer_client = transaction_args
+        .clone()
+        .try_into_signer_client()
+        .await
+        .map_err(|e| {
+            toast_error(app_handle.clone(), e.to_string());
+            e
+        })?;
     let tx_status_notice = TransactionStatusNoticeRwLock::new("Remove order".into());
     let _ = remove_order_args
-        .execute(transaction_args.clone(), |status| {
+        .execute(transaction_args.clone(), &signer_client, |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
         })
         .await
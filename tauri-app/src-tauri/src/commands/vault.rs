@@ -2,10 +2,12 @@ use crate::error::CommandResult;
 use crate::toast::toast_error;
 use crate::transaction_status::TransactionStatusNoticeRwLock;
 use alloy::primitives::{Bytes, U256};
+use rain_orderbook_app_settings::deployment::Deployment;
 use rain_orderbook_common::{
     csv::TryIntoCsv,
     deposit::DepositArgs,
     subgraph::SubgraphArgs,
+    task::PostTaskArgs,
     transaction::TransactionArgs,
     types::{FlattenError, TokenVaultFlattened, VaultBalanceChangeFlattened},
     withdraw::WithdrawArgs,
@@ -102,16 +104,44 @@ pub async fn vault_detail(
     Ok(vault)
 }
 
+/// Post task of a vault deposit or withdraw, if a post task dotrain and
+/// deployment are given
+fn post_task_args(dotrain: Option<String>, deployment: Option<Deployment>) -> Option<PostTaskArgs> {
+    let (dotrain, deployment) = dotrain.zip(deployment)?;
+    Some(PostTaskArgs::new_from_deployment(dotrain, &deployment))
+}
+
 #[tauri::command]
 pub async fn vault_deposit(
     app_handle: AppHandle,
-    deposit_args: DepositArgs,
+    mut deposit_args: DepositArgs,
     transaction_args: TransactionArgs,
+    post_task_dotrain: Option<String>,
+    post_task_deployment: Option<Deployment>,
 ) -> CommandResult<()> {
+    if let Some(post_task) = post_task_args(post_task_dotrain, post_task_deployment) {
+        deposit_args
+            .try_add_post_task(&post_task, transaction_args.rpc_url.clone())
+            .await
+            .map_err(|e| {
+                toast_error(app_handle.clone(), e.to_string());
+                e
+            })?;
+    }
+
+    let signer_client = transaction_args
+        .clone()
+        .try_into_signer_client()
+        .await
+        .map_err(|e| {
+            toast_error(app_handle.clone(), e.to_string());
+            e
+        })?;
+
     let tx_status_notice =
         TransactionStatusNoticeRwLock::new("Approve ERC20 token transfer".into());
     let _ = deposit_args
-        .execute_approve(transaction_args.clone(), |status| {
+        .execute_approve(transaction_args.clone(), &signer_client, |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
         })
         .await
@@ -121,7 +151,7 @@ pub async fn vault_deposit(
 
     let tx_status_notice = TransactionStatusNoticeRwLock::new("Deposit tokens into vault".into());
     let _ = deposit_args
-        .execute_deposit(transaction_args.clone(), |status| {
+        .execute_deposit(transaction_args.clone(), &signer_client, |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
         })
         .await
@@ -152,8 +182,21 @@ pub async fn vault_deposit_approve_calldata(
 #[tauri::command]
 pub async fn vault_deposit_calldata(
     app_handle: AppHandle,
-    deposit_args: DepositArgs,
+    mut deposit_args: DepositArgs,
+    transaction_args: TransactionArgs,
+    post_task_dotrain: Option<String>,
+    post_task_deployment: Option<Deployment>,
 ) -> CommandResult<Bytes> {
+    if let Some(post_task) = post_task_args(post_task_dotrain, post_task_deployment) {
+        deposit_args
+            .try_add_post_task(&post_task, transaction_args.rpc_url.clone())
+            .await
+            .map_err(|e| {
+                toast_error(app_handle.clone(), e.to_string());
+                e
+            })?;
+    }
+
     let calldata = deposit_args.get_deposit_calldata().await.map_err(|e| {
         toast_error(app_handle.clone(), e.to_string());
         e
@@ -165,12 +208,33 @@ pub async fn vault_deposit_calldata(
 #[tauri::command]
 pub async fn vault_withdraw(
     app_handle: AppHandle,
-    withdraw_args: WithdrawArgs,
+    mut withdraw_args: WithdrawArgs,
     transaction_args: TransactionArgs,
+    post_task_dotrain: Option<String>,
+    post_task_deployment: Option<Deployment>,
 ) -> CommandResult<()> {
+    if let Some(post_task) = post_task_args(post_task_dotrain, post_task_deployment) {
+        withdraw_args
+            .try_add_post_task(&post_task, transaction_args.rpc_url.clone())
+            .await
+            .map_err(|e| {
+                toast_error(app_handle.clone(), e.to_string());
+                e
+            })?;
+    }
+
+    let signer_client = transaction_args
+        .clone()
+        .try_into_signer_client()
+        .await
+        .map_err(|e| {
+            toast_error(app_handle.clone(), e.to_string());
+            e
+        })?;
+
     let tx_status_notice = TransactionStatusNoticeRwLock::new("Withdraw tokens from vault".into());
     let _ = withdraw_args
-        .execute(transaction_args.clone(), |status| {
+        .execute(transaction_args.clone(), &signer_client, |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
         })
         .await
@@ -184,8 +248,21 @@ pub async fn vault_withdraw(
 #[tauri::command]
 pub async fn vault_withdraw_calldata(
     app_handle: AppHandle,
-    withdraw_args: WithdrawArgs,
+    mut withdraw_args: WithdrawArgs,
+    transaction_args: TransactionArgs,
+    post_task_dotrain: Option<String>,
+    post_task_deployment: Option<Deployment>,
 ) -> CommandResult<Bytes> {
+    if let Some(post_task) = post_task_args(post_task_dotrain, post_task_deployment) {
+        withdraw_args
+            .try_add_post_task(&post_task, transaction_args.rpc_url.clone())
+            .await
+            .map_err(|e| {
+                toast_error(app_handle.clone(), e.to_string());
+                e
+            })?;
+    }
+
     let calldata = withdraw_args.get_withdraw_calldata().await.map_err(|e| {
         toast_error(app_handle.clone(), e.to_string());
         e
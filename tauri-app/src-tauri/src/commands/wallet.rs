@@ -1,6 +1,7 @@
 use crate::error::CommandResult;
 use alloy_ethers_typecast::{client::LedgerClient, ethers_address_to_alloy, client::HDPath};
 use alloy::primitives::Address;
+use rain_orderbook_common::transaction::TransactionArgs;
 
 #[tauri::command]
 pub async fn get_address_from_ledger(
@@ -14,3 +15,10 @@ pub async fn get_address_from_ledger(
 
     Ok(ledger_address)
 }
+
+#[tauri::command]
+pub async fn get_signer_address(transaction_args: TransactionArgs) -> CommandResult<Address> {
+    let signer_client = transaction_args.try_into_signer_client().await?;
+
+    Ok(signer_client.address())
+}
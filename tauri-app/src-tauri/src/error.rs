@@ -6,8 +6,10 @@ use rain_orderbook_app_settings::config_source::ConfigSourceError;
 use rain_orderbook_app_settings::merge::MergeError;
 use rain_orderbook_common::dotrain_order::DotrainOrderError;
 use rain_orderbook_common::fuzz::FuzzRunnerError;
+use rain_orderbook_common::journal::JournalError;
 use rain_orderbook_common::remove_order::RemoveOrderArgsError;
-use rain_orderbook_common::transaction::WritableTransactionExecuteError;
+use rain_orderbook_common::task::PostTaskArgsError;
+use rain_orderbook_common::transaction::{TransactionArgsError, WritableTransactionExecuteError};
 use rain_orderbook_common::{
     add_order::AddOrderArgsError, csv::TryIntoCsvError, meta::TryDecodeRainlangSourceError,
     rainlang::ForkParseError, utils::timestamp::FormatTimestampDisplayError,
@@ -70,6 +72,9 @@ pub enum CommandError {
     #[error(transparent)]
     WritableTransactionExecuteError(#[from] WritableTransactionExecuteError),
 
+    #[error(transparent)]
+    TransactionArgsError(#[from] TransactionArgsError),
+
     #[error(transparent)]
     ComposeError(#[from] ComposeError),
 
@@ -81,6 +86,15 @@ pub enum CommandError {
 
     #[error(transparent)]
     FlattenError(#[from] rain_orderbook_common::types::FlattenError),
+
+    #[error(transparent)]
+    QuoteError(#[from] rain_orderbook_quote::error::Error),
+
+    #[error(transparent)]
+    PostTaskArgsError(#[from] PostTaskArgsError),
+
+    #[error(transparent)]
+    JournalError(#[from] JournalError),
 }
 
 impl Serialize for CommandError {
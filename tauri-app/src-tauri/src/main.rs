@@ -10,8 +10,10 @@ use commands::authoring_meta::get_authoring_meta_v2_for_scenarios;
 use commands::chain::{get_block_number, get_chainid};
 use commands::charts::make_charts;
 use commands::config::{convert_configstring_to_config, merge_configstrings, parse_configstring};
+use commands::depth::order_book_depth;
 use commands::dotrain::parse_dotrain;
 use commands::dotrain_add_order_lsp::{call_lsp_completion, call_lsp_hover, call_lsp_problems};
+use commands::journal::{journal_list, journal_wait};
 use commands::order::{
     compose_from_scenario, order_add, order_add_calldata, order_detail, order_remove,
     order_remove_calldata, orders_list, orders_list_write_csv,
@@ -22,7 +24,7 @@ use commands::vault::{
     vault_deposit_approve_calldata, vault_deposit_calldata, vault_detail, vault_withdraw,
     vault_withdraw_calldata, vaults_list, vaults_list_write_csv,
 };
-use commands::wallet::get_address_from_ledger;
+use commands::wallet::{get_address_from_ledger, get_signer_address};
 
 fn main() {
     if std::env::consts::OS == "linux" {
@@ -54,6 +56,7 @@ fn run_tauri_app() {
             order_takes_list,
             order_takes_list_write_csv,
             get_address_from_ledger,
+            get_signer_address,
             get_chainid,
             get_block_number,
             parse_dotrain,
@@ -71,6 +74,9 @@ fn run_tauri_app() {
             vault_withdraw_calldata,
             get_authoring_meta_v2_for_scenarios,
             compose_from_scenario,
+            order_book_depth,
+            journal_list,
+            journal_wait,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
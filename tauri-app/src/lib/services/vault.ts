@@ -58,6 +58,12 @@ export async function vaultDepositCalldata(vaultId: bigint, token: string, amoun
       token,
       amount: amount.toString(),
     },
+    transactionArgs: {
+      rpc_url: get(rpcUrl),
+      orderbook_address: get(orderbookAddress),
+      derivation_index: get(ledgerWalletDerivationIndex),
+      chain_id: get(chainId),
+    },
   });
 }
 